@@ -198,6 +198,10 @@ pub struct AuctionConditions {
     pub on_fixed_sequence_number: Option<u32>,
     pub on_minimum_participants: Option<u32>,
     pub on_maximum_participants: Option<u32>,
+    pub soft_close: SoftClose,
+    pub min_increment: MinIncrement,
+    pub reserve_price: Option<i128>,
+    pub buy_now_price: Option<i128>,
 }
 
 #[contracttype]
@@ -206,6 +210,7 @@ pub enum AuctionType {
     Regular,
     Reverse,
     Dutch(DutchAuctionData),
+    Sealed(SealedAuctionData),
 }
 
 #[contracttype]
@@ -213,3 +218,40 @@ pub enum AuctionType {
 pub struct DutchAuctionData {
     pub floor_price: i128,
 }
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SealedAuctionData {
+    pub reveal_end_time: u64,
+    pub pricing: SealedPricing,
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SealedPricing {
+    FirstPrice,
+    SecondPrice,
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SoftClose {
+    Disabled,
+    Enabled(SoftCloseRule),
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SoftCloseRule {
+    pub window_seconds: u64,
+    pub extension_seconds: u64,
+    pub max_end_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum MinIncrement {
+    None,
+    Absolute(i128),
+    BasisPoints(u32),
+}
@@ -1,9 +1,16 @@
-use crate::event::{AuctionCanceled, AuctionCompleted, AuctionCreated, NewBidPlaced};
+use crate::event::{
+    AuctionCanceled, AuctionCompleted, AuctionCreated, AuctionExtended, AuctionUnsold,
+    BidCommitted, BidRevealed, ConditionsUpdated, EscrowLocked, EscrowReleased, NewBidPlaced,
+};
 use crate::traits::AuctionTrait;
-use crate::{bid::record_bid, errors::AuctionError};
+use crate::{
+    bid::{apply_soft_close, record_bid},
+    errors::{AuctionError, ConditionError},
+};
 use crate::{distribution, types::*};
 use soroban_sdk::{
-    contract, contractimpl, contracttype, panic_with_error, Address, Env, IntoVal, Symbol, Val,
+    contract, contractimpl, contracttype, panic_with_error, Address, BytesN, Env, IntoVal, Symbol,
+    Val, Vec,
 };
 
 #[contract]
@@ -13,13 +20,136 @@ pub struct AuctionContract;
 #[contracttype]
 #[derive(Clone)]
 enum DataKey {
-    TotalAuctions,        // Key for storing total number of auctions
-    Auction(u32),         // Key for storing a specific Auction by its internal ID
+    Admin,                         // Key for storing the admin allowed to update auction conditions
+    TotalAuctions,                 // Key for storing total number of auctions
+    Auction(u32),                  // Key for storing a specific Auction by its internal ID
     HasBid(Address, u32), // Key for storing if a user has made a bid for a specific auction
+    Escrow(u32),          // Key for storing the escrowed winning price of a reverse auction
+    SealedBid(u32, Address), // Key for storing a bidder's sealed bid for a specific auction
+    Bid(u32, u32),        // Key for storing a bid in an auction's history by its position
+    BidHistoryLen(u32),   // Key for storing the number of bids in an auction's history
+    Participant(u32, u32), // Key for storing a bidder of an auction by position
+    ParticipantsLen(u32), // Key for storing the number of bidders of an auction
+    ParticipantsPruned(u32), // Key for storing how many bidders no longer list an ended auction as active
+    IndexEntry(AuctionIndex, u32), // Key for storing the auction id in a slot of a list
+    IndexSlot(AuctionIndex, u32), // Key for storing the slot of an auction id in a list
+    IndexLen(AuctionIndex),  // Key for storing the number of slots used by a list
+}
+
+/// Lists of auction ids, stored one slot per entry so no list is loaded whole.
+///
+/// Removing an auction moves the list's last entry into its slot, keeping the slots dense.
+#[contracttype]
+#[derive(Clone)]
+enum AuctionIndex {
+    ActiveBids(Address),   // Auctions a user has bid on that are still active
+    Won(Address),          // Auctions a user has won
+    Owner(Address),        // Auctions a user has created
+    Status(AuctionStatus), // Auctions with a given status
 }
 
+/// Largest number of entries returned by a paginated query.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Largest number of bidders whose active lists are cleaned up by one `prune_active_bids` call.
+///
+/// Each removal rewrites up to five entries, which keeps a call within the transaction's write limit.
+pub const MAX_PRUNE_BATCH: u32 = 8;
+
+/// Time the buyer of a reverse auction has to release the escrow before the seller can claim it.
+pub const ESCROW_RELEASE_PERIOD: u64 = 14 * 24 * 60 * 60;
+
+// Persistent entries are kept alive for about 30 days after each write.
+const DAY_IN_LEDGERS: u32 = 17280;
+const BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const LIFETIME_THRESHOLD: u32 = BUMP_AMOUNT - DAY_IN_LEDGERS;
+
 #[contractimpl]
 impl AuctionTrait for AuctionContract {
+    /// Sets the admin allowed to update auction conditions.
+    fn initialize(env: Env, admin: Address) {
+        if Self::_get_admin(&env).is_some() {
+            panic_with_error!(&env, AuctionError::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+
+        Self::_save_admin(&env, &admin);
+    }
+
+    /// Hands the admin role to a new address.
+    fn set_admin(env: Env, new_admin: Address) {
+        let admin = Self::_get_admin(&env);
+
+        if admin.is_none() {
+            panic_with_error!(&env, AuctionError::NotInitialized);
+        }
+
+        admin.unwrap().require_auth(); // Only the current admin can hand over the role
+
+        Self::_save_admin(&env, &new_admin);
+    }
+
+    fn get_admin(env: Env) -> Option<Address> {
+        Self::_get_admin(&env)
+    }
+
+    /// Replaces the conditions of an active auction.
+    fn update_conditions(env: Env, auction_id: u32, conditions: AuctionConditions) {
+        let admin = Self::_get_admin(&env);
+
+        if admin.is_none() {
+            panic_with_error!(&env, AuctionError::NotInitialized);
+        }
+
+        let admin = admin.unwrap();
+
+        admin.require_auth(); // Only the admin can update conditions
+
+        let auction_data = Self::_get_auction(&env, auction_id);
+
+        if auction_data.is_none() {
+            panic_with_error!(&env, AuctionError::AuctionNotFound);
+        }
+
+        let mut auction_data = auction_data.unwrap();
+
+        // Reject if the auction was canceled or already completed
+        if auction_data.is_canceled() {
+            panic_with_error!(&env, AuctionError::AuctionCanceled);
+        }
+
+        if auction_data.is_completed() {
+            panic_with_error!(&env, AuctionError::AuctionCompleted);
+        }
+
+        // Validate the new conditions (e.g., end time, floor price)
+        conditions.validate_conditions(&env);
+
+        auction_data
+            .item_metadata
+            .validate_item_asset(&env, &conditions.auction_type);
+
+        // Refuse changes that would invalidate bids or deposits already placed
+        if !auction_data.can_update_conditions(&conditions) {
+            panic_with_error!(&env, AuctionError::ConditionsUpdateNotAllowed);
+        }
+
+        auction_data.auction_conditions = conditions;
+
+        // Save updated auction state
+        Self::_save_auction(&env, auction_id, &auction_data);
+
+        env.events().publish(
+            (Symbol::new(&env, "conditions_updated"), auction_id),
+            ConditionsUpdated {
+                auction_id,
+                updated_by: admin,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+
     /// Creates a new auction with provided metadata and auction conditions.
     fn create_auction(
         env: Env,
@@ -36,12 +166,27 @@ impl AuctionTrait for AuctionContract {
         // Validate custom auction conditions (e.g., end time, floor price)
         auction_conditions.validate_conditions(&env);
 
+        item_metadata.validate_item_asset(&env, &auction_conditions.auction_type);
+
         // Fetch the current number of auctions
         let total_auctions = Self::_get_total_auctions(&env);
         let auction_id = total_auctions + 1;
 
         let current_time = env.ledger().timestamp();
 
+        // Take custody of the auctioned asset until settlement
+        distribution::lock_asset(&env, &item_metadata.asset, &owner);
+
+        // Reverse auctions lock the buyer's maximum budget up front
+        if let AuctionType::Reverse = auction_conditions.auction_type {
+            distribution::transfer_to_contract(
+                &env,
+                &token,
+                &owner,
+                &auction_conditions.starting_price,
+            );
+        }
+
         // Initialize the auction with default state
         let auction = Auction {
             id: auction_id,
@@ -56,13 +201,22 @@ impl AuctionTrait for AuctionContract {
             last_bid_time: 0,
             token,
             auction_status: AuctionStatus::Active,
+            no_of_extensions: 0,
         };
 
         // Persist auction data to storage
         Self::_save_auction(&env, auction_id, &auction);
 
         // Update total auctions count
-        Self::_save_total_auctions(&env, &total_auctions);
+        Self::_save_total_auctions(&env, &auction_id);
+
+        // List the auction under its owner and status
+        Self::_append_index(&env, AuctionIndex::Owner(owner.clone()), auction_id);
+        Self::_append_index(
+            &env,
+            AuctionIndex::Status(AuctionStatus::Active),
+            auction_id,
+        );
 
         // Emit Auction Created event
         env.events().publish(
@@ -99,6 +253,10 @@ impl AuctionTrait for AuctionContract {
 
         // Perform the bid and update state
         record_bid(&env, &mut auction_data, bidder.clone(), bid_amount);
+        Self::_record_bid_history(&env, auction_id, &bidder, bid_amount);
+
+        // Extend the auction if the bid landed close to the end
+        let extended = apply_soft_close(&env, &mut auction_data);
 
         // Check if bidder is a new participant
         if !Self::_has_bid(&env, &bidder, &auction_id) {
@@ -118,6 +276,23 @@ impl AuctionTrait for AuctionContract {
                 bid_amount,
             },
         );
+
+        // Emit Auction Extended event
+        if extended {
+            env.events().publish(
+                (Symbol::new(&env, "auction_extended"), auction_id),
+                AuctionExtended {
+                    auction_id,
+                    end_time: auction_data.auction_conditions.end_time,
+                    no_of_extensions: auction_data.no_of_extensions,
+                },
+            );
+        }
+
+        // A bid at the buy-now price completes the auction immediately
+        if auction_data.is_buy_now(bid_amount) {
+            Self::_complete_auction(&env, &mut auction_data);
+        }
     }
 
     /// Cancels an active auction if it's still cancelable.
@@ -146,8 +321,21 @@ impl AuctionTrait for AuctionContract {
             panic_with_error!(&env, AuctionError::CannotCancelAuction);
         }
 
+        // Return the budget deposited for a reverse auction
+        if !auction_data.holds_bid_funds() {
+            distribution::transfer_from_contract(
+                &env,
+                &auction_data.token,
+                &auction_data.owner,
+                &auction_data.auction_conditions.starting_price,
+            );
+        }
+
+        // Return the auctioned asset to the seller
+        distribution::release_asset(&env, &auction_data.item_metadata.asset, &auction_data.owner);
+
         // Mark the auction as canceled and save
-        auction_data.auction_status = AuctionStatus::Cancelled;
+        Self::_set_status(&env, &mut auction_data, AuctionStatus::Cancelled);
 
         Self::_save_auction(&env, auction_id, &auction_data);
 
@@ -189,44 +377,243 @@ impl AuctionTrait for AuctionContract {
         // Check if any of the custom end conditions are met
         auction_data.check_can_end(&env);
 
-        // If there is a current bidder, transfer the item and update owner
-        if let Some(curr_bidder) = &auction_data.curr_bidder {
-            let bid_amount = auction_data.curr_bid_amount.expect("Missing bid amount");
+        Self::_complete_auction(&env, &mut auction_data);
+    }
 
-            let prev_owner = auction_data.owner;
-            auction_data.owner = curr_bidder.clone();
+    fn get_auction(env: Env, auction_id: u32) -> Option<Auction> {
+        Self::_get_auction(&env, auction_id)
+    }
 
-            // Transfer bid amount from contract to seller
-            distribution::transfer_from_contract(
-                &env,
-                &auction_data.token,
-                &prev_owner,
-                &bid_amount,
-            );
+    /// Releases the escrowed winning price of a reverse auction to the winning seller.
+    fn release_escrow(env: Env, auction_id: u32) {
+        let escrow = Self::_get_escrow(&env, auction_id);
+
+        if escrow.is_none() {
+            panic_with_error!(&env, AuctionError::EscrowNotFound);
         }
 
-        // Update auction status to completed
-        auction_data.auction_status = AuctionStatus::Completed;
+        let escrow = escrow.unwrap();
+
+        escrow.buyer.require_auth(); // Only the buyer can release the payment
+
+        Self::_release_escrow(&env, escrow);
+    }
+
+    /// Pays an unreleased escrow to the winning seller once the release deadline has passed.
+    fn claim_escrow(env: Env, auction_id: u32) {
+        let escrow = Self::_get_escrow(&env, auction_id);
+
+        if escrow.is_none() {
+            panic_with_error!(&env, AuctionError::EscrowNotFound);
+        }
+
+        let escrow = escrow.unwrap();
+
+        escrow.seller.require_auth(); // Only the winning seller can claim the payment
+
+        if env.ledger().timestamp() <= escrow.release_deadline {
+            panic_with_error!(&env, AuctionError::EscrowReleaseDeadlineNotReached);
+        }
+
+        Self::_release_escrow(&env, escrow);
+    }
+
+    fn get_escrow(env: Env, auction_id: u32) -> Option<RfqEscrow> {
+        Self::_get_escrow(&env, auction_id)
+    }
+
+    /// Commits a sealed bid to a sealed-bid auction and deposits its funds.
+    fn commit_bid(
+        env: Env,
+        auction_id: u32,
+        bidder: Address,
+        commitment: BytesN<32>,
+        deposit: i128,
+    ) {
+        bidder.require_auth(); // Ensure bidder is authenticated
+
+        let mut auction_data = Self::_get_sealed_auction(&env, auction_id).0;
+
+        // Check if user can commit
+        auction_data.check_can_commit(&env, &deposit);
+
+        // Only one commitment per bidder
+        if Self::_get_sealed_bid(&env, auction_id, &bidder).is_some() {
+            panic_with_error!(&env, ConditionError::BidAlreadyCommitted);
+        }
+
+        // Transfer the deposit to contract
+        distribution::transfer_to_contract(&env, &auction_data.token, &bidder, &deposit);
+
+        let sealed_bid = SealedBid {
+            bidder: bidder.clone(),
+            commitment,
+            deposit,
+            revealed_amount: None,
+        };
+        Self::_save_sealed_bid(&env, auction_id, &sealed_bid);
+
+        auction_data.no_of_bids += 1;
+        auction_data.no_of_participants += 1;
+        Self::_register_user_bid(&env, &bidder, &auction_id);
 
         // Save updated auction state
         Self::_save_auction(&env, auction_id, &auction_data);
 
+        // Emit Bid Committed event; the amount stays hidden until revealed
         env.events().publish(
-            (
-                Symbol::new(&env, "auction_completed"),
-                auction_data.curr_bidder.clone(),
-            ),
-            AuctionCompleted {
+            (Symbol::new(&env, "bid_committed"), bidder.clone()),
+            BidCommitted {
                 auction_id,
-                winner: auction_data.curr_bidder,
-                final_price: auction_data.curr_bid_amount,
-                timestamp: env.ledger().timestamp(),
+                bidder,
+                deposit,
             },
         );
     }
 
-    fn get_auction(env: Env, auction_id: u32) -> Option<Auction> {
-        Self::_get_auction(&env, auction_id)
+    /// Reveals a sealed bid, checking it against its commitment.
+    fn reveal_bid(env: Env, auction_id: u32, bidder: Address, bid_amount: i128, salt: BytesN<32>) {
+        bidder.require_auth(); // Ensure bidder is authenticated
+
+        let (mut auction_data, sealed_data) = Self::_get_sealed_auction(&env, auction_id);
+
+        // Check if the reveal phase is open
+        auction_data.check_can_reveal(&env, &sealed_data);
+
+        let sealed_bid = Self::_get_sealed_bid(&env, auction_id, &bidder);
+
+        if sealed_bid.is_none() {
+            panic_with_error!(&env, AuctionError::SealedBidNotFound);
+        }
+
+        let mut sealed_bid = sealed_bid.unwrap();
+
+        sealed_bid.check_reveal(
+            &env,
+            auction_id,
+            bid_amount,
+            &salt,
+            auction_data.auction_conditions.starting_price,
+        );
+
+        sealed_bid.revealed_amount = Some(bid_amount);
+        Self::_save_sealed_bid(&env, auction_id, &sealed_bid);
+        Self::_record_bid_history(&env, auction_id, &bidder, bid_amount);
+
+        // Track the highest revealed bid; ties go to the earliest reveal
+        if auction_data
+            .curr_bid_amount
+            .is_none_or(|curr_bid_amount| bid_amount > curr_bid_amount)
+        {
+            auction_data.curr_bidder = Some(bidder.clone());
+            auction_data.curr_bid_amount = Some(bid_amount);
+            Self::_save_auction(&env, auction_id, &auction_data);
+        }
+
+        // Emit Bid Revealed event
+        env.events().publish(
+            (Symbol::new(&env, "bid_revealed"), bidder.clone()),
+            BidRevealed {
+                auction_id,
+                bidder,
+                bid_amount,
+            },
+        );
+    }
+
+    fn get_sealed_bid(env: Env, auction_id: u32, bidder: Address) -> Option<SealedBid> {
+        Self::_get_sealed_bid(&env, auction_id, &bidder)
+    }
+
+    /// Returns a page of an auction's bid history, oldest first.
+    fn get_bids(env: Env, auction_id: u32, start: u32, limit: u32) -> Vec<Bid> {
+        let len = env
+            .storage()
+            .persistent()
+            .get::<Val, u32>(&DataKey::BidHistoryLen(auction_id).into_val(&env))
+            .unwrap_or(0);
+
+        let mut bids = Vec::new(&env);
+        let first = start.saturating_add(1);
+        let last = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+        for id in first..=last {
+            if let Some(bid) = env
+                .storage()
+                .persistent()
+                .get::<Val, Bid>(&DataKey::Bid(auction_id, id).into_val(&env))
+            {
+                bids.push_back(bid);
+            }
+        }
+        bids
+    }
+
+    /// Returns a page of a user's active list, skipping auctions that ended but were not pruned yet.
+    fn get_active_bids(env: Env, bidder: Address, start: u32, limit: u32) -> Vec<Auction> {
+        let mut auctions = Vec::new(&env);
+        for auction in
+            Self::_page_auctions(&env, AuctionIndex::ActiveBids(bidder), start, limit).iter()
+        {
+            if auction.auction_status == AuctionStatus::Active {
+                auctions.push_back(auction);
+            }
+        }
+        auctions
+    }
+
+    /// Removes an auction that is no longer active from the active lists of its next bidders.
+    fn prune_active_bids(env: Env, auction_id: u32, limit: u32) -> u32 {
+        let auction_data = Self::_get_auction(&env, auction_id);
+
+        if auction_data.is_none() {
+            panic_with_error!(&env, AuctionError::AuctionNotFound);
+        }
+
+        if auction_data.unwrap().auction_status == AuctionStatus::Active {
+            panic_with_error!(&env, AuctionError::AuctionStillActive);
+        }
+
+        let pruned_key = DataKey::ParticipantsPruned(auction_id).into_val(&env);
+        let pruned = env
+            .storage()
+            .persistent()
+            .get::<Val, u32>(&pruned_key)
+            .unwrap_or(0);
+
+        let last = pruned
+            .saturating_add(limit.min(MAX_PRUNE_BATCH))
+            .min(Self::_participants_len(&env, auction_id));
+        for position in (pruned + 1)..=last {
+            if let Some(bidder) = Self::_get_participant(&env, auction_id, position) {
+                Self::_remove_index(&env, AuctionIndex::ActiveBids(bidder), auction_id);
+            }
+        }
+
+        if last > pruned {
+            env.storage()
+                .persistent()
+                .set::<Val, u32>(&pruned_key, &last);
+            Self::_extend_ttl(&env, &pruned_key);
+        }
+
+        Self::_participants_len(&env, auction_id) - last
+    }
+
+    fn get_won_auctions(env: Env, winner: Address, start: u32, limit: u32) -> Vec<Auction> {
+        Self::_page_auctions(&env, AuctionIndex::Won(winner), start, limit)
+    }
+
+    fn get_auctions_by_status(
+        env: Env,
+        status: AuctionStatus,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Auction> {
+        Self::_page_auctions(&env, AuctionIndex::Status(status), start, limit)
+    }
+
+    fn get_auctions_by_owner(env: Env, owner: Address, start: u32, limit: u32) -> Vec<Auction> {
+        Self::_page_auctions(&env, AuctionIndex::Owner(owner), start, limit)
     }
 }
 
@@ -234,15 +621,31 @@ impl AuctionContract {
     /// Internal helper to fetch a auction from storage.
     fn _get_auction(env: &Env, auction_id: u32) -> Option<Auction> {
         env.storage()
-            .instance()
+            .persistent()
             .get::<Val, Auction>(&DataKey::Auction(auction_id).into_val(env))
     }
 
     /// Internal helper to save a auction to storage.
     fn _save_auction(env: &Env, auction_id: u32, auction: &Auction) {
+        let key = DataKey::Auction(auction_id).into_val(env);
+        env.storage()
+            .persistent()
+            .set::<Val, Auction>(&key, auction);
+        Self::_extend_ttl(env, &key);
+    }
+
+    /// Internal helper to fetch the admin from storage.
+    fn _get_admin(env: &Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get::<Val, Address>(&DataKey::Admin.into_val(env))
+    }
+
+    /// Internal helper to save the admin to storage.
+    fn _save_admin(env: &Env, admin: &Address) {
         env.storage()
             .instance()
-            .set::<Val, Auction>(&DataKey::Auction(auction_id).into_val(env), auction);
+            .set::<Val, Address>(&DataKey::Admin.into_val(env), admin);
     }
 
     /// Internal helper to get total number of auctions
@@ -263,22 +666,499 @@ impl AuctionContract {
     /// Internal helper to check if auction_id exists
     fn _check_auction_id(env: &Env, auction_id: u32) -> bool {
         env.storage()
-            .instance()
+            .persistent()
             .has::<Val>(&DataKey::Auction(auction_id).into_val(env))
     }
 
+    /// Internal helper to fetch the escrow of a reverse auction from storage.
+    fn _get_escrow(env: &Env, auction_id: u32) -> Option<RfqEscrow> {
+        env.storage()
+            .persistent()
+            .get::<Val, RfqEscrow>(&DataKey::Escrow(auction_id).into_val(env))
+    }
+
+    /// Internal helper to pay an escrow to the winning seller and close it.
+    fn _release_escrow(env: &Env, mut escrow: RfqEscrow) {
+        if escrow.released {
+            panic_with_error!(env, AuctionError::EscrowAlreadyReleased);
+        }
+
+        // Pay the winning seller and close the escrow
+        distribution::transfer_from_contract(env, &escrow.token, &escrow.seller, &escrow.amount);
+        escrow.released = true;
+
+        Self::_save_escrow(env, escrow.auction_id, &escrow);
+
+        env.events().publish(
+            (Symbol::new(env, "escrow_released"), escrow.seller.clone()),
+            EscrowReleased {
+                auction_id: escrow.auction_id,
+                seller: escrow.seller,
+                amount: escrow.amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    /// Internal helper to save the escrow of a reverse auction to storage.
+    fn _save_escrow(env: &Env, auction_id: u32, escrow: &RfqEscrow) {
+        let key = DataKey::Escrow(auction_id).into_val(env);
+        env.storage()
+            .persistent()
+            .set::<Val, RfqEscrow>(&key, escrow);
+        Self::_extend_ttl(env, &key);
+    }
+
+    /// Internal helper to settle an auction's funds and mark it completed.
+    fn _complete_auction(env: &Env, auction_data: &mut Auction) {
+        if let AuctionType::Sealed(sealed_data) =
+            auction_data.auction_conditions.auction_type.clone()
+        {
+            // Sealed-bid auction: charge the winner and settle every deposit
+            Self::_settle_sealed_auction(env, auction_data, &sealed_data);
+        } else if !auction_data.holds_bid_funds() {
+            // Reverse auction: escrow the winning price and refund the rest of the budget
+            Self::_settle_reverse_auction(env, auction_data);
+        } else if !auction_data.meets_reserve() {
+            // Reserve price not met: return all funds and leave the item unsold
+            Self::_settle_unsold_auction(env, auction_data);
+            return;
+        } else if let Some(curr_bidder) = &auction_data.curr_bidder {
+            // If there is a current bidder, transfer the item and update owner
+            let bid_amount = auction_data.curr_bid_amount.expect("Missing bid amount");
+
+            let prev_owner = auction_data.owner.clone();
+            auction_data.owner = curr_bidder.clone();
+
+            // Transfer bid amount from contract to seller
+            distribution::transfer_from_contract(
+                env,
+                &auction_data.token,
+                &prev_owner,
+                &bid_amount,
+            );
+        }
+
+        // Deliver the asset to the new owner, or back to the seller without a winner
+        distribution::release_asset(env, &auction_data.item_metadata.asset, &auction_data.owner);
+
+        // Update auction status to completed
+        Self::_set_status(env, auction_data, AuctionStatus::Completed);
+
+        if let Some(winner) = &auction_data.curr_bidder {
+            Self::_append_index(env, AuctionIndex::Won(winner.clone()), auction_data.id);
+        }
+
+        // Save updated auction state
+        Self::_save_auction(env, auction_data.id, auction_data);
+
+        env.events().publish(
+            (
+                Symbol::new(env, "auction_completed"),
+                auction_data.curr_bidder.clone(),
+            ),
+            AuctionCompleted {
+                auction_id: auction_data.id,
+                winner: auction_data.curr_bidder.clone(),
+                final_price: auction_data.curr_bid_amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    /// Internal helper to close an auction whose reserve price was not met.
+    fn _settle_unsold_auction(env: &Env, auction_data: &mut Auction) {
+        // Refund the highest bidder
+        if let Some(curr_bidder) = &auction_data.curr_bidder {
+            let bid_amount = auction_data.curr_bid_amount.expect("Missing bid amount");
+
+            distribution::transfer_from_contract(
+                env,
+                &auction_data.token,
+                curr_bidder,
+                &bid_amount,
+            );
+        }
+
+        // Return the asset to the seller
+        distribution::release_asset(env, &auction_data.item_metadata.asset, &auction_data.owner);
+
+        // Update auction status to unsold
+        Self::_set_status(env, auction_data, AuctionStatus::Unsold);
+
+        // Save updated auction state
+        Self::_save_auction(env, auction_data.id, auction_data);
+
+        env.events().publish(
+            (
+                Symbol::new(env, "auction_unsold"),
+                auction_data.owner.clone(),
+            ),
+            AuctionUnsold {
+                auction_id: auction_data.id,
+                highest_bid: auction_data.curr_bid_amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    /// Internal helper to settle a reverse auction's budget.
+    ///
+    /// The winning price stays in the contract as an escrow for the winning
+    /// seller and the rest of the budget goes back to the buyer. Without bids
+    /// the whole budget is refunded.
+    fn _settle_reverse_auction(env: &Env, auction_data: &Auction) {
+        let budget = auction_data.auction_conditions.starting_price;
+
+        let (seller, winning_price) =
+            match (&auction_data.curr_bidder, auction_data.curr_bid_amount) {
+                (Some(seller), Some(winning_price)) => (seller.clone(), winning_price),
+                _ => {
+                    distribution::transfer_from_contract(
+                        env,
+                        &auction_data.token,
+                        &auction_data.owner,
+                        &budget,
+                    );
+                    return;
+                }
+            };
+
+        let escrow = RfqEscrow {
+            auction_id: auction_data.id,
+            buyer: auction_data.owner.clone(),
+            seller: seller.clone(),
+            token: auction_data.token.clone(),
+            amount: winning_price,
+            released: false,
+            release_deadline: env.ledger().timestamp() + ESCROW_RELEASE_PERIOD,
+        };
+        Self::_save_escrow(env, auction_data.id, &escrow);
+
+        // Refund the unused part of the budget
+        let refunded = budget - winning_price;
+        if refunded > 0 {
+            distribution::transfer_from_contract(
+                env,
+                &auction_data.token,
+                &auction_data.owner,
+                &refunded,
+            );
+        }
+
+        env.events().publish(
+            (Symbol::new(env, "escrow_locked"), seller.clone()),
+            EscrowLocked {
+                auction_id: auction_data.id,
+                buyer: auction_data.owner.clone(),
+                seller,
+                amount: winning_price,
+                refunded,
+            },
+        );
+    }
+
+    /// Internal helper to fetch an active sealed-bid auction and its sealed-bid data.
+    fn _get_sealed_auction(env: &Env, auction_id: u32) -> (Auction, SealedAuctionData) {
+        let auction_data = Self::_get_auction(env, auction_id);
+
+        if auction_data.is_none() {
+            panic_with_error!(env, AuctionError::AuctionNotFound); // Auction must exist
+        }
+
+        let auction_data = auction_data.unwrap();
+
+        // Reject if the auction was canceled or already completed
+        if auction_data.is_canceled() {
+            panic_with_error!(env, AuctionError::AuctionCanceled);
+        }
+
+        if auction_data.is_completed() {
+            panic_with_error!(env, AuctionError::AuctionCompleted);
+        }
+
+        match auction_data.auction_conditions.auction_type.clone() {
+            AuctionType::Sealed(sealed_data) => (auction_data, sealed_data),
+            _ => panic_with_error!(env, AuctionError::NotSealedAuction),
+        }
+    }
+
+    /// Internal helper to fetch a bidder's sealed bid from storage.
+    fn _get_sealed_bid(env: &Env, auction_id: u32, bidder: &Address) -> Option<SealedBid> {
+        env.storage()
+            .persistent()
+            .get::<Val, SealedBid>(&DataKey::SealedBid(auction_id, bidder.clone()).into_val(env))
+    }
+
+    /// Internal helper to save a sealed bid to storage.
+    fn _save_sealed_bid(env: &Env, auction_id: u32, sealed_bid: &SealedBid) {
+        let key = DataKey::SealedBid(auction_id, sealed_bid.bidder.clone()).into_val(env);
+        env.storage()
+            .persistent()
+            .set::<Val, SealedBid>(&key, sealed_bid);
+        Self::_extend_ttl(env, &key);
+    }
+
+    /// Internal helper to settle a sealed-bid auction.
+    ///
+    /// The highest revealed bid wins and pays either its own bid or the
+    /// second-highest revealed bid, falling back to the starting price. The
+    /// rest of the winner's deposit and the deposits of all other revealed
+    /// bids are refunded; deposits of bids never revealed go to the owner.
+    fn _settle_sealed_auction(
+        env: &Env,
+        auction_data: &mut Auction,
+        sealed_data: &SealedAuctionData,
+    ) {
+        let seller = auction_data.owner.clone();
+        let winner = auction_data.curr_bidder.clone();
+
+        let mut second_price: Option<i128> = None;
+        let mut forfeited: i128 = 0;
+
+        // Refund losing bids and collect unrevealed deposits
+        for position in 1..=Self::_participants_len(env, auction_data.id) {
+            let bidder =
+                Self::_get_participant(env, auction_data.id, position).expect("Missing bidder");
+            let sealed_bid =
+                Self::_get_sealed_bid(env, auction_data.id, &bidder).expect("Missing sealed bid");
+
+            match sealed_bid.revealed_amount {
+                None => forfeited += sealed_bid.deposit,
+                Some(_) if winner.as_ref() == Some(&bidder) => {}
+                Some(bid_amount) => {
+                    second_price = Some(second_price.map_or(bid_amount, |p| p.max(bid_amount)));
+
+                    distribution::transfer_from_contract(
+                        env,
+                        &auction_data.token,
+                        &bidder,
+                        &sealed_bid.deposit,
+                    );
+                }
+            }
+        }
+
+        let mut seller_proceeds = forfeited;
+
+        if let Some(winner) = winner {
+            let sealed_bid =
+                Self::_get_sealed_bid(env, auction_data.id, &winner).expect("Missing sealed bid");
+
+            // The price is paid out of the winner's deposit, never beyond it
+            let price = match sealed_data.pricing {
+                SealedPricing::FirstPrice => {
+                    auction_data.curr_bid_amount.expect("Missing bid amount")
+                }
+                SealedPricing::SecondPrice => {
+                    second_price.unwrap_or(auction_data.auction_conditions.starting_price)
+                }
+            }
+            .clamp(0, sealed_bid.deposit);
+
+            // Refund the part of the deposit above the price
+            let refund = sealed_bid.deposit - price;
+            if refund > 0 {
+                distribution::transfer_from_contract(env, &auction_data.token, &winner, &refund);
+            }
+
+            seller_proceeds += price;
+            auction_data.owner = winner;
+            auction_data.curr_bid_amount = Some(price);
+        }
+
+        // Pay the price and forfeited deposits to the seller
+        if seller_proceeds > 0 {
+            distribution::transfer_from_contract(
+                env,
+                &auction_data.token,
+                &seller,
+                &seller_proceeds,
+            );
+        }
+    }
+
     /// Internal helper to register that a user has bid for an auction.
     fn _register_user_bid(env: &Env, bidder: &Address, auction_id: &u32) {
-        env.storage().instance().set::<Val, bool>(
-            &DataKey::HasBid(bidder.clone(), auction_id.clone()).into_val(env),
-            &true,
-        );
+        let key = DataKey::HasBid(bidder.clone(), *auction_id).into_val(env);
+        env.storage().persistent().set::<Val, bool>(&key, &true);
+        Self::_extend_ttl(env, &key);
+
+        Self::_append_index(env, AuctionIndex::ActiveBids(bidder.clone()), *auction_id);
+
+        // Remember the bidder for settlement and for pruning their active list
+        let len_key = DataKey::ParticipantsLen(*auction_id).into_val(env);
+        let position = env
+            .storage()
+            .persistent()
+            .get::<Val, u32>(&len_key)
+            .unwrap_or(0)
+            + 1;
+
+        let key = DataKey::Participant(*auction_id, position).into_val(env);
+        env.storage().persistent().set::<Val, Address>(&key, bidder);
+        Self::_extend_ttl(env, &key);
+
+        env.storage()
+            .persistent()
+            .set::<Val, u32>(&len_key, &position);
+        Self::_extend_ttl(env, &len_key);
+    }
+
+    /// Internal helper to fetch the number of bidders of an auction.
+    fn _participants_len(env: &Env, auction_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get::<Val, u32>(&DataKey::ParticipantsLen(auction_id).into_val(env))
+            .unwrap_or(0)
+    }
+
+    /// Internal helper to fetch a bidder of an auction by position.
+    fn _get_participant(env: &Env, auction_id: u32, position: u32) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get::<Val, Address>(&DataKey::Participant(auction_id, position).into_val(env))
     }
 
     /// Internal helper to check if a user has bid for an auction
     fn _has_bid(env: &Env, bidder: &Address, auction_id: &u32) -> bool {
         env.storage()
-            .instance()
-            .has::<Val>(&DataKey::HasBid(bidder.clone(), auction_id.clone()).into_val(env))
+            .persistent()
+            .has::<Val>(&DataKey::HasBid(bidder.clone(), *auction_id).into_val(env))
+    }
+
+    /// Internal helper to keep a persistent entry alive.
+    fn _extend_ttl(env: &Env, key: &Val) {
+        env.storage()
+            .persistent()
+            .extend_ttl::<Val>(key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    }
+
+    /// Internal helper to fetch the number of slots used by a list of auction ids.
+    fn _index_len(env: &Env, index: &AuctionIndex) -> u32 {
+        env.storage()
+            .persistent()
+            .get::<Val, u32>(&DataKey::IndexLen(index.clone()).into_val(env))
+            .unwrap_or(0)
+    }
+
+    /// Internal helper to add an auction id to a list in the next free slot.
+    fn _append_index(env: &Env, index: AuctionIndex, auction_id: u32) {
+        let slot = Self::_index_len(env, &index) + 1;
+
+        let entry_key = DataKey::IndexEntry(index.clone(), slot).into_val(env);
+        env.storage()
+            .persistent()
+            .set::<Val, u32>(&entry_key, &auction_id);
+        Self::_extend_ttl(env, &entry_key);
+
+        let slot_key = DataKey::IndexSlot(index.clone(), auction_id).into_val(env);
+        env.storage().persistent().set::<Val, u32>(&slot_key, &slot);
+        Self::_extend_ttl(env, &slot_key);
+
+        let len_key = DataKey::IndexLen(index).into_val(env);
+        env.storage().persistent().set::<Val, u32>(&len_key, &slot);
+        Self::_extend_ttl(env, &len_key);
+    }
+
+    /// Internal helper to remove an auction id from a list, filling its slot with the last entry.
+    fn _remove_index(env: &Env, index: AuctionIndex, auction_id: u32) {
+        let slot_key = DataKey::IndexSlot(index.clone(), auction_id).into_val(env);
+        let slot = env.storage().persistent().get::<Val, u32>(&slot_key);
+
+        if slot.is_none() {
+            return;
+        }
+
+        let slot = slot.unwrap();
+        let last = Self::_index_len(env, &index);
+        let last_key = DataKey::IndexEntry(index.clone(), last).into_val(env);
+
+        // Move the last entry into the freed slot
+        if slot != last {
+            let moved_id = env
+                .storage()
+                .persistent()
+                .get::<Val, u32>(&last_key)
+                .expect("Missing index entry");
+
+            let entry_key = DataKey::IndexEntry(index.clone(), slot).into_val(env);
+            env.storage()
+                .persistent()
+                .set::<Val, u32>(&entry_key, &moved_id);
+            Self::_extend_ttl(env, &entry_key);
+
+            let moved_key = DataKey::IndexSlot(index.clone(), moved_id).into_val(env);
+            env.storage()
+                .persistent()
+                .set::<Val, u32>(&moved_key, &slot);
+            Self::_extend_ttl(env, &moved_key);
+        }
+
+        env.storage().persistent().remove::<Val>(&last_key);
+        env.storage().persistent().remove::<Val>(&slot_key);
+
+        let len_key = DataKey::IndexLen(index).into_val(env);
+        env.storage()
+            .persistent()
+            .set::<Val, u32>(&len_key, &(last - 1));
+        Self::_extend_ttl(env, &len_key);
+    }
+
+    /// Internal helper to move an auction to a new status, keeping the status lists in sync.
+    fn _set_status(env: &Env, auction_data: &mut Auction, status: AuctionStatus) {
+        Self::_remove_index(
+            env,
+            AuctionIndex::Status(auction_data.auction_status.clone()),
+            auction_data.id,
+        );
+        Self::_append_index(env, AuctionIndex::Status(status.clone()), auction_data.id);
+
+        auction_data.auction_status = status;
+    }
+
+    /// Internal helper to load a page of auctions from the slots of a list.
+    fn _page_auctions(env: &Env, index: AuctionIndex, start: u32, limit: u32) -> Vec<Auction> {
+        let len = Self::_index_len(env, &index);
+
+        let mut auctions = Vec::new(env);
+        let first = start.saturating_add(1);
+        let last = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+        for slot in first..=last {
+            if let Some(auction) = env
+                .storage()
+                .persistent()
+                .get::<Val, u32>(&DataKey::IndexEntry(index.clone(), slot).into_val(env))
+                .and_then(|auction_id| Self::_get_auction(env, auction_id))
+            {
+                auctions.push_back(auction);
+            }
+        }
+        auctions
+    }
+
+    /// Internal helper to append a bid to an auction's history.
+    fn _record_bid_history(env: &Env, auction_id: u32, bidder: &Address, bid_amount: i128) {
+        let len_key = DataKey::BidHistoryLen(auction_id).into_val(env);
+        let id = env
+            .storage()
+            .persistent()
+            .get::<Val, u32>(&len_key)
+            .unwrap_or(0)
+            + 1;
+
+        let bid = Bid {
+            id,
+            bidder: bidder.clone(),
+            bid_amount,
+            timestamp: env.ledger().timestamp(),
+        };
+
+        let key = DataKey::Bid(auction_id, id).into_val(env);
+        env.storage().persistent().set::<Val, Bid>(&key, &bid);
+        Self::_extend_ttl(env, &key);
+
+        env.storage().persistent().set::<Val, u32>(&len_key, &id);
+        Self::_extend_ttl(env, &len_key);
     }
 }
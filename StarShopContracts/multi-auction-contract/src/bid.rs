@@ -11,25 +11,63 @@ pub fn record_bid(
     // Check if user can bid
     auction_data.check_can_bid(env, &new_bid_amount);
 
-    // Transfer tokens to contract
-    distribution::transfer_to_contract(&env, &auction_data.token, &new_bidder, &new_bid_amount);
-
-    // Check if prev bidder and refund
-    if let Some(prev_bidder) = &auction_data.curr_bidder {
-        let prev_bid_amount = auction_data
-            .curr_bid_amount
-            .expect("Missing previous bid amount");
-
-        distribution::transfer_from_contract(
-            &env,
-            &auction_data.token,
-            &prev_bidder,
-            &prev_bid_amount,
-        );
+    // Reverse auctions are funded by the owner's budget; sellers only quote prices
+    if auction_data.holds_bid_funds() {
+        // Transfer tokens to contract
+        distribution::transfer_to_contract(env, &auction_data.token, &new_bidder, &new_bid_amount);
+
+        // Check if prev bidder and refund
+        if let Some(prev_bidder) = &auction_data.curr_bidder {
+            let prev_bid_amount = auction_data
+                .curr_bid_amount
+                .expect("Missing previous bid amount");
+
+            distribution::transfer_from_contract(
+                env,
+                &auction_data.token,
+                prev_bidder,
+                &prev_bid_amount,
+            );
+        }
     }
 
     // Update curr_bids and increment bid_count
     auction_data.curr_bidder = Option::Some(new_bidder.clone());
-    auction_data.curr_bid_amount = Option::Some(new_bid_amount.clone());
+    auction_data.curr_bid_amount = Option::Some(new_bid_amount);
     auction_data.no_of_bids += 1;
 }
+
+/// Push the end time forward when a bid lands inside the soft-close window.
+///
+/// Returns true if the auction was extended.
+pub fn apply_soft_close(env: &Env, auction_data: &mut Auction) -> bool {
+    let conditions = &mut auction_data.auction_conditions;
+
+    let SoftClose::Enabled(soft_close) = &conditions.soft_close else {
+        return false;
+    };
+
+    // Only bids close to the end trigger an extension
+    let current_time = env.ledger().timestamp();
+    if current_time
+        < conditions
+            .end_time
+            .saturating_sub(soft_close.window_seconds)
+    {
+        return false;
+    }
+
+    // Never extend past the hard cap
+    let new_end_time = conditions
+        .end_time
+        .saturating_add(soft_close.extension_seconds)
+        .min(soft_close.max_end_time);
+    if new_end_time <= conditions.end_time {
+        return false;
+    }
+
+    conditions.end_time = new_end_time;
+    auction_data.no_of_extensions += 1;
+
+    true
+}
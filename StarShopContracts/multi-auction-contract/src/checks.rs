@@ -1,8 +1,9 @@
-use soroban_sdk::{panic_with_error, Env};
+use soroban_sdk::{panic_with_error, BytesN, Env};
 
 use crate::{
     errors::{ConditionError, ValidationError},
     types::*,
+    utils::sealed_bid_commitment,
 };
 
 impl ItemMetadata {
@@ -16,6 +17,22 @@ impl ItemMetadata {
         if self.title.is_empty() {
             panic_with_error!(&env, ValidationError::AuctionDescriptionCannotBeEmpty)
         }
+
+        // Ensure a fungible asset has a positive amount
+        if let AuctionAsset::Token(fungible) = &self.asset {
+            if fungible.amount <= 0 {
+                panic_with_error!(&env, ValidationError::AssetAmountMustBeGreaterThanZero)
+            }
+        }
+    }
+
+    pub fn validate_item_asset(&self, env: &Env, auction_type: &AuctionType) {
+        // Reverse auctions buy a service; the owner has nothing to deliver
+        if let AuctionType::Reverse = auction_type {
+            if self.asset != AuctionAsset::None {
+                panic_with_error!(&env, ValidationError::ReverseAuctionCannotHoldAsset)
+            }
+        }
     }
 }
 
@@ -26,8 +43,36 @@ impl Auction {
     }
 
     pub fn is_completed(&self) -> bool {
-        // Check if the auction status is "Completed"
-        self.auction_status == AuctionStatus::Completed
+        // Check if the auction status is "Completed"; unsold auctions are over as well
+        matches!(
+            self.auction_status,
+            AuctionStatus::Completed | AuctionStatus::Unsold
+        )
+    }
+
+    pub fn meets_reserve(&self) -> bool {
+        // Without a reserve price any outcome stands
+        match self.auction_conditions.reserve_price {
+            Some(reserve_price) => self
+                .curr_bid_amount
+                .is_some_and(|curr_bid_amount| curr_bid_amount >= reserve_price),
+            None => true,
+        }
+    }
+
+    pub fn is_buy_now(&self, bid_amount: i128) -> bool {
+        // Check if the bid reaches the buy-now price
+        self.auction_conditions
+            .buy_now_price
+            .is_some_and(|buy_now_price| bid_amount >= buy_now_price)
+    }
+
+    /// Whether bids move tokens into the contract
+    ///
+    /// Reverse auctions are funded by the owner's budget at creation, so
+    /// sellers only quote prices and never deposit.
+    pub fn holds_bid_funds(&self) -> bool {
+        !matches!(self.auction_conditions.auction_type, AuctionType::Reverse)
     }
 
     pub fn can_cancel(&self) -> bool {
@@ -38,9 +83,90 @@ impl Auction {
         }
     }
 
+    pub fn can_update_conditions(&self, conditions: &AuctionConditions) -> bool {
+        let current = &self.auction_conditions;
+
+        // A reverse auction's budget is deposited at creation and cannot change
+        let is_reverse = |auction_type: &AuctionType| matches!(auction_type, AuctionType::Reverse);
+        if (is_reverse(&current.auction_type) || is_reverse(&conditions.auction_type))
+            && (current.auction_type != conditions.auction_type
+                || current.starting_price != conditions.starting_price)
+        {
+            return false;
+        }
+
+        if self.no_of_bids == 0 {
+            return true;
+        }
+
+        // Bids were placed under the current auction type
+        if current.auction_type != conditions.auction_type {
+            return false;
+        }
+
+        // Moving the commit deadline would reopen or cut short a running phase, and a new
+        // starting price could reject committed bids at reveal and forfeit their deposits
+        if let AuctionType::Sealed(_) = conditions.auction_type {
+            if current.end_time != conditions.end_time
+                || current.starting_price != conditions.starting_price
+            {
+                return false;
+            }
+        }
+
+        // Placed bids must still clear the starting price
+        if let (AuctionType::Regular, Some(curr_bid_amount)) =
+            (&conditions.auction_type, self.curr_bid_amount)
+        {
+            if conditions.starting_price >= curr_bid_amount {
+                return false;
+            }
+        }
+
+        // Limits must leave room for what has already happened
+        if conditions
+            .on_bid_count
+            .is_some_and(|bid_count| bid_count < self.no_of_bids)
+        {
+            return false;
+        }
+
+        if conditions
+            .on_maximum_participants
+            .is_some_and(|max_participants| max_participants < self.no_of_participants)
+        {
+            return false;
+        }
+
+        // Raising the reserve above the current bid would turn a sale into no sale
+        if let (Some(reserve_price), Some(curr_bid_amount)) =
+            (conditions.reserve_price, self.curr_bid_amount)
+        {
+            if reserve_price > curr_bid_amount {
+                return false;
+            }
+        }
+
+        // The current bid would already have bought the item
+        if let (Some(buy_now_price), Some(curr_bid_amount)) =
+            (conditions.buy_now_price, self.curr_bid_amount)
+        {
+            if curr_bid_amount >= buy_now_price {
+                return false;
+            }
+        }
+
+        true
+    }
+
     pub fn check_can_bid(&self, env: &Env, bid_amount: &i128) {
         let conditions = &self.auction_conditions;
 
+        // Sealed-bid auctions only accept commitments
+        if let AuctionType::Sealed(_) = conditions.auction_type {
+            panic_with_error!(&env, ConditionError::SealedBidMustBeCommitted)
+        }
+
         // Check if the auction has ended based on the current time
         let current_time = env.ledger().timestamp();
         if current_time > conditions.end_time {
@@ -75,6 +201,7 @@ impl Auction {
                             return;
                         }
                     }
+                    AuctionType::Sealed(_) => {}
                 }
             }
         }
@@ -104,7 +231,7 @@ impl Auction {
             }
         }
 
-        let bid_amount = bid_amount.clone();
+        let bid_amount = *bid_amount;
 
         // Match auction type and validate bid logic
         let starting_price = conditions.starting_price;
@@ -116,6 +243,18 @@ impl Auction {
                         if curr_bid_amount >= bid_amount {
                             panic_with_error!(&env, ConditionError::BidMustBeHigherThanMaxBid)
                         }
+
+                        // Buy-now bids complete the auction regardless of the increment
+                        let min_bid_amount = conditions
+                            .min_increment
+                            .increment(curr_bid_amount)
+                            .and_then(|increment| curr_bid_amount.checked_add(increment))
+                            .unwrap_or_else(|| {
+                                panic_with_error!(&env, ConditionError::MinIncrementOverflow)
+                            });
+                        if bid_amount < min_bid_amount && !self.is_buy_now(bid_amount) {
+                            panic_with_error!(&env, ConditionError::BidBelowMinimumIncrement)
+                        }
                     }
                     Option::None => {
                         if starting_price >= bid_amount {
@@ -128,6 +267,11 @@ impl Auction {
                 }
             }
             AuctionType::Reverse => {
+                // Quotes move no tokens, so only the budget check below bounds them
+                if bid_amount <= 0 {
+                    panic_with_error!(&env, ConditionError::BidMustBeGreaterThanZero)
+                }
+
                 // Reverse auction: bid must be lower than the current bid or starting price
                 match self.curr_bid_amount {
                     Option::Some(curr_bid_amount) => {
@@ -159,6 +303,46 @@ impl Auction {
                     panic_with_error!(&env, ConditionError::BidMustMatchDutchPrice)
                 }
             }
+            AuctionType::Sealed(_) => {}
+        }
+    }
+
+    pub fn check_can_commit(&self, env: &Env, deposit: &i128) {
+        let conditions = &self.auction_conditions;
+
+        // Commitments are accepted until the end time
+        if env.ledger().timestamp() > conditions.end_time {
+            panic_with_error!(&env, ConditionError::AuctionEnded)
+        }
+
+        // Check if the maximum number of participants has been reached
+        if let Some(max_participants) = conditions.on_maximum_participants {
+            if self.no_of_participants + 1 > max_participants {
+                panic_with_error!(&env, ConditionError::MaxNumParticipantsReached);
+            }
+        }
+
+        // Deposits are refunded from the contract, so they must be positive
+        if *deposit <= 0 {
+            panic_with_error!(&env, ConditionError::DepositMustBeGreaterThanZero)
+        }
+
+        // The deposit must be able to cover at least the starting price
+        if *deposit < conditions.starting_price {
+            panic_with_error!(&env, ConditionError::DepositMustCoverStartingPrice)
+        }
+    }
+
+    pub fn check_can_reveal(&self, env: &Env, sealed_data: &SealedAuctionData) {
+        let current_time = env.ledger().timestamp();
+
+        // Bids are revealed between the end time and the reveal end time
+        if current_time <= self.auction_conditions.end_time {
+            panic_with_error!(&env, ConditionError::RevealPhaseNotStarted)
+        }
+
+        if current_time > sealed_data.reveal_end_time {
+            panic_with_error!(&env, ConditionError::RevealPhaseEnded)
         }
     }
 
@@ -166,6 +350,14 @@ impl Auction {
         let conditions = &self.auction_conditions;
         let current_time = env.ledger().timestamp();
 
+        // Sealed-bid auctions end only once the reveal phase is over
+        if let AuctionType::Sealed(sealed_data) = &conditions.auction_type {
+            if current_time <= sealed_data.reveal_end_time {
+                panic_with_error!(&env, ConditionError::RevealPhaseNotEnded);
+            }
+            return;
+        }
+
         if current_time >= conditions.end_time {
             return;
         }
@@ -204,6 +396,7 @@ impl Auction {
                             return;
                         }
                     }
+                    AuctionType::Sealed(_) => {}
                 }
             }
         }
@@ -262,6 +455,53 @@ impl Auction {
     }
 }
 
+impl SealedBid {
+    pub fn check_reveal(
+        &self,
+        env: &Env,
+        auction_id: u32,
+        bid_amount: i128,
+        salt: &BytesN<32>,
+        starting_price: i128,
+    ) {
+        if self.revealed_amount.is_some() {
+            panic_with_error!(&env, ConditionError::BidAlreadyRevealed)
+        }
+
+        // The auction, bidder, revealed amount and salt must hash to the commitment
+        if sealed_bid_commitment(env, auction_id, &self.bidder, bid_amount, salt) != self.commitment
+        {
+            panic_with_error!(&env, ConditionError::CommitmentMismatch)
+        }
+
+        if bid_amount < starting_price {
+            panic_with_error!(&env, ConditionError::BidMustBeHigherThanStartingPrice)
+        }
+
+        // The winner pays out of the deposit, so it must cover the bid
+        if bid_amount > self.deposit {
+            panic_with_error!(&env, ConditionError::BidExceedsDeposit)
+        }
+    }
+}
+
+impl MinIncrement {
+    /// Smallest raise over the current bid, or None if it does not fit in an i128
+    pub fn increment(&self, curr_bid_amount: i128) -> Option<i128> {
+        match self {
+            MinIncrement::None => Some(0),
+            MinIncrement::Absolute(increment) => Some(*increment),
+            MinIncrement::BasisPoints(basis_points) => {
+                // Round up so small bids still need a raise
+                curr_bid_amount
+                    .checked_mul(*basis_points as i128)?
+                    .checked_add(9_999)
+                    .map(|scaled| scaled / 10_000)
+            }
+        }
+    }
+}
+
 impl DutchAuctionData {
     pub fn calculate_current_price(
         &self,
@@ -282,7 +522,7 @@ impl DutchAuctionData {
         let elapsed = current_time - start_time;
         let price_diff = start_price - self.floor_price;
 
-        let price_decrease = (price_diff as i128 * elapsed as i128) / duration as i128;
+        let price_decrease = (price_diff * elapsed as i128) / duration as i128;
 
         // Return the current price
         start_price - price_decrease
@@ -298,8 +538,8 @@ impl AuctionConditions {
             panic_with_error!(&env, ValidationError::EndTimeInPast)
         }
 
-        // Ensure the starting price is greater than zero
-        if self.starting_price == 0 {
+        // Ensure the starting price is greater than zero; it is a reverse auction's budget
+        if self.starting_price <= 0 {
             panic_with_error!(&env, ValidationError::StartingPriceCannotBeZero);
         }
 
@@ -358,12 +598,72 @@ impl AuctionConditions {
                 );
             }
         }
+
+        // Validate the anti-sniping rule
+        if let SoftClose::Enabled(soft_close) = &self.soft_close {
+            // Dutch auctions end on their first bid and sealed bids are hidden
+            if let AuctionType::Dutch(_) | AuctionType::Sealed(_) = self.auction_type {
+                panic_with_error!(&env, ValidationError::SoftCloseNotSupported);
+            }
+
+            if soft_close.window_seconds == 0 || soft_close.extension_seconds == 0 {
+                panic_with_error!(&env, ValidationError::SoftCloseSecondsMustBeGreaterThanZero);
+            }
+
+            if soft_close.max_end_time < self.end_time {
+                panic_with_error!(&env, ValidationError::SoftCloseMaxEndTimeBeforeEndTime);
+            }
+        }
+
+        // Validate regular auction pricing
+        let has_regular_pricing = self.min_increment != MinIncrement::None
+            || self.reserve_price.is_some()
+            || self.buy_now_price.is_some();
+        if has_regular_pricing && self.auction_type != AuctionType::Regular {
+            panic_with_error!(&env, ValidationError::RegularAuctionOnlyCondition);
+        }
+
+        match self.min_increment {
+            MinIncrement::Absolute(increment) if increment <= 0 => {
+                panic_with_error!(&env, ValidationError::MinIncrementMustBeGreaterThanZero);
+            }
+            MinIncrement::BasisPoints(0) => {
+                panic_with_error!(&env, ValidationError::MinIncrementMustBeGreaterThanZero);
+            }
+            _ => {}
+        }
+
+        if let Some(reserve_price) = self.reserve_price {
+            if reserve_price <= 0 {
+                panic_with_error!(&env, ValidationError::ReservePriceMustBeGreaterThanZero);
+            }
+        }
+
+        if let Some(buy_now_price) = self.buy_now_price {
+            if buy_now_price <= self.starting_price {
+                panic_with_error!(&env, ValidationError::BuyNowPriceMustExceedStartingPrice);
+            }
+
+            if self
+                .reserve_price
+                .is_some_and(|reserve_price| buy_now_price < reserve_price)
+            {
+                panic_with_error!(&env, ValidationError::BuyNowPriceBelowReservePrice);
+            }
+        }
+
+        // Validate sealed-bid auction-specific conditions
+        if let AuctionType::Sealed(sealed_data) = &self.auction_type {
+            if sealed_data.reveal_end_time <= self.end_time {
+                panic_with_error!(&env, ValidationError::RevealEndTimeMustBeAfterEndTime);
+            }
+        }
     }
 
     // Only useful for exposing ducth auction current price
     pub fn get_item_current_price(&self, env: &Env, start_time: u64) -> i128 {
         if let AuctionType::Dutch(dutch_data) = &self.auction_type {
-            dutch_data.calculate_current_price(&env, self.starting_price, start_time, self.end_time)
+            dutch_data.calculate_current_price(env, self.starting_price, start_time, self.end_time)
         } else {
             0
         }
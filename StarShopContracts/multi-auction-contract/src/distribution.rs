@@ -1,4 +1,13 @@
-use soroban_sdk::{token, Address, Env};
+use soroban_sdk::{contractclient, token, Address, Env};
+
+use crate::types::AuctionAsset;
+
+/// Interface of nft-contract used to custody auctioned NFTs.
+#[allow(dead_code)]
+#[contractclient(name = "NftClient")]
+pub trait NftInterface {
+    fn transfer_nft(env: Env, from: Address, to: Address, token_id: u32);
+}
 
 // Transfer tokens from contract
 pub fn transfer_from_contract(env: &Env, token: &Address, to: &Address, amount: &i128) {
@@ -9,3 +18,33 @@ pub fn transfer_from_contract(env: &Env, token: &Address, to: &Address, amount:
 pub fn transfer_to_contract(env: &Env, token: &Address, from: &Address, amount: &i128) {
     token::Client::new(env, token).transfer(from, &env.current_contract_address(), amount);
 }
+
+// Transfer an auctioned asset to contract
+pub fn lock_asset(env: &Env, asset: &AuctionAsset, from: &Address) {
+    match asset {
+        AuctionAsset::None => {}
+        AuctionAsset::Nft(nft) => NftClient::new(env, &nft.contract).transfer_nft(
+            from,
+            &env.current_contract_address(),
+            &nft.token_id,
+        ),
+        AuctionAsset::Token(fungible) => {
+            transfer_to_contract(env, &fungible.token, from, &fungible.amount)
+        }
+    }
+}
+
+// Transfer an auctioned asset from contract
+pub fn release_asset(env: &Env, asset: &AuctionAsset, to: &Address) {
+    match asset {
+        AuctionAsset::None => {}
+        AuctionAsset::Nft(nft) => NftClient::new(env, &nft.contract).transfer_nft(
+            &env.current_contract_address(),
+            to,
+            &nft.token_id,
+        ),
+        AuctionAsset::Token(fungible) => {
+            transfer_from_contract(env, &fungible.token, to, &fungible.amount)
+        }
+    }
+}
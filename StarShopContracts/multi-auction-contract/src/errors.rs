@@ -15,6 +15,17 @@ pub enum ValidationError {
     EndTimeInPast = 111,
     DutchAuctionFloorPriceMustBeGreaterThanZero = 112,
     MaximumParticipantsMustBeGreaterThanZero = 113,
+    RevealEndTimeMustBeAfterEndTime = 114,
+    SoftCloseSecondsMustBeGreaterThanZero = 115,
+    SoftCloseMaxEndTimeBeforeEndTime = 116,
+    SoftCloseNotSupported = 117,
+    MinIncrementMustBeGreaterThanZero = 118,
+    ReservePriceMustBeGreaterThanZero = 119,
+    BuyNowPriceMustExceedStartingPrice = 120,
+    BuyNowPriceBelowReservePrice = 121,
+    RegularAuctionOnlyCondition = 122,
+    AssetAmountMustBeGreaterThanZero = 123,
+    ReverseAuctionCannotHoldAsset = 124,
 }
 
 #[contracterror]
@@ -25,6 +36,15 @@ pub enum AuctionError {
     AuctionCanceled = 202,
     AuctionCompleted = 203,
     CannotCancelAuction = 204,
+    EscrowNotFound = 205,
+    EscrowAlreadyReleased = 206,
+    NotSealedAuction = 207,
+    SealedBidNotFound = 208,
+    AlreadyInitialized = 209,
+    NotInitialized = 210,
+    ConditionsUpdateNotAllowed = 211,
+    EscrowReleaseDeadlineNotReached = 212,
+    AuctionStillActive = 213,
 }
 
 #[contracterror]
@@ -52,4 +72,17 @@ pub enum ConditionError {
     MinNumParticipantsNotReached = 319,
     MaxNumParticipantsNotReached = 320,
     NoBidsRegisteredYet = 321,
+    DepositMustCoverStartingPrice = 322,
+    BidAlreadyCommitted = 323,
+    RevealPhaseNotStarted = 324,
+    RevealPhaseEnded = 325,
+    BidAlreadyRevealed = 326,
+    CommitmentMismatch = 327,
+    BidExceedsDeposit = 328,
+    SealedBidMustBeCommitted = 329,
+    RevealPhaseNotEnded = 330,
+    BidBelowMinimumIncrement = 331,
+    BidMustBeGreaterThanZero = 332,
+    DepositMustBeGreaterThanZero = 333,
+    MinIncrementOverflow = 334,
 }
@@ -33,3 +33,62 @@ pub struct AuctionCompleted {
     pub final_price: Option<i128>,
     pub timestamp: u64,
 }
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowLocked {
+    pub auction_id: u32,
+    pub buyer: Address,
+    pub seller: Address,
+    pub amount: i128,
+    pub refunded: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowReleased {
+    pub auction_id: u32,
+    pub seller: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidCommitted {
+    pub auction_id: u32,
+    pub bidder: Address,
+    pub deposit: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidRevealed {
+    pub auction_id: u32,
+    pub bidder: Address,
+    pub bid_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionExtended {
+    pub auction_id: u32,
+    pub end_time: u64,
+    pub no_of_extensions: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionUnsold {
+    pub auction_id: u32,
+    pub highest_bid: Option<i128>,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConditionsUpdated {
+    pub auction_id: u32,
+    pub updated_by: Address,
+    pub timestamp: u64,
+}
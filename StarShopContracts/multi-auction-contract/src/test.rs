@@ -1,13 +1,19 @@
 #![cfg(test)]
 
 // use super::*;
-use crate::auction::{AuctionContract, AuctionContractClient};
-use crate::types::{AuctionStatus, AuctionType, DutchAuctionData, ItemMetadata};
+use crate::auction::{
+    AuctionContract, AuctionContractClient, ESCROW_RELEASE_PERIOD, MAX_PRUNE_BATCH,
+};
+use crate::errors::ConditionError;
+use crate::types::{
+    AuctionAsset, AuctionStatus, AuctionType, DutchAuctionData, ItemMetadata, MinIncrement,
+    NftAsset, SealedAuctionData, SealedPricing, TokenAsset,
+};
 use soroban_sdk::testutils::{Address as _, Ledger};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{token, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, token, Address, BytesN, Env, Error, String};
 
-use crate::utils::AuctionConditionsBuilder;
+use crate::utils::{sealed_bid_commitment, AuctionConditionsBuilder};
 
 struct Auction {
     env: Env,
@@ -29,6 +35,30 @@ fn create_token_contract<'a>(
     )
 }
 
+// Minimal stand-in for nft-contract's ownership and transfer entry points
+#[contract]
+struct MockNftContract;
+
+#[contractimpl]
+impl MockNftContract {
+    pub fn mint_nft(env: Env, to: Address, token_id: u32) {
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn transfer_nft(env: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+
+        let owner: Address = env.storage().persistent().get(&token_id).unwrap();
+        assert!(owner == from, "You are not the owner");
+
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn get_owner(env: Env, token_id: u32) -> Address {
+        env.storage().persistent().get(&token_id).unwrap()
+    }
+}
+
 impl Auction {
     fn new() -> Self {
         let env = Env::default();
@@ -43,6 +73,7 @@ impl Auction {
         let item_metadata = ItemMetadata {
             title: String::from_str(&env, "Auction Item"),
             description: String::from_str(&env, "Item description"),
+            asset: AuctionAsset::None,
         };
 
         let (token, token_admin) = create_token_contract(&env, &owner);
@@ -453,6 +484,9 @@ fn test_bid_reverse_auction() {
 
     let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 100).build();
 
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &100);
+
     client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
 
     let auction_id = 1;
@@ -501,6 +535,9 @@ fn test_bid_fail_reverse_auction_higher_than_starting_bid() {
 
     let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 100).build();
 
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &100);
+
     client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
 
     let auction_id = 1;
@@ -514,6 +551,62 @@ fn test_bid_fail_reverse_auction_higher_than_starting_bid() {
     client.make_bid(&auction_id, &bidder, &bid_amount);
 }
 
+#[test]
+fn test_bid_fail_reverse_auction_not_positive() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 100).build();
+
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &100);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    let not_positive = Error::from_contract_error(ConditionError::BidMustBeGreaterThanZero as u32);
+
+    // neither a zero nor a negative quote is accepted
+    assert_eq!(
+        client.try_make_bid(&auction_id, &bidder, &0),
+        Err(Ok(not_positive))
+    );
+    assert_eq!(
+        client.try_make_bid(&auction_id, &bidder, &-500),
+        Err(Ok(not_positive))
+    );
+
+    // the budget is untouched
+    assert_eq!(client.get_auction(&auction_id).unwrap().no_of_bids, 0);
+    assert_eq!(token.balance(&client.address), 100);
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_auction_creation_failed_negative_reverse_budget() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, -100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+}
+
 #[test]
 fn test_multiple_bid_reverse_auction() {
     let Auction {
@@ -529,6 +622,9 @@ fn test_multiple_bid_reverse_auction() {
     let auction_conditions =
         AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 1000).build();
 
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &1000);
+
     client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
 
     let auction_id = 1;
@@ -589,6 +685,9 @@ fn test_multiple_bid_fail_reverse_auction_higher_than_curr_lowest_bid() {
     let auction_conditions =
         AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 1000).build();
 
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &1000);
+
     client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
 
     let auction_id = 1;
@@ -1351,3 +1450,1367 @@ fn test_end_regular_auction_when_no_bids() {
         "Auction status should match"
     );
 }
+
+#[test]
+fn test_end_reverse_auction_escrows_winning_price() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 1000).build();
+
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &1000);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    assert_eq!(token.balance(&owner), 0);
+    assert_eq!(token.balance(&client.address), 1000);
+
+    let auction_id = 1;
+
+    // sellers quote prices without depositing
+    let seller1 = Address::generate(&env);
+    let seller2 = Address::generate(&env);
+
+    client.make_bid(&auction_id, &seller1, &900);
+    client.make_bid(&auction_id, &seller2, &700);
+
+    assert_eq!(token.balance(&seller1), 0);
+    assert_eq!(token.balance(&seller2), 0);
+
+    // move to end
+    env.ledger().set_timestamp(1200);
+
+    client.end_auction(&auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+
+    assert!(auction.owner == owner, "Auction owner should match");
+    assert!(
+        auction.auction_status == AuctionStatus::Completed,
+        "Auction status should match"
+    );
+
+    // unused budget is refunded, the winning price stays in escrow
+    assert_eq!(token.balance(&owner), 300);
+    assert_eq!(token.balance(&client.address), 700);
+
+    let escrow = client.get_escrow(&auction_id).unwrap();
+
+    assert!(escrow.seller == seller2, "Escrow seller should match");
+    assert_eq!(escrow.amount, 700);
+    assert!(!escrow.released, "Escrow should not be released");
+
+    client.release_escrow(&auction_id);
+
+    assert_eq!(token.balance(&seller2), 700);
+    assert_eq!(token.balance(&client.address), 0);
+    assert!(client.get_escrow(&auction_id).unwrap().released);
+}
+
+#[test]
+#[should_panic(expected = "#206")]
+fn test_release_escrow_fail_already_released() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 1000).build();
+
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &1000);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let seller = Address::generate(&env);
+    client.make_bid(&auction_id, &seller, &800);
+
+    // move to end
+    env.ledger().set_timestamp(1200);
+
+    client.end_auction(&auction_id);
+
+    client.release_escrow(&auction_id);
+    client.release_escrow(&auction_id);
+}
+
+#[test]
+#[should_panic(expected = "#205")]
+fn test_release_escrow_fail_not_found() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    // after end time
+    env.ledger().set_timestamp(1001);
+
+    client.end_auction(&auction_id);
+
+    client.release_escrow(&auction_id);
+}
+
+#[test]
+fn test_claim_escrow_after_release_deadline() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 1000).build();
+
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &1000);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let seller = Address::generate(&env);
+    client.make_bid(&auction_id, &seller, &800);
+
+    // move to end
+    env.ledger().set_timestamp(1200);
+
+    client.end_auction(&auction_id);
+
+    let escrow = client.get_escrow(&auction_id).unwrap();
+    assert_eq!(escrow.release_deadline, 1200 + ESCROW_RELEASE_PERIOD);
+
+    // the buyer never releases, so the seller collects after the deadline
+    env.ledger().set_timestamp(escrow.release_deadline + 1);
+
+    client.claim_escrow(&auction_id);
+
+    assert_eq!(token.balance(&seller), 800);
+    assert_eq!(token.balance(&client.address), 0);
+    assert!(client.get_escrow(&auction_id).unwrap().released);
+}
+
+#[test]
+#[should_panic(expected = "#212")]
+fn test_claim_escrow_fail_before_release_deadline() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 1000).build();
+
+    // the buyer deposits the budget
+    token_admin.mint(&owner, &1000);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let seller = Address::generate(&env);
+    client.make_bid(&auction_id, &seller, &800);
+
+    // move to end
+    env.ledger().set_timestamp(1200);
+
+    client.end_auction(&auction_id);
+
+    // the buyer still has time to release
+    env.ledger().set_timestamp(1200 + ESCROW_RELEASE_PERIOD);
+
+    client.claim_escrow(&auction_id);
+}
+
+#[test]
+fn test_reverse_auction_budget_refunded_without_winner() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 1000).build();
+
+    // the buyer deposits both budgets
+    token_admin.mint(&owner, &2000);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    assert_eq!(token.balance(&owner), 0);
+
+    // canceling returns the budget
+    client.cancel_auction(&1);
+
+    assert_eq!(token.balance(&owner), 1000);
+
+    // ending without bids returns the budget
+    env.ledger().set_timestamp(1001);
+
+    client.end_auction(&2);
+
+    assert_eq!(token.balance(&owner), 2000);
+    assert_eq!(token.balance(&client.address), 0);
+    assert!(client.get_escrow(&2).is_none());
+}
+
+fn sealed_auction_type(pricing: SealedPricing) -> AuctionType {
+    AuctionType::Sealed(SealedAuctionData {
+        reveal_end_time: 2000,
+        pricing,
+    })
+}
+
+#[test]
+fn test_end_sealed_first_price_auction() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::FirstPrice), 1000, 100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    let bidder3 = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[7; 32]);
+
+    // COMMIT PHASE
+    for bidder in [&bidder1, &bidder2, &bidder3] {
+        token_admin.mint(bidder, &1000);
+    }
+
+    client.commit_bid(
+        &auction_id,
+        &bidder1,
+        &sealed_bid_commitment(&env, auction_id, &bidder1, 500, &salt),
+        &800,
+    );
+    client.commit_bid(
+        &auction_id,
+        &bidder2,
+        &sealed_bid_commitment(&env, auction_id, &bidder2, 300, &salt),
+        &1000,
+    );
+    client.commit_bid(
+        &auction_id,
+        &bidder3,
+        &sealed_bid_commitment(&env, auction_id, &bidder3, 900, &salt),
+        &1000,
+    );
+
+    // amounts stay hidden until revealed
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert!(
+        auction.curr_bid_amount.is_none(),
+        "Bid amount should be hidden"
+    );
+    assert_eq!(auction.no_of_bids, 3);
+
+    // REVEAL PHASE; bidder3 never reveals
+    env.ledger().set_timestamp(1500);
+
+    client.reveal_bid(&auction_id, &bidder2, &300, &salt);
+    client.reveal_bid(&auction_id, &bidder1, &500, &salt);
+
+    // move past the reveal phase
+    env.ledger().set_timestamp(2001);
+
+    client.end_auction(&auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+
+    assert!(auction.owner == bidder1, "Auction owner should match");
+    assert_eq!(auction.curr_bid_amount, Some(500));
+    assert!(
+        auction.auction_status == AuctionStatus::Completed,
+        "Auction status should match"
+    );
+
+    // winner pays their bid, the loser is refunded, the unrevealed deposit is forfeited
+    assert_eq!(token.balance(&bidder1), 500);
+    assert_eq!(token.balance(&bidder2), 1000);
+    assert_eq!(token.balance(&bidder3), 0);
+    assert_eq!(token.balance(&owner), 1500);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_end_sealed_second_price_auction() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::SecondPrice), 1000, 100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    let salt1 = BytesN::from_array(&env, &[1; 32]);
+    let salt2 = BytesN::from_array(&env, &[2; 32]);
+
+    token_admin.mint(&bidder1, &1000);
+    token_admin.mint(&bidder2, &1000);
+
+    client.commit_bid(
+        &auction_id,
+        &bidder1,
+        &sealed_bid_commitment(&env, auction_id, &bidder1, 700, &salt1),
+        &1000,
+    );
+    client.commit_bid(
+        &auction_id,
+        &bidder2,
+        &sealed_bid_commitment(&env, auction_id, &bidder2, 400, &salt2),
+        &1000,
+    );
+
+    env.ledger().set_timestamp(1500);
+
+    client.reveal_bid(&auction_id, &bidder1, &700, &salt1);
+    client.reveal_bid(&auction_id, &bidder2, &400, &salt2);
+
+    env.ledger().set_timestamp(2001);
+
+    client.end_auction(&auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+
+    // winner pays the second-highest bid
+    assert!(auction.owner == bidder1, "Auction owner should match");
+    assert_eq!(auction.curr_bid_amount, Some(400));
+    assert_eq!(token.balance(&bidder1), 600);
+    assert_eq!(token.balance(&bidder2), 1000);
+    assert_eq!(token.balance(&owner), 400);
+}
+
+#[test]
+fn test_end_sealed_second_price_auction_single_bid_pays_starting_price() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::SecondPrice), 1000, 100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[7; 32]);
+
+    token_admin.mint(&bidder, &1000);
+
+    client.commit_bid(
+        &auction_id,
+        &bidder,
+        &sealed_bid_commitment(&env, auction_id, &bidder, 700, &salt),
+        &1000,
+    );
+
+    env.ledger().set_timestamp(1500);
+
+    client.reveal_bid(&auction_id, &bidder, &700, &salt);
+
+    env.ledger().set_timestamp(2001);
+
+    client.end_auction(&auction_id);
+
+    // without a second bid the winner pays the starting price out of the deposit
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert!(auction.owner == bidder, "Auction owner should match");
+    assert_eq!(auction.curr_bid_amount, Some(100));
+    assert_eq!(token.balance(&bidder), 900);
+    assert_eq!(token.balance(&owner), 100);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "#104")]
+fn test_auction_creation_failed_negative_sealed_starting_price() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::SecondPrice), 1000, -100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+}
+
+#[test]
+#[should_panic(expected = "#327")]
+fn test_reveal_bid_fail_commitment_mismatch() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::FirstPrice), 1000, 100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[7; 32]);
+
+    token_admin.mint(&bidder, &1000);
+
+    client.commit_bid(
+        &auction_id,
+        &bidder,
+        &sealed_bid_commitment(&env, auction_id, &bidder, 500, &salt),
+        &1000,
+    );
+
+    env.ledger().set_timestamp(1500);
+
+    // revealing a different amount than committed
+    client.reveal_bid(&auction_id, &bidder, &200, &salt);
+}
+
+#[test]
+#[should_panic(expected = "#327")]
+fn test_reveal_bid_fail_copied_commitment() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::FirstPrice), 1000, 100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    let copier = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[7; 32]);
+
+    token_admin.mint(&bidder, &1000);
+    token_admin.mint(&copier, &1000);
+
+    let commitment = sealed_bid_commitment(&env, auction_id, &bidder, 500, &salt);
+    client.commit_bid(&auction_id, &bidder, &commitment, &1000);
+
+    // another bidder copies the public commitment
+    client.commit_bid(&auction_id, &copier, &commitment, &1000);
+
+    env.ledger().set_timestamp(1500);
+
+    client.reveal_bid(&auction_id, &bidder, &500, &salt);
+
+    // learning the amount and salt from the reveal is not enough to reveal the copy
+    client.reveal_bid(&auction_id, &copier, &500, &salt);
+}
+
+#[test]
+#[should_panic(expected = "#329")]
+fn test_bid_fail_sealed_auction() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::FirstPrice), 1000, 100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let bidder = Address::generate(&env);
+
+    token_admin.mint(&bidder, &500);
+
+    client.make_bid(&1, &bidder, &500);
+}
+
+#[test]
+fn test_soft_close_extends_end_time() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .soft_close(100, 300, 1500)
+        .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &10_000);
+
+    // bid outside the window does not extend
+    env.ledger().set_timestamp(500);
+    client.make_bid(&auction_id, &bidder, &200);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.auction_conditions.end_time, 1000);
+    assert_eq!(auction.no_of_extensions, 0);
+
+    // bid inside the window pushes the end time
+    env.ledger().set_timestamp(950);
+    client.make_bid(&auction_id, &bidder, &300);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.auction_conditions.end_time, 1300);
+    assert_eq!(auction.no_of_extensions, 1);
+
+    // bid after the original end time is accepted and capped
+    env.ledger().set_timestamp(1250);
+    client.make_bid(&auction_id, &bidder, &400);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.auction_conditions.end_time, 1500);
+    assert_eq!(auction.no_of_extensions, 2);
+
+    // no extension past the hard cap
+    env.ledger().set_timestamp(1450);
+    client.make_bid(&auction_id, &bidder, &500);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.auction_conditions.end_time, 1500);
+    assert_eq!(auction.no_of_extensions, 2);
+
+    env.ledger().set_timestamp(1500);
+    client.end_auction(&auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert!(
+        auction.auction_status == AuctionStatus::Completed,
+        "Auction status should match"
+    );
+}
+
+#[test]
+#[should_panic(expected = "#313")]
+fn test_bid_fail_after_extended_end_time() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .soft_close(100, 300, 5000)
+        .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &10_000);
+
+    env.ledger().set_timestamp(950);
+    client.make_bid(&auction_id, &bidder, &200);
+
+    // extended to 1300
+    env.ledger().set_timestamp(1301);
+    client.make_bid(&auction_id, &bidder, &300);
+}
+
+#[test]
+#[should_panic(expected = "#117")]
+fn test_auction_creation_failed_soft_close_on_dutch_auction() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(
+        AuctionType::Dutch(DutchAuctionData { floor_price: 50 }),
+        1000,
+        100,
+    )
+    .soft_close(100, 300, 1500)
+    .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+}
+
+#[test]
+#[should_panic(expected = "#331")]
+fn test_multiple_bid_fail_regular_auction_below_minimum_increment() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .min_increment(MinIncrement::BasisPoints(1000))
+        .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+
+    token_admin.mint(&bidder1, &200);
+    token_admin.mint(&bidder2, &210);
+
+    client.make_bid(&auction_id, &bidder1, &200);
+
+    // 10% over 200 requires at least 220
+    client.make_bid(&auction_id, &bidder2, &210);
+}
+
+#[test]
+#[should_panic(expected = "#334")]
+fn test_multiple_bid_fail_regular_auction_minimum_increment_overflow() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .min_increment(MinIncrement::BasisPoints(1000))
+        .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    let large_bid = i128::MAX / 2;
+
+    token_admin.mint(&bidder1, &large_bid);
+    token_admin.mint(&bidder2, &(large_bid + 1));
+
+    client.make_bid(&auction_id, &bidder1, &large_bid);
+
+    // 10% of the current bid no longer fits in an i128
+    client.make_bid(&auction_id, &bidder2, &(large_bid + 1));
+}
+
+#[test]
+fn test_end_regular_auction_below_reserve_price() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .reserve_price(500)
+        .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &300);
+
+    client.make_bid(&auction_id, &bidder, &300);
+
+    assert_eq!(token.balance(&bidder), 0);
+
+    // move to end
+    env.ledger().set_timestamp(1200);
+
+    client.end_auction(&auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+
+    // all funds are returned and the item stays with the owner
+    assert!(auction.owner == owner, "Auction owner should match");
+    assert!(
+        auction.auction_status == AuctionStatus::Unsold,
+        "Auction status should match"
+    );
+    assert_eq!(token.balance(&bidder), 300);
+    assert_eq!(token.balance(&owner), 0);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_buy_now_completes_regular_auction() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .min_increment(MinIncrement::Absolute(100))
+        .reserve_price(800)
+        .buy_now_price(1000)
+        .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+
+    token_admin.mint(&bidder1, &950);
+    token_admin.mint(&bidder2, &1000);
+
+    client.make_bid(&auction_id, &bidder1, &950);
+
+    // the buy-now price is accepted below the minimum increment
+    client.make_bid(&auction_id, &bidder2, &1000);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+
+    assert!(auction.owner == bidder2, "Auction owner should match");
+    assert!(
+        auction.auction_status == AuctionStatus::Completed,
+        "Auction status should match"
+    );
+    assert_eq!(token.balance(&bidder1), 950);
+    assert_eq!(token.balance(&bidder2), 0);
+    assert_eq!(token.balance(&owner), 1000);
+}
+
+#[test]
+#[should_panic(expected = "#122")]
+fn test_auction_creation_failed_reserve_price_on_reverse_auction() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Reverse, 1000, 100)
+        .reserve_price(50)
+        .build();
+
+    token_admin.mint(&owner, &100);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+}
+
+#[test]
+fn test_end_auction_delivers_nft_to_winner() {
+    let Auction {
+        client,
+        mut item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let nft_address = env.register(MockNftContract, ());
+    let nft = MockNftContractClient::new(&env, &nft_address);
+    nft.mint_nft(&owner, &7);
+
+    item_metadata.asset = AuctionAsset::Nft(NftAsset {
+        contract: nft_address,
+        token_id: 7,
+    });
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    // the contract holds the NFT while the auction runs
+    assert!(nft.get_owner(&7) == client.address, "NFT should be locked");
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &500);
+
+    client.make_bid(&auction_id, &bidder, &500);
+
+    // move to end
+    env.ledger().set_timestamp(1200);
+
+    client.end_auction(&auction_id);
+
+    // item and payment settle together
+    assert!(nft.get_owner(&7) == bidder, "NFT should go to the winner");
+    assert_eq!(token.balance(&owner), 500);
+}
+
+#[test]
+fn test_end_auction_without_bids_returns_nft() {
+    let Auction {
+        client,
+        mut item_metadata,
+        token,
+        owner,
+        env,
+        ..
+    } = Auction::new();
+
+    let nft_address = env.register(MockNftContract, ());
+    let nft = MockNftContractClient::new(&env, &nft_address);
+    nft.mint_nft(&owner, &7);
+
+    item_metadata.asset = AuctionAsset::Nft(NftAsset {
+        contract: nft_address,
+        token_id: 7,
+    });
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    // after end time
+    env.ledger().set_timestamp(1001);
+
+    client.end_auction(&1);
+
+    assert!(
+        nft.get_owner(&7) == owner,
+        "NFT should return to the seller"
+    );
+}
+
+#[test]
+fn test_auction_cancelation_returns_token_asset() {
+    let Auction {
+        client,
+        mut item_metadata,
+        token,
+        owner,
+        env,
+        ..
+    } = Auction::new();
+
+    let (asset_token, asset_admin) = create_token_contract(&env, &owner);
+    asset_admin.mint(&owner, &50);
+
+    item_metadata.asset = AuctionAsset::Token(TokenAsset {
+        token: asset_token.address.clone(),
+        amount: 50,
+    });
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    assert_eq!(asset_token.balance(&owner), 0);
+    assert_eq!(asset_token.balance(&client.address), 50);
+
+    client.cancel_auction(&1);
+
+    assert_eq!(asset_token.balance(&owner), 50);
+    assert_eq!(asset_token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_update_conditions_by_admin() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        env,
+        ..
+    } = Auction::new();
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let new_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 2000, 50)
+        .on_bid_count(5)
+        .build();
+
+    client.update_conditions(&auction_id, &new_conditions);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+
+    assert!(
+        auction.auction_conditions == new_conditions,
+        "Auction conditions should match"
+    );
+}
+
+#[test]
+#[should_panic(expected = "#211")]
+fn test_update_conditions_fail_invalidates_placed_bid() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &300);
+
+    client.make_bid(&auction_id, &bidder, &300);
+
+    // the placed bid would no longer clear the starting price
+    let new_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 400).build();
+
+    client.update_conditions(&auction_id, &new_conditions);
+}
+
+#[test]
+#[should_panic(expected = "#211")]
+fn test_update_conditions_fail_reserve_above_placed_bid() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .reserve_price(200)
+        .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &300);
+
+    client.make_bid(&auction_id, &bidder, &300);
+
+    // a reserve up to the placed bid is still allowed
+    let new_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .reserve_price(300)
+        .build();
+
+    client.update_conditions(&auction_id, &new_conditions);
+
+    // the placed bid would no longer meet the reserve price
+    let new_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100)
+        .reserve_price(400)
+        .build();
+
+    client.update_conditions(&auction_id, &new_conditions);
+}
+
+#[test]
+#[should_panic(expected = "#211")]
+fn test_update_conditions_fail_sealed_starting_price_after_commit() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let auction_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::FirstPrice), 1000, 100)
+            .build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[7; 32]);
+
+    token_admin.mint(&bidder, &1000);
+
+    client.commit_bid(
+        &auction_id,
+        &bidder,
+        &sealed_bid_commitment(&env, auction_id, &bidder, 300, &salt),
+        &1000,
+    );
+
+    // the committed bid would fall below the new starting price at reveal
+    let new_conditions =
+        AuctionConditionsBuilder::new(sealed_auction_type(SealedPricing::FirstPrice), 1000, 500)
+            .build();
+
+    client.update_conditions(&auction_id, &new_conditions);
+}
+
+#[test]
+#[should_panic(expected = "#210")]
+fn test_update_conditions_fail_without_admin() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    client.update_conditions(&1, &auction_conditions);
+}
+
+#[test]
+fn test_bid_history_pagination() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    token_admin.mint(&bidder1, &1000);
+    token_admin.mint(&bidder2, &1000);
+
+    client.make_bid(&auction_id, &bidder1, &200);
+    client.make_bid(&auction_id, &bidder2, &300);
+
+    env.ledger().set_timestamp(50);
+    client.make_bid(&auction_id, &bidder1, &400);
+
+    // FIRST PAGE
+    let bids = client.get_bids(&auction_id, &0, &2);
+
+    assert_eq!(bids.len(), 2);
+    assert_eq!(bids.get(0).unwrap().id, 1);
+    assert_eq!(bids.get(0).unwrap().bid_amount, 200);
+    assert!(
+        bids.get(1).unwrap().bidder == bidder2,
+        "Bidder should match"
+    );
+
+    // SECOND PAGE
+    let bids = client.get_bids(&auction_id, &2, &2);
+
+    assert_eq!(bids.len(), 1);
+    let bid = bids.get(0).unwrap();
+    assert_eq!(bid.id, 3);
+    assert!(bid.bidder == bidder1, "Bidder should match");
+    assert_eq!(bid.bid_amount, 400);
+    assert_eq!(bid.timestamp, 50);
+
+    // PAST THE END
+    assert_eq!(client.get_bids(&auction_id, &3, &2).len(), 0);
+}
+
+#[test]
+fn test_active_bids_and_won_auctions() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let short_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+    let long_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 5000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &short_conditions);
+    client.create_auction(&owner, &token.address, &item_metadata, &long_conditions);
+
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &1000);
+
+    client.make_bid(&1, &bidder, &200);
+    client.make_bid(&2, &bidder, &300);
+
+    assert_eq!(client.get_active_bids(&bidder, &0, &10).len(), 2);
+    assert_eq!(client.get_won_auctions(&bidder, &0, &10).len(), 0);
+
+    // first auction ends with the bidder as winner
+    env.ledger().set_timestamp(1200);
+    client.end_auction(&1);
+
+    let active = client.get_active_bids(&bidder, &0, &10);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get(0).unwrap().id, 2);
+
+    // the ended auction is skipped until it is pruned from the list
+    assert_eq!(client.get_active_bids(&bidder, &0, &1).len(), 0);
+    assert_eq!(client.prune_active_bids(&1, &10), 0);
+
+    // pruned auctions are gone before paging, so the first page is not empty
+    let first_page = client.get_active_bids(&bidder, &0, &1);
+    assert_eq!(first_page.len(), 1);
+    assert_eq!(first_page.get(0).unwrap().id, 2);
+    assert_eq!(client.get_active_bids(&bidder, &1, &1).len(), 0);
+
+    let won = client.get_won_auctions(&bidder, &0, &10);
+    assert_eq!(won.len(), 1);
+    assert_eq!(won.get(0).unwrap().id, 1);
+}
+
+#[test]
+fn test_prune_active_bids_in_batches() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        token_admin,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    let auction_id = 1;
+
+    let bidders = [0; 10].map(|_| Address::generate(&env));
+    for (i, bidder) in bidders.iter().enumerate() {
+        token_admin.mint(bidder, &1000);
+        client.make_bid(&auction_id, bidder, &(200 + i as i128 * 10));
+    }
+
+    env.ledger().set_timestamp(1200);
+    client.end_auction(&auction_id);
+
+    // a call handles at most MAX_PRUNE_BATCH bidders
+    assert_eq!(
+        client.prune_active_bids(&auction_id, &50),
+        10 - MAX_PRUNE_BATCH
+    );
+    assert_eq!(client.prune_active_bids(&auction_id, &50), 0);
+    assert_eq!(client.prune_active_bids(&auction_id, &50), 0);
+
+    for bidder in bidders.iter() {
+        assert_eq!(client.get_active_bids(bidder, &0, &10).len(), 0);
+    }
+}
+
+#[test]
+#[should_panic(expected = "#213")]
+fn test_prune_active_bids_fail_auction_still_active() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    client.prune_active_bids(&1, &10);
+}
+
+#[test]
+fn test_list_auctions_by_status_and_owner() {
+    let Auction {
+        client,
+        item_metadata,
+        token,
+        owner,
+        env,
+        ..
+    } = Auction::new();
+
+    let auction_conditions = AuctionConditionsBuilder::new(AuctionType::Regular, 1000, 100).build();
+
+    let other_owner = Address::generate(&env);
+
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+    client.create_auction(
+        &other_owner,
+        &token.address,
+        &item_metadata,
+        &auction_conditions,
+    );
+    client.create_auction(&owner, &token.address, &item_metadata, &auction_conditions);
+
+    client.cancel_auction(&1);
+
+    // BY STATUS; the newest active auction takes the canceled one's position
+    let active = client.get_auctions_by_status(&AuctionStatus::Active, &0, &10);
+    assert_eq!(active.len(), 2);
+    assert_eq!(active.get(0).unwrap().id, 3);
+    assert_eq!(active.get(1).unwrap().id, 2);
+
+    let first_page = client.get_auctions_by_status(&AuctionStatus::Active, &0, &1);
+    assert_eq!(first_page.len(), 1);
+    assert_eq!(first_page.get(0).unwrap().id, 3);
+
+    let canceled = client.get_auctions_by_status(&AuctionStatus::Cancelled, &0, &10);
+    assert_eq!(canceled.len(), 1);
+    assert_eq!(canceled.get(0).unwrap().id, 1);
+
+    // BY OWNER, paginated
+    let first_page = client.get_auctions_by_owner(&owner, &0, &1);
+    assert_eq!(first_page.len(), 1);
+    assert_eq!(first_page.get(0).unwrap().id, 1);
+
+    let second_page = client.get_auctions_by_owner(&owner, &1, &1);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page.get(0).unwrap().id, 3);
+}
@@ -1,9 +1,25 @@
 use crate::types::*;
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{Address, BytesN, Env, Vec};
 
 /// Interface for the Auction contract.
 pub trait AuctionTrait {
+    /// Set the admin allowed to update auction conditions, e.g. the governance contract.
+    fn initialize(env: Env, admin: Address);
+
+    /// Hand the admin role to a new address; callable by the current admin.
+    fn set_admin(env: Env, new_admin: Address);
+
+    fn get_admin(env: Env) -> Option<Address>;
+
+    /// Replace the conditions of an active auction; callable by the admin.
+    ///
+    /// Changes that would invalidate bids already placed are refused.
+    fn update_conditions(env: Env, auction_id: u32, conditions: AuctionConditions);
+
     /// Create a new auction in the auction marketplace.
+    ///
+    /// Reverse auctions are requests for quotes: the owner deposits the
+    /// starting price as the maximum budget when the auction is created.
     fn create_auction(
         env: Env,
         owner: Address,
@@ -19,4 +35,60 @@ pub trait AuctionTrait {
     fn end_auction(env: Env, auction_id: u32);
 
     fn get_auction(env: Env, auction_id: u32) -> Option<Auction>;
+
+    /// Pay the escrowed winning price of a reverse auction to the winning seller.
+    fn release_escrow(env: Env, auction_id: u32);
+
+    /// Let the winning seller collect an escrow the buyer has not released by its deadline.
+    fn claim_escrow(env: Env, auction_id: u32);
+
+    fn get_escrow(env: Env, auction_id: u32) -> Option<RfqEscrow>;
+
+    /// Commit a sealed bid, depositing funds that must cover the bid.
+    ///
+    /// The commitment is `sealed_bid_commitment(auction_id, bidder, bid_amount, salt)`.
+    fn commit_bid(
+        env: Env,
+        auction_id: u32,
+        bidder: Address,
+        commitment: BytesN<32>,
+        deposit: i128,
+    );
+
+    /// Reveal a sealed bid after the auction's end time.
+    fn reveal_bid(env: Env, auction_id: u32, bidder: Address, bid_amount: i128, salt: BytesN<32>);
+
+    fn get_sealed_bid(env: Env, auction_id: u32, bidder: Address) -> Option<SealedBid>;
+
+    /// Page through an auction's bid history, oldest first.
+    ///
+    /// Pages hold at most `MAX_PAGE_SIZE` entries; `start` is the number of entries to skip.
+    fn get_bids(env: Env, auction_id: u32, start: u32, limit: u32) -> Vec<Bid>;
+
+    /// Page through the auctions a user has bid on that are still active.
+    ///
+    /// Ended auctions are skipped until `prune_active_bids` removes them, so pages may be short.
+    fn get_active_bids(env: Env, bidder: Address, start: u32, limit: u32) -> Vec<Auction>;
+
+    /// Remove an ended or canceled auction from the active lists of up to `limit` more bidders.
+    ///
+    /// Anyone may call it; at most `MAX_PRUNE_BATCH` bidders are handled per call.
+    /// Returns the number of bidders still to prune.
+    fn prune_active_bids(env: Env, auction_id: u32, limit: u32) -> u32;
+
+    /// Page through the auctions a user has won.
+    fn get_won_auctions(env: Env, winner: Address, start: u32, limit: u32) -> Vec<Auction>;
+
+    /// Page through the auctions with a given status.
+    ///
+    /// An auction changing status is replaced in its old list by that list's newest entry.
+    fn get_auctions_by_status(
+        env: Env,
+        status: AuctionStatus,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Auction>;
+
+    /// Page through the auctions a user has created, oldest first.
+    fn get_auctions_by_owner(env: Env, owner: Address, start: u32, limit: u32) -> Vec<Auction>;
 }
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, String};
+use soroban_sdk::{contracttype, Address, BytesN, String};
 
 #[contracttype]
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -15,14 +15,39 @@ pub struct Auction {
     pub last_bid_time: u64,
     pub token: Address,
     pub auction_status: AuctionStatus,
+    pub no_of_extensions: u32,
 }
 
 #[contracttype]
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Bid {
-    pub id: u32,
+    pub id: u32, // Position in the auction's bid history, starting at 1
     pub bidder: Address,
     pub bid_amount: i128,
+    pub timestamp: u64,
+}
+
+// Winning price of a reverse auction, held for the winning seller until the buyer releases it
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct RfqEscrow {
+    pub auction_id: u32,
+    pub buyer: Address,
+    pub seller: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub released: bool,
+    pub release_deadline: u64, // After this time the seller can claim the escrow
+}
+
+// Commitment of a sealed bid, revealed after the auction's end time
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SealedBid {
+    pub bidder: Address,
+    pub commitment: BytesN<32>, // sealed_bid_commitment(auction_id, bidder, bid amount, salt)
+    pub deposit: i128,
+    pub revealed_amount: Option<i128>,
 }
 
 // Extendable Metadata to pass any required information
@@ -31,6 +56,30 @@ pub struct Bid {
 pub struct ItemMetadata {
     pub title: String,
     pub description: String,
+    pub asset: AuctionAsset, // Asset held by the contract until settlement
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum AuctionAsset {
+    None,
+    Nft(NftAsset),
+    Token(TokenAsset),
+}
+
+// Token minted by nft-contract
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct NftAsset {
+    pub contract: Address,
+    pub token_id: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TokenAsset {
+    pub token: Address,
+    pub amount: i128,
 }
 
 #[contracttype]
@@ -39,6 +88,7 @@ pub enum AuctionType {
     Regular,
     Reverse,
     Dutch(DutchAuctionData),
+    Sealed(SealedAuctionData),
 }
 
 #[contracttype]
@@ -47,10 +97,24 @@ pub struct DutchAuctionData {
     pub floor_price: i128,
 }
 
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SealedAuctionData {
+    pub reveal_end_time: u64, // Bids are committed until end_time and revealed until this time
+    pub pricing: SealedPricing,
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SealedPricing {
+    FirstPrice,  // Winner pays their own bid
+    SecondPrice, // Winner pays the second-highest revealed bid (Vickrey)
+}
+
 #[contracttype]
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct AuctionConditions {
-    pub auction_type: AuctionType, // Auction Type (Regular, Reverse, Dutch)
+    pub auction_type: AuctionType, // Auction Type (Regular, Reverse, Dutch, Sealed)
     pub end_time: u64,             // Auction End Time
     pub starting_price: i128,      // Auction Starting Price
 
@@ -61,6 +125,37 @@ pub struct AuctionConditions {
     pub on_fixed_sequence_number: Option<u32>, // Close at a specific ledger sequence number
     pub on_minimum_participants: Option<u32>,  // Close after X unique bidders
     pub on_maximum_participants: Option<u32>,  // Stop auction after X unique bidders
+
+    // Anti-Sniping
+    pub soft_close: SoftClose, // Extend end_time when bids land close to it
+
+    // Regular Auction Pricing
+    pub min_increment: MinIncrement, // Minimum raise over the current bid
+    pub reserve_price: Option<i128>, // Below this, the auction ends unsold
+    pub buy_now_price: Option<i128>, // A bid at this price completes the auction immediately
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum MinIncrement {
+    None,
+    Absolute(i128),
+    BasisPoints(u32), // Of the current bid, rounded up
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SoftClose {
+    Disabled,
+    Enabled(SoftCloseRule),
+}
+
+#[contracttype]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SoftCloseRule {
+    pub window_seconds: u64, // Bids within this many seconds of end_time extend it
+    pub extension_seconds: u64, // How far each such bid pushes end_time
+    pub max_end_time: u64,   // Hard cap end_time can never be extended past
 }
 
 #[contracttype]
@@ -69,4 +164,5 @@ pub enum AuctionStatus {
     Active,
     Cancelled,
     Completed,
+    Unsold, // Ended below the reserve price; all funds returned
 }
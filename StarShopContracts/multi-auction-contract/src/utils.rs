@@ -1,4 +1,5 @@
 use crate::types::*;
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env};
 
 pub struct AuctionConditionsBuilder {
     auction_type: AuctionType,
@@ -10,6 +11,10 @@ pub struct AuctionConditionsBuilder {
     on_fixed_sequence_number: Option<u32>,
     on_minimum_participants: Option<u32>,
     on_maximum_participants: Option<u32>,
+    soft_close: SoftClose,
+    min_increment: MinIncrement,
+    reserve_price: Option<i128>,
+    buy_now_price: Option<i128>,
 }
 
 impl AuctionConditionsBuilder {
@@ -25,6 +30,10 @@ impl AuctionConditionsBuilder {
             on_fixed_sequence_number: None,
             on_minimum_participants: None,
             on_maximum_participants: None,
+            soft_close: SoftClose::Disabled,
+            min_increment: MinIncrement::None,
+            reserve_price: None,
+            buy_now_price: None,
         }
     }
 
@@ -60,6 +69,35 @@ impl AuctionConditionsBuilder {
         self
     }
 
+    pub fn soft_close(
+        mut self,
+        window_seconds: u64,
+        extension_seconds: u64,
+        max_end_time: u64,
+    ) -> Self {
+        self.soft_close = SoftClose::Enabled(SoftCloseRule {
+            window_seconds,
+            extension_seconds,
+            max_end_time,
+        });
+        self
+    }
+
+    pub fn min_increment(mut self, min_increment: MinIncrement) -> Self {
+        self.min_increment = min_increment;
+        self
+    }
+
+    pub fn reserve_price(mut self, price: i128) -> Self {
+        self.reserve_price = Some(price);
+        self
+    }
+
+    pub fn buy_now_price(mut self, price: i128) -> Self {
+        self.buy_now_price = Some(price);
+        self
+    }
+
     /// Finally, build the AuctionConditions.
     pub fn build(self) -> AuctionConditions {
         AuctionConditions {
@@ -72,6 +110,30 @@ impl AuctionConditionsBuilder {
             on_fixed_sequence_number: self.on_fixed_sequence_number,
             on_minimum_participants: self.on_minimum_participants,
             on_maximum_participants: self.on_maximum_participants,
+            soft_close: self.soft_close,
+            min_increment: self.min_increment,
+            reserve_price: self.reserve_price,
+            buy_now_price: self.buy_now_price,
         }
     }
 }
+
+/// Compute the commitment of a sealed bid:
+/// sha256(auction id as big-endian u32 || bidder as XDR || bid amount as big-endian i128 || salt).
+///
+/// Binding the auction and bidder keeps a commitment from being copied into another
+/// auction or by another bidder.
+pub fn sealed_bid_commitment(
+    env: &Env,
+    auction_id: u32,
+    bidder: &Address,
+    bid_amount: i128,
+    salt: &BytesN<32>,
+) -> BytesN<32> {
+    let mut data = Bytes::from_array(env, &auction_id.to_be_bytes());
+    data.append(&bidder.clone().to_xdr(env));
+    data.extend_from_array(&bid_amount.to_be_bytes());
+    data.extend_from_array(&salt.to_array());
+
+    env.crypto().sha256(&data).to_bytes()
+}
@@ -0,0 +1,159 @@
+use crate::registry::check_token;
+use crate::transaction::{record_deposit, DepositStatus};
+use crate::{PaymentContract, PaymentContractArgs, PaymentContractClient};
+use soroban_sdk::token::Client as TokenClient;
+use soroban_sdk::{
+    contracterror, contractimpl, contracttype, symbol_short, Address, Env, String, Vec,
+};
+
+/// Largest number of payouts accepted in a single batch
+///
+/// Every paid recipient writes its token balance and a deposit record, and a
+/// transaction may only touch a few dozen ledger entries, so a full batch must
+/// stay well inside the network's per-transaction write limit.
+pub const MAX_BATCH_SIZE: u32 = 10;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum BatchError {
+    InsufficientFunds = 1,
+    EmptyBatch = 2,
+    BatchTooLarge = 3,
+    ContractPaused = 4,
+    TokenNotAllowed = 5,
+    TotalOverflow = 6,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PayoutStatus {
+    Paid,               // Transferred and recorded in the deposit ledger
+    InvalidAmount,      // Amount was zero or negative
+    UnauthorizedAccess, // Recipient was the signer
+    AmountOutOfRange,   // Amount outside the token's limits
+    TransferFailed,     // Token rejected the transfer to the recipient
+}
+
+/// One recipient of a batch payout
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payout {
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// Outcome of one payout in a batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutResult {
+    pub recipient: Address,
+    pub amount: i128,
+    pub status: PayoutStatus,
+    pub deposit_id: Option<u64>, // Ledger entry of the transfer, None if rejected
+}
+
+#[contractimpl]
+impl PaymentContract {
+    /// Pays several recipients in one token from the signer's balance
+    ///
+    /// Payouts that fail validation are reported in the returned results and
+    /// skipped; the rest are transferred and recorded as deposits. The total
+    /// of the accepted payouts is checked against the signer's balance once.
+    /// A recipient the token refuses to pay is reported as `TransferFailed`
+    /// without aborting the other payouts.
+    pub fn process_batch(
+        e: Env,
+        token_id: Address,
+        signer: Address,
+        payouts: Vec<Payout>,
+        order_ref: String,
+    ) -> Result<Vec<PayoutResult>, BatchError> {
+        // Check authorization
+        signer.require_auth();
+
+        if Self::is_paused(e.clone()) {
+            return Err(BatchError::ContractPaused);
+        }
+
+        // Input validations
+        if payouts.is_empty() {
+            return Err(BatchError::EmptyBatch);
+        }
+
+        if payouts.len() > MAX_BATCH_SIZE {
+            return Err(BatchError::BatchTooLarge);
+        }
+
+        let config = check_token(&e, &token_id).map_err(|_| BatchError::TokenNotAllowed)?;
+
+        // Validate each payout and total the accepted ones
+        let mut results = Vec::new(&e);
+        let mut total: i128 = 0;
+        for payout in payouts.iter() {
+            let status = if payout.amount <= 0 {
+                PayoutStatus::InvalidAmount
+            } else if payout.recipient == signer {
+                PayoutStatus::UnauthorizedAccess
+            } else if payout.amount < config.min_amount || payout.amount > config.max_amount {
+                PayoutStatus::AmountOutOfRange
+            } else {
+                total = total
+                    .checked_add(payout.amount)
+                    .ok_or(BatchError::TotalOverflow)?;
+                PayoutStatus::Paid
+            };
+            results.push_back(PayoutResult {
+                recipient: payout.recipient,
+                amount: payout.amount,
+                status,
+                deposit_id: None,
+            });
+        }
+
+        let token = TokenClient::new(&e, &token_id);
+
+        // Check balance once for the whole batch
+        if token.balance(&signer) < total {
+            return Err(BatchError::InsufficientFunds);
+        }
+
+        // Process transfers
+        let mut paid: u32 = 0;
+        let mut paid_total: i128 = 0;
+        for i in 0..results.len() {
+            let mut result = results.get(i).unwrap();
+            if result.status != PayoutStatus::Paid {
+                continue;
+            }
+
+            if !matches!(
+                token.try_transfer(&signer, &result.recipient, &result.amount),
+                Ok(Ok(()))
+            ) {
+                result.status = PayoutStatus::TransferFailed;
+                results.set(i, result);
+                continue;
+            }
+            result.deposit_id = Some(record_deposit(
+                &e,
+                &signer,
+                &result.recipient,
+                &token_id,
+                result.amount,
+                order_ref.clone(),
+                DepositStatus::Completed,
+            ));
+            paid_total += result.amount;
+            results.set(i, result);
+            paid += 1;
+        }
+
+        // Emit event
+        e.events().publish(
+            (symbol_short!("batch"), signer),
+            (token_id, paid, paid_total),
+        );
+
+        Ok(results)
+    }
+}
@@ -1,7 +1,9 @@
+use crate::transaction::{load_deposit, save_deposit, DepositRecord, DepositStatus};
+use crate::{PaymentContract, PaymentContractArgs, PaymentContractClient};
 use soroban_sdk::token::Client as TokenClient;
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, ConversionError, Env, IntoVal,
-    TryFromVal, TryIntoVal, Val,
+    contracterror, contractimpl, symbol_short, Address, ConversionError, Env, IntoVal, TryFromVal,
+    TryIntoVal, Val,
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -37,52 +39,78 @@ pub enum DisputeError {
     TransferFailed = 2,
     InvalidAmount = 3,
     UnauthorizedAccess = 4,
+    DepositNotFound = 5,
+    AlreadyResolved = 6,
+    NotArbitrator = 7,
+    ContractPaused = 8,
+    NotHeld = 9,
 }
 
-#[contract]
-pub struct DisputeContract;
-
 #[contractimpl]
-impl DisputeContract {
+impl PaymentContract {
+    /// Settles a deposit held by the contract in favour of its payer or its payee
     pub fn resolve_dispute(
         e: Env,
-        token_id: Address,
+        deposit_id: u64,
         arbitrator: Address,
-        buyer: Address,
-        seller: Address,
-        refund_amount: i128,
         decision: DisputeDecision,
     ) -> Result<(), DisputeError> {
         // Check authorization
         arbitrator.require_auth();
 
-        // Input validations
-        if refund_amount <= 0 {
-            return Err(DisputeError::InvalidAmount);
+        if Self::is_paused(e.clone()) {
+            return Err(DisputeError::ContractPaused);
+        }
+
+        // Only admin-registered arbitrators can resolve disputes
+        if !Self::is_arbitrator(e.clone(), arbitrator.clone()) {
+            return Err(DisputeError::NotArbitrator);
         }
 
-        let token = TokenClient::new(&e, &token_id);
+        let deposit = load_deposit(&e, deposit_id).ok_or(DisputeError::DepositNotFound)?;
 
-        // Check balance
-        let arbitrator_balance = token.balance(&arbitrator);
-        if arbitrator_balance < refund_amount {
-            return Err(DisputeError::InsufficientFunds);
+        // Disputed funds must still be in the contract's custody
+        match deposit.status {
+            DepositStatus::Held => {}
+            DepositStatus::Refunded | DepositStatus::Resolved => {
+                return Err(DisputeError::AlreadyResolved)
+            }
+            DepositStatus::Completed | DepositStatus::PartiallyRefunded => {
+                return Err(DisputeError::NotHeld)
+            }
         }
 
+        let disputed_amount = deposit.remaining();
+        let token = TokenClient::new(&e, &deposit.token);
+
         // Process transfer based on decision
-        match decision {
+        let buyer = deposit.payer.clone();
+        let seller = deposit.payee.clone();
+        let refunded_amount = match decision {
             DisputeDecision::RefundBuyer => {
-                token.transfer(&arbitrator, &buyer, &refund_amount);
+                token.transfer(&e.current_contract_address(), &buyer, &disputed_amount);
+                deposit.refunded_amount + disputed_amount
             }
             DisputeDecision::PaySeller => {
-                token.transfer(&arbitrator, &seller, &refund_amount);
+                token.transfer(&e.current_contract_address(), &seller, &disputed_amount);
+                deposit.refunded_amount
             }
-        }
+        };
+
+        // Update the ledger
+        save_deposit(
+            &e,
+            &DepositRecord {
+                refunded_amount,
+                status: DepositStatus::Resolved,
+                ..deposit
+            },
+        );
 
         // Emit event
         e.events().publish(
-            (symbol_short!("dispute"),),
-            (arbitrator, buyer, seller, refund_amount, decision as u32),
+            (symbol_short!("dispute"), deposit_id),
+            (arbitrator, buyer, seller, disputed_amount, decision as u32),
         );
 
         Ok(())
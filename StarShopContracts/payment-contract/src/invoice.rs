@@ -0,0 +1,309 @@
+use crate::registry::{check_payment, TokenRejection};
+use crate::storage::{DataKey, InvoiceIndex};
+use crate::transaction::{transfer_and_record, TransactionError};
+use crate::{PaymentContract, PaymentContractArgs, PaymentContractClient};
+use soroban_sdk::{
+    contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, String, Vec,
+};
+
+/// Largest number of invoice ids returned by a single paginated query
+pub const MAX_INVOICE_PAGE_SIZE: u32 = 50;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum InvoiceError {
+    InvalidAmount = 1,
+    InvalidDueDate = 2,
+    InvoiceNotFound = 3,
+    UnauthorizedAccess = 4,
+    NotOpen = 5,
+    InsufficientFunds = 6,
+    ContractPaused = 7,
+    TokenNotAllowed = 8,
+    AmountOutOfRange = 9,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceStatus {
+    Open,      // Awaiting payment
+    Paid,      // Settled through the deposit ledger
+    Cancelled, // Withdrawn by the seller
+}
+
+/// Payment request issued by a seller
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    pub id: u64,
+    pub seller: Address,
+    pub buyer: Option<Address>, // None lets anyone settle the invoice
+    pub token: Address,
+    pub amount: i128,
+    pub due_date: u64,
+    pub memo_hash: BytesN<32>,
+    pub status: InvoiceStatus,
+    pub created_at: u64,
+    pub paid_by: Option<Address>,
+    pub deposit_id: Option<u64>, // Ledger entry of the payment
+}
+
+#[contractimpl]
+impl PaymentContract {
+    /// Issues an invoice payable to the seller
+    pub fn create_invoice(
+        e: Env,
+        seller: Address,
+        buyer: Option<Address>,
+        token_id: Address,
+        amount: i128,
+        due_date: u64,
+        memo_hash: BytesN<32>,
+    ) -> Result<u64, InvoiceError> {
+        // Check authorization
+        seller.require_auth();
+
+        if Self::is_paused(e.clone()) {
+            return Err(InvoiceError::ContractPaused);
+        }
+
+        // Input validations
+        if amount <= 0 {
+            return Err(InvoiceError::InvalidAmount);
+        }
+
+        if due_date <= e.ledger().timestamp() {
+            return Err(InvoiceError::InvalidDueDate);
+        }
+
+        if buyer.as_ref() == Some(&seller) {
+            return Err(InvoiceError::UnauthorizedAccess);
+        }
+
+        check_payment(&e, &token_id, amount).map_err(|rejection| match rejection {
+            TokenRejection::NotAllowed => InvoiceError::TokenNotAllowed,
+            TokenRejection::OutOfRange => InvoiceError::AmountOutOfRange,
+        })?;
+
+        let invoice_id: u64 = e
+            .storage()
+            .instance()
+            .get(&DataKey::InvoiceCounter)
+            .unwrap_or(0)
+            + 1;
+        let invoice = Invoice {
+            id: invoice_id,
+            seller: seller.clone(),
+            buyer: buyer.clone(),
+            token: token_id,
+            amount,
+            due_date,
+            memo_hash,
+            status: InvoiceStatus::Open,
+            created_at: e.ledger().timestamp(),
+            paid_by: None,
+            deposit_id: None,
+        };
+        e.storage()
+            .instance()
+            .set(&DataKey::InvoiceCounter, &invoice_id);
+        save_invoice(&e, &invoice);
+
+        index_invoice(&e, InvoiceIndex::Seller(seller.clone()), invoice_id);
+        if let Some(buyer) = buyer {
+            index_invoice(&e, InvoiceIndex::Buyer(buyer), invoice_id);
+        }
+
+        // Emit event
+        e.events().publish(
+            (symbol_short!("invoice"), invoice_id),
+            (seller, amount, due_date),
+        );
+
+        Ok(invoice_id)
+    }
+
+    /// Settles an open invoice, transferring its amount from the payer to the seller
+    ///
+    /// Invoices naming a buyer can only be paid by that buyer. Overdue
+    /// invoices can still be paid. Returns the deposit id of the payment.
+    pub fn pay_invoice(e: Env, invoice_id: u64, payer: Address) -> Result<u64, InvoiceError> {
+        // Check authorization
+        payer.require_auth();
+
+        let mut invoice = load_invoice(&e, invoice_id)?;
+
+        if invoice.status != InvoiceStatus::Open {
+            return Err(InvoiceError::NotOpen);
+        }
+
+        if let Some(buyer) = &invoice.buyer {
+            if *buyer != payer {
+                return Err(InvoiceError::UnauthorizedAccess);
+            }
+        }
+
+        let deposit_id = transfer_and_record(
+            &e,
+            &invoice.token,
+            &payer,
+            &invoice.seller,
+            invoice.amount,
+            invoice_ref(&e, invoice_id),
+        )
+        .map_err(|err| match err {
+            TransactionError::InsufficientFunds => InvoiceError::InsufficientFunds,
+            TransactionError::InvalidAmount => InvoiceError::InvalidAmount,
+            TransactionError::ContractPaused => InvoiceError::ContractPaused,
+            TransactionError::TokenNotAllowed => InvoiceError::TokenNotAllowed,
+            TransactionError::AmountOutOfRange => InvoiceError::AmountOutOfRange,
+            _ => InvoiceError::UnauthorizedAccess,
+        })?;
+
+        // Open invoices are indexed under whoever settles them
+        if invoice.buyer.is_none() {
+            index_invoice(&e, InvoiceIndex::Buyer(payer.clone()), invoice_id);
+        }
+
+        invoice.status = InvoiceStatus::Paid;
+        invoice.paid_by = Some(payer.clone());
+        invoice.deposit_id = Some(deposit_id);
+        save_invoice(&e, &invoice);
+
+        // Emit event
+        e.events()
+            .publish((symbol_short!("inv_paid"), invoice_id), (payer, deposit_id));
+
+        Ok(deposit_id)
+    }
+
+    /// Withdraws an open invoice; callable by the seller only
+    pub fn cancel_invoice(e: Env, invoice_id: u64, seller: Address) -> Result<(), InvoiceError> {
+        // Check authorization
+        seller.require_auth();
+
+        let mut invoice = load_invoice(&e, invoice_id)?;
+
+        if invoice.seller != seller {
+            return Err(InvoiceError::UnauthorizedAccess);
+        }
+
+        if invoice.status != InvoiceStatus::Open {
+            return Err(InvoiceError::NotOpen);
+        }
+
+        invoice.status = InvoiceStatus::Cancelled;
+        save_invoice(&e, &invoice);
+
+        // Emit event
+        e.events()
+            .publish((symbol_short!("inv_cnl"), invoice_id), seller);
+
+        Ok(())
+    }
+
+    /// Returns an invoice
+    pub fn get_invoice(e: Env, invoice_id: u64) -> Result<Invoice, InvoiceError> {
+        load_invoice(&e, invoice_id)
+    }
+
+    /// Returns true if the invoice is still open past its due date
+    pub fn is_invoice_overdue(e: Env, invoice_id: u64) -> Result<bool, InvoiceError> {
+        let invoice = load_invoice(&e, invoice_id)?;
+        Ok(invoice.status == InvoiceStatus::Open && e.ledger().timestamp() > invoice.due_date)
+    }
+
+    /// Returns a page of the ids of invoices issued by a seller, oldest first
+    ///
+    /// `start` is the number of ids to skip and `limit` is capped at
+    /// MAX_INVOICE_PAGE_SIZE.
+    pub fn get_seller_invoices(e: Env, seller: Address, start: u32, limit: u32) -> Vec<u64> {
+        load_invoice_page(&e, InvoiceIndex::Seller(seller), start, limit)
+    }
+
+    /// Returns the number of invoices issued by a seller
+    pub fn get_seller_invoice_count(e: Env, seller: Address) -> u32 {
+        invoice_index_len(&e, &InvoiceIndex::Seller(seller))
+    }
+
+    /// Returns a page of the ids of invoices addressed to or paid by a buyer, oldest first
+    ///
+    /// `start` is the number of ids to skip and `limit` is capped at
+    /// MAX_INVOICE_PAGE_SIZE.
+    pub fn get_buyer_invoices(e: Env, buyer: Address, start: u32, limit: u32) -> Vec<u64> {
+        load_invoice_page(&e, InvoiceIndex::Buyer(buyer), start, limit)
+    }
+
+    /// Returns the number of invoices addressed to or paid by a buyer
+    pub fn get_buyer_invoice_count(e: Env, buyer: Address) -> u32 {
+        invoice_index_len(&e, &InvoiceIndex::Buyer(buyer))
+    }
+}
+
+fn load_invoice(e: &Env, invoice_id: u64) -> Result<Invoice, InvoiceError> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Invoice(invoice_id))
+        .ok_or(InvoiceError::InvoiceNotFound)
+}
+
+fn save_invoice(e: &Env, invoice: &Invoice) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice.id), invoice);
+}
+
+fn invoice_index_len(e: &Env, index: &InvoiceIndex) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::InvoiceIndexLen(index.clone()))
+        .unwrap_or(0)
+}
+
+/// Appends an invoice id to a list, writing only its new slot and the count
+fn index_invoice(e: &Env, index: InvoiceIndex, invoice_id: u64) {
+    let len = invoice_index_len(e, &index);
+    e.storage()
+        .persistent()
+        .set(&DataKey::InvoiceIndexEntry(index.clone(), len), &invoice_id);
+    e.storage()
+        .persistent()
+        .set(&DataKey::InvoiceIndexLen(index), &(len + 1));
+}
+
+fn load_invoice_page(e: &Env, index: InvoiceIndex, start: u32, limit: u32) -> Vec<u64> {
+    let mut page = Vec::new(e);
+    let end = start
+        .saturating_add(limit.min(MAX_INVOICE_PAGE_SIZE))
+        .min(invoice_index_len(e, &index));
+    for slot in start..end {
+        let invoice_id: u64 = e
+            .storage()
+            .persistent()
+            .get(&DataKey::InvoiceIndexEntry(index.clone(), slot))
+            .unwrap();
+        page.push_back(invoice_id);
+    }
+    page
+}
+
+/// Order reference recorded on an invoice's deposit, e.g. "INV-42"
+fn invoice_ref(e: &Env, invoice_id: u64) -> String {
+    let mut buf = [0u8; 24];
+    buf[..4].copy_from_slice(b"INV-");
+    let mut digits = [0u8; 20];
+    let mut n = invoice_id;
+    let mut len = 0;
+    loop {
+        digits[len] = b'0' + (n % 10) as u8;
+        len += 1;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    for (slot, digit) in buf[4..4 + len].iter_mut().zip(digits[..len].iter().rev()) {
+        *slot = *digit;
+    }
+    String::from_bytes(e, &buf[..4 + len])
+}
@@ -1,15 +1,33 @@
 #![no_std]
 use soroban_sdk::{contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env};
 
+mod batch;
 mod dispute;
+mod invoice;
 mod refund;
+mod registry;
+mod schedule;
+mod storage;
 #[cfg(test)]
 mod test;
 mod transaction;
 
-pub use dispute::{DisputeContract, DisputeContractClient, DisputeDecision, DisputeError};
-pub use refund::{RefundContract, RefundContractClient, RefundError};
-pub use transaction::{TransactionContract, TransactionContractClient, TransactionError};
+use storage::DataKey;
+
+pub use batch::{BatchError, Payout, PayoutResult, PayoutStatus, MAX_BATCH_SIZE};
+pub use dispute::{DisputeDecision, DisputeError};
+pub use invoice::{Invoice, InvoiceError, InvoiceStatus, MAX_INVOICE_PAGE_SIZE};
+pub use refund::RefundError;
+pub use registry::TokenConfig;
+pub use schedule::{
+    PaymentSchedule, ScheduleError, ScheduleExecution, ScheduleStatus, MAX_EXECUTION_PAGE_SIZE,
+    MAX_INSTALLMENTS,
+};
+pub use transaction::{DepositRecord, DepositStatus, TransactionError};
+
+// Deposits, refunds and disputes share one ledger, so the former
+// TransactionContract, RefundContract and DisputeContract are merged into
+// PaymentContract. Deployments and clients must switch to PaymentContract.
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -17,6 +35,10 @@ pub enum PaymentError {
     NotInitialized = 1,
     AlreadyInitialized = 2,
     UnauthorizedAccess = 3,
+    ArbitratorAlreadyExists = 4,
+    ArbitratorNotFound = 5,
+    InvalidTokenLimits = 6,
+    TokenNotFound = 7,
 }
 
 #[contract]
@@ -90,4 +112,81 @@ impl PaymentContract {
 
         Ok(())
     }
+
+    /// Pauses deposits, refunds and dispute resolution
+    pub fn pause(env: Env) -> Result<(), PaymentError> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &true);
+
+        // Emit pause event
+        env.events().publish((symbol_short!("paused"),), (admin,));
+
+        Ok(())
+    }
+
+    /// Resumes deposits, refunds and dispute resolution
+    pub fn unpause(env: Env) -> Result<(), PaymentError> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &false);
+
+        // Emit resume event
+        env.events().publish((symbol_short!("unpaused"),), (admin,));
+
+        Ok(())
+    }
+
+    /// Returns true while the contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Registers an address allowed to resolve disputes
+    pub fn add_arbitrator(env: Env, arbitrator: Address) -> Result<(), PaymentError> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let key = DataKey::Arbitrator(arbitrator.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(PaymentError::ArbitratorAlreadyExists);
+        }
+        env.storage().persistent().set(&key, &true);
+
+        // Emit arbitrator event
+        env.events()
+            .publish((symbol_short!("arb_add"),), (admin, arbitrator));
+
+        Ok(())
+    }
+
+    /// Removes a registered arbitrator
+    pub fn remove_arbitrator(env: Env, arbitrator: Address) -> Result<(), PaymentError> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let key = DataKey::Arbitrator(arbitrator.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(PaymentError::ArbitratorNotFound);
+        }
+        env.storage().persistent().remove(&key);
+
+        // Emit arbitrator event
+        env.events()
+            .publish((symbol_short!("arb_rem"),), (admin, arbitrator));
+
+        Ok(())
+    }
+
+    /// Returns true if the address is a registered arbitrator
+    pub fn is_arbitrator(env: Env, arbitrator: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Arbitrator(arbitrator))
+    }
 }
@@ -1,5 +1,8 @@
+use crate::registry::check_token;
+use crate::transaction::{load_deposit, save_deposit, DepositRecord, DepositStatus};
+use crate::{PaymentContract, PaymentContractArgs, PaymentContractClient};
 use soroban_sdk::token::Client as TokenClient;
-use soroban_sdk::{contract, contracterror, contractimpl, symbol_short, Address, Env};
+use soroban_sdk::{contracterror, contractimpl, symbol_short, Address, Env};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -8,33 +11,58 @@ pub enum RefundError {
     TransferFailed = 2,
     InvalidAmount = 3,
     UnauthorizedAccess = 4,
+    DepositNotFound = 5,
+    ExceedsRemaining = 6,
+    ContractPaused = 7,
+    TokenNotAllowed = 8,
+    DepositHeld = 9,
 }
 
-#[contract]
-pub struct RefundContract;
-
 #[contractimpl]
-impl RefundContract {
+impl PaymentContract {
+    /// Returns part or all of a recorded deposit to its payer
+    ///
+    /// Only the deposit's payee or the contract admin may issue the refund.
     pub fn process_refund(
         e: Env,
-        token_id: Address,
+        deposit_id: u64,
         signer: Address,
-        to: Address,
         refund_amount: i128,
     ) -> Result<(), RefundError> {
         // Check authorization
         signer.require_auth();
 
+        if Self::is_paused(e.clone()) {
+            return Err(RefundError::ContractPaused);
+        }
+
         // Input validations
         if refund_amount <= 0 {
             return Err(RefundError::InvalidAmount);
         }
 
-        if signer == to {
+        let deposit = load_deposit(&e, deposit_id).ok_or(RefundError::DepositNotFound)?;
+
+        // Only the payee who received the funds, or the admin, can refund
+        let is_admin = Self::get_admin(e.clone()).ok() == Some(signer.clone());
+        if signer == deposit.payer || (signer != deposit.payee && !is_admin) {
             return Err(RefundError::UnauthorizedAccess);
         }
 
-        let token = TokenClient::new(&e, &token_id);
+        // Held deposits never reached the payee; they are released or disputed instead
+        if deposit.status == DepositStatus::Held {
+            return Err(RefundError::DepositHeld);
+        }
+
+        // Refunds cannot exceed what has not yet been returned
+        if refund_amount > deposit.remaining() {
+            return Err(RefundError::ExceedsRemaining);
+        }
+
+        // Refunds move the deposit's token, which must still be enabled
+        check_token(&e, &deposit.token).map_err(|_| RefundError::TokenNotAllowed)?;
+
+        let token = TokenClient::new(&e, &deposit.token);
 
         // Check balance
         let signer_balance = token.balance(&signer);
@@ -43,11 +71,30 @@ impl RefundContract {
         }
 
         // Process transfer
-        token.transfer(&signer, &to, &refund_amount);
+        token.transfer(&signer, &deposit.payer, &refund_amount);
+
+        // Update the ledger
+        let refunded_amount = deposit.refunded_amount + refund_amount;
+        let status = if refunded_amount == deposit.amount {
+            DepositStatus::Refunded
+        } else {
+            DepositStatus::PartiallyRefunded
+        };
+        let to = deposit.payer.clone();
+        save_deposit(
+            &e,
+            &DepositRecord {
+                refunded_amount,
+                status,
+                ..deposit
+            },
+        );
 
         // Emit event
-        e.events()
-            .publish((symbol_short!("refund"),), (signer, to, refund_amount));
+        e.events().publish(
+            (symbol_short!("refund"), deposit_id),
+            (signer, to, refund_amount),
+        );
 
         Ok(())
     }
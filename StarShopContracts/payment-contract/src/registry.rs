@@ -0,0 +1,110 @@
+use crate::storage::DataKey;
+use crate::{PaymentContract, PaymentContractArgs, PaymentContractClient, PaymentError};
+use soroban_sdk::{contractimpl, contracttype, symbol_short, Address, Env, Vec};
+
+/// Acceptance rules for a Stellar asset contract
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenConfig {
+    pub min_amount: i128,
+    pub max_amount: i128,
+    pub enabled: bool,
+}
+
+/// Why a token or amount was rejected by the registry
+pub(crate) enum TokenRejection {
+    NotAllowed,
+    OutOfRange,
+}
+
+#[contractimpl]
+impl PaymentContract {
+    /// Accepts a token with per-payment amount limits, or updates its limits
+    pub fn add_token(
+        env: Env,
+        token: Address,
+        min_amount: i128,
+        max_amount: i128,
+    ) -> Result<(), PaymentError> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        // Input validations
+        if min_amount <= 0 || max_amount < min_amount {
+            return Err(PaymentError::InvalidTokenLimits);
+        }
+
+        let key = DataKey::Token(token.clone());
+        if !env.storage().persistent().has(&key) {
+            let mut tokens = Self::get_tokens(env.clone());
+            tokens.push_back(token.clone());
+            env.storage().persistent().set(&DataKey::Tokens, &tokens);
+        }
+
+        let config = TokenConfig {
+            min_amount,
+            max_amount,
+            enabled: true,
+        };
+        env.storage().persistent().set(&key, &config);
+
+        // Emit token event
+        env.events()
+            .publish((symbol_short!("token_add"),), (token, min_amount, max_amount));
+
+        Ok(())
+    }
+
+    /// Enables or disables a registered token without losing its limits
+    pub fn set_token_enabled(env: Env, token: Address, enabled: bool) -> Result<(), PaymentError> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+
+        let key = DataKey::Token(token.clone());
+        let config: TokenConfig = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(PaymentError::TokenNotFound)?;
+
+        env.storage()
+            .persistent()
+            .set(&key, &TokenConfig { enabled, ..config });
+
+        // Emit token event
+        env.events()
+            .publish((symbol_short!("token_en"),), (token, enabled));
+
+        Ok(())
+    }
+
+    /// Returns the acceptance rules of a token, if registered
+    pub fn get_token_config(env: Env, token: Address) -> Option<TokenConfig> {
+        env.storage().persistent().get(&DataKey::Token(token))
+    }
+
+    /// Returns every registered token, enabled or not
+    pub fn get_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Tokens)
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+/// Checks that a token is registered and enabled
+pub(crate) fn check_token(env: &Env, token: &Address) -> Result<TokenConfig, TokenRejection> {
+    match PaymentContract::get_token_config(env.clone(), token.clone()) {
+        Some(config) if config.enabled => Ok(config),
+        _ => Err(TokenRejection::NotAllowed),
+    }
+}
+
+/// Checks that a token is accepted and the amount is within its limits
+pub(crate) fn check_payment(env: &Env, token: &Address, amount: i128) -> Result<(), TokenRejection> {
+    let config = check_token(env, token)?;
+    if amount < config.min_amount || amount > config.max_amount {
+        return Err(TokenRejection::OutOfRange);
+    }
+    Ok(())
+}
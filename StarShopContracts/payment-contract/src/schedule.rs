@@ -0,0 +1,343 @@
+use crate::registry::{check_payment, check_token, TokenRejection};
+use crate::storage::DataKey;
+use crate::transaction::{record_deposit, DepositStatus};
+use crate::{PaymentContract, PaymentContractArgs, PaymentContractClient};
+use soroban_sdk::token::Client as TokenClient;
+use soroban_sdk::{
+    contracterror, contractimpl, contracttype, symbol_short, Address, Env, String, Vec,
+};
+
+/// Longest installment plan a payer can authorize
+pub const MAX_INSTALLMENTS: u32 = 120;
+
+/// Largest number of executions returned by a single paginated query
+pub const MAX_EXECUTION_PAGE_SIZE: u32 = 50;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum ScheduleError {
+    InvalidAmount = 1,
+    InvalidSchedule = 2,
+    ScheduleNotFound = 3,
+    UnauthorizedAccess = 4,
+    NotActive = 5,
+    NothingDue = 6,
+    ContractPaused = 7,
+    TokenNotAllowed = 8,
+    AmountOutOfRange = 9,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ScheduleStatus {
+    Active,    // Installments remain to be paid
+    Completed, // Every installment has been paid
+    Cancelled, // Remaining installments cancelled by the payer or payee
+}
+
+/// Installment plan authorized by a payer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentSchedule {
+    pub id: u64,
+    pub payer: Address,
+    pub payee: Address,
+    pub token: Address,
+    pub amount_per_period: i128,
+    pub period: u64, // Seconds between installments
+    pub installments: u32,
+    pub paid_installments: u32,
+    pub next_due: u64,
+    pub status: ScheduleStatus,
+    pub order_ref: String,
+}
+
+/// Outcome of one attempt to pay an installment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduleExecution {
+    pub installment: u32,
+    pub timestamp: u64,
+    pub amount: i128,
+    pub deposit_id: Option<u64>, // Ledger entry of the transfer, None if it failed
+}
+
+#[contractimpl]
+impl PaymentContract {
+    /// Authorizes an installment plan; the first installment is due immediately
+    ///
+    /// Installments are pulled with `transfer_from`, so the payer must also
+    /// grant this contract a token allowance covering the plan.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_schedule(
+        e: Env,
+        payer: Address,
+        payee: Address,
+        token_id: Address,
+        amount_per_period: i128,
+        period: u64,
+        installments: u32,
+        order_ref: String,
+    ) -> Result<u64, ScheduleError> {
+        // Check authorization
+        payer.require_auth();
+
+        if Self::is_paused(e.clone()) {
+            return Err(ScheduleError::ContractPaused);
+        }
+
+        // Input validations
+        if amount_per_period <= 0 {
+            return Err(ScheduleError::InvalidAmount);
+        }
+
+        if period == 0 || installments == 0 || installments > MAX_INSTALLMENTS {
+            return Err(ScheduleError::InvalidSchedule);
+        }
+
+        if payer == payee {
+            return Err(ScheduleError::UnauthorizedAccess);
+        }
+
+        // Each installment must be an acceptable deposit
+        check_payment(&e, &token_id, amount_per_period).map_err(|rejection| match rejection {
+            TokenRejection::NotAllowed => ScheduleError::TokenNotAllowed,
+            TokenRejection::OutOfRange => ScheduleError::AmountOutOfRange,
+        })?;
+
+        let schedule_id: u64 = e
+            .storage()
+            .instance()
+            .get(&DataKey::ScheduleCounter)
+            .unwrap_or(0)
+            + 1;
+        let schedule = PaymentSchedule {
+            id: schedule_id,
+            payer: payer.clone(),
+            payee: payee.clone(),
+            token: token_id,
+            amount_per_period,
+            period,
+            installments,
+            paid_installments: 0,
+            next_due: e.ledger().timestamp(),
+            status: ScheduleStatus::Active,
+            order_ref,
+        };
+        e.storage()
+            .instance()
+            .set(&DataKey::ScheduleCounter, &schedule_id);
+        save_schedule(&e, &schedule);
+
+        // Emit event
+        e.events().publish(
+            (symbol_short!("sched"), schedule_id),
+            (payer, payee, amount_per_period, installments),
+        );
+
+        Ok(schedule_id)
+    }
+
+    /// Pays every installment that has fallen due; callable by anyone
+    ///
+    /// Returns the number of installments paid. If the payer's balance or
+    /// allowance cannot cover an installment, the failed attempt is recorded
+    /// and the installment stays due for a later call.
+    pub fn execute_schedule(e: Env, schedule_id: u64) -> Result<u32, ScheduleError> {
+        if Self::is_paused(e.clone()) {
+            return Err(ScheduleError::ContractPaused);
+        }
+
+        let mut schedule = load_schedule(&e, schedule_id)?;
+
+        if schedule.status != ScheduleStatus::Active {
+            return Err(ScheduleError::NotActive);
+        }
+
+        let now = e.ledger().timestamp();
+        if schedule.next_due > now {
+            return Err(ScheduleError::NothingDue);
+        }
+
+        let token = TokenClient::new(&e, &schedule.token);
+        let contract = e.current_contract_address();
+        let recorded = execution_count(&e, schedule_id);
+        let mut execution_count = recorded;
+        let mut paid: u32 = 0;
+
+        while schedule.next_due <= now && schedule.paid_installments < schedule.installments {
+            let installment = schedule.paid_installments + 1;
+            let amount = schedule.amount_per_period;
+
+            // Stop at the first installment that cannot be covered
+            let covered = check_token(&e, &schedule.token).is_ok()
+                && token.balance(&schedule.payer) >= amount
+                && token.allowance(&schedule.payer, &contract) >= amount;
+            if !covered {
+                // Repeated failures of one installment keep a single record
+                let failure = ScheduleExecution {
+                    installment,
+                    timestamp: now,
+                    amount,
+                    deposit_id: None,
+                };
+                let retry_slot = execution_count.checked_sub(1).filter(|slot| {
+                    matches!(
+                        load_execution(&e, schedule_id, *slot),
+                        Some(last) if last.installment == installment && last.deposit_id.is_none()
+                    )
+                });
+                match retry_slot {
+                    Some(slot) => save_execution(&e, schedule_id, slot, &failure),
+                    None => {
+                        save_execution(&e, schedule_id, execution_count, &failure);
+                        execution_count += 1;
+                    }
+                }
+                e.events()
+                    .publish((symbol_short!("sched_err"), schedule_id), installment);
+                break;
+            }
+
+            // Pull the installment through the payer's allowance
+            token.transfer_from(&contract, &schedule.payer, &schedule.payee, &amount);
+            let deposit_id = record_deposit(
+                &e,
+                &schedule.payer,
+                &schedule.payee,
+                &schedule.token,
+                amount,
+                schedule.order_ref.clone(),
+                DepositStatus::Completed,
+            );
+            save_execution(
+                &e,
+                schedule_id,
+                execution_count,
+                &ScheduleExecution {
+                    installment,
+                    timestamp: now,
+                    amount,
+                    deposit_id: Some(deposit_id),
+                },
+            );
+            execution_count += 1;
+
+            schedule.paid_installments = installment;
+            schedule.next_due += schedule.period;
+            paid += 1;
+
+            // Emit event
+            e.events().publish(
+                (symbol_short!("sched_pay"), schedule_id),
+                (installment, deposit_id),
+            );
+        }
+
+        if schedule.paid_installments == schedule.installments {
+            schedule.status = ScheduleStatus::Completed;
+        }
+        save_schedule(&e, &schedule);
+        if execution_count != recorded {
+            e.storage().persistent().set(
+                &DataKey::ScheduleExecutionCount(schedule_id),
+                &execution_count,
+            );
+        }
+
+        Ok(paid)
+    }
+
+    /// Cancels the remaining installments; callable by the payer or the payee
+    pub fn cancel_schedule(e: Env, schedule_id: u64, caller: Address) -> Result<(), ScheduleError> {
+        // Check authorization
+        caller.require_auth();
+
+        let schedule = load_schedule(&e, schedule_id)?;
+
+        if caller != schedule.payer && caller != schedule.payee {
+            return Err(ScheduleError::UnauthorizedAccess);
+        }
+
+        if schedule.status != ScheduleStatus::Active {
+            return Err(ScheduleError::NotActive);
+        }
+
+        save_schedule(
+            &e,
+            &PaymentSchedule {
+                status: ScheduleStatus::Cancelled,
+                ..schedule
+            },
+        );
+
+        // Emit event
+        e.events()
+            .publish((symbol_short!("sched_cnl"), schedule_id), caller);
+
+        Ok(())
+    }
+
+    /// Returns an installment plan
+    pub fn get_schedule(e: Env, schedule_id: u64) -> Result<PaymentSchedule, ScheduleError> {
+        load_schedule(&e, schedule_id)
+    }
+
+    /// Returns a page of the recorded attempts to pay an installment, oldest first
+    ///
+    /// `start` is the number of executions to skip and `limit` is capped at
+    /// MAX_EXECUTION_PAGE_SIZE.
+    pub fn get_schedule_executions(
+        e: Env,
+        schedule_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Vec<ScheduleExecution> {
+        let mut page = Vec::new(&e);
+        let end = start
+            .saturating_add(limit.min(MAX_EXECUTION_PAGE_SIZE))
+            .min(execution_count(&e, schedule_id));
+        for slot in start..end {
+            page.push_back(load_execution(&e, schedule_id, slot).unwrap());
+        }
+        page
+    }
+
+    /// Returns the number of recorded attempts to pay an installment
+    pub fn get_schedule_execution_count(e: Env, schedule_id: u64) -> u32 {
+        execution_count(&e, schedule_id)
+    }
+}
+
+fn load_schedule(e: &Env, schedule_id: u64) -> Result<PaymentSchedule, ScheduleError> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Schedule(schedule_id))
+        .ok_or(ScheduleError::ScheduleNotFound)
+}
+
+fn save_schedule(e: &Env, schedule: &PaymentSchedule) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::Schedule(schedule.id), schedule);
+}
+
+fn execution_count(e: &Env, schedule_id: u64) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::ScheduleExecutionCount(schedule_id))
+        .unwrap_or(0)
+}
+
+fn load_execution(e: &Env, schedule_id: u64, slot: u32) -> Option<ScheduleExecution> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::ScheduleExecution(schedule_id, slot))
+}
+
+/// Executions are stored one per slot so recording one never rewrites the history
+fn save_execution(e: &Env, schedule_id: u64, slot: u32, execution: &ScheduleExecution) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::ScheduleExecution(schedule_id, slot), execution);
+}
@@ -0,0 +1,29 @@
+use soroban_sdk::{contracttype, Address};
+
+/// Storage keys shared by the payment contract modules
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    DepositCounter,
+    Deposit(u64),
+    Paused,
+    Arbitrator(Address),
+    Token(Address),
+    Tokens,
+    ScheduleCounter,
+    Schedule(u64),
+    ScheduleExecutionCount(u64),
+    ScheduleExecution(u64, u32),
+    InvoiceCounter,
+    Invoice(u64),
+    InvoiceIndexLen(InvoiceIndex),
+    InvoiceIndexEntry(InvoiceIndex, u32),
+}
+
+/// Invoice id lists, stored one id per slot
+#[contracttype]
+#[derive(Clone)]
+pub enum InvoiceIndex {
+    Seller(Address),
+    Buyer(Address),
+}
@@ -2,23 +2,19 @@
 extern crate std;
 
 use super::*;
-use crate::{
-    refund::{RefundContract, RefundContractClient},
-    transaction::TransactionContractClient,
-};
 use soroban_sdk::token::{StellarAssetClient as TokenAdmin, TokenClient};
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
-    Address, Env, IntoVal, Symbol,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, IssuerFlags, Ledger},
+    vec, Address, Env, IntoVal, String, Symbol, Vec,
 };
 
 // Create a simulated WASM for tests
 fn install_new_wasm(e: &Env) -> BytesN<32> {
     // Generate random bytes as a simulated WASM hash
     let mut bytes = [0u8; 32];
-    for i in 0..32 {
-        bytes[i] = i as u8;
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = i as u8;
     }
     BytesN::from_array(e, &bytes)
 }
@@ -28,24 +24,33 @@ fn test_process_deposit_with_auth() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(TransactionContract, ());
-    let client = TransactionContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
 
     let token_admin = Address::generate(&env);
 
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Register the contract admin and accept the token
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
     let sender = Address::generate(&env);
     let recipient = Address::generate(&env);
 
+    let order_ref = String::from_str(&env, "ORDER-1");
+
     // Mint tokens to sender
     token.mint(&sender, &1000);
 
     // Execute transaction
-    client.process_deposit(&token_contract_id.clone(), &sender, &recipient, &100);
+    let deposit_id =
+        client.process_deposit(&token_contract_id.clone(), &sender, &recipient, &100, &order_ref);
 
     // Verify signed transactions
     assert_eq!(
@@ -60,7 +65,8 @@ fn test_process_deposit_with_auth() {
                         token_contract_id.clone(),
                         sender.clone(),
                         recipient.clone(),
-                        100_i128
+                        100_i128,
+                        order_ref.clone()
                     )
                         .into_val(&env),
                 )),
@@ -80,6 +86,18 @@ fn test_process_deposit_with_auth() {
     let token_client = TokenClient::new(&env, &token_contract_id);
     assert_eq!(token_client.balance(&sender), 900);
     assert_eq!(token_client.balance(&recipient), 100);
+
+    // Verify the ledger record
+    assert_eq!(deposit_id, 1);
+    assert_eq!(client.get_deposit_count(), 1);
+    let record = client.get_deposit(&deposit_id);
+    assert_eq!(record.payer, sender);
+    assert_eq!(record.payee, recipient);
+    assert_eq!(record.token, token_contract_id);
+    assert_eq!(record.amount, 100);
+    assert_eq!(record.refunded_amount, 0);
+    assert_eq!(record.status, DepositStatus::Completed);
+    assert_eq!(record.order_ref, order_ref);
 }
 
 #[test]
@@ -212,35 +230,116 @@ fn test_reinitialize() {
     client.initialize(&new_admin);
 }
 
+
 #[test]
 fn test_process_refund_success() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(RefundContract, ());
-    let client = RefundContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
 
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
 
+    // Register the contract admin and accept the token
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
     let seller = Address::generate(&env);
     let buyer = Address::generate(&env);
 
-    // Mint tokens to the seller
-    token.mint(&seller, &1000);
+    // Mint tokens to the buyer and pay the seller
+    token.mint(&buyer, &1000);
+    let order_ref = String::from_str(&env, "ORDER-1");
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
 
     // Process refund
     let refund_amount = 100;
-    client.process_refund(&token_contract_id, &seller, &buyer, &refund_amount);
+    client.process_refund(&deposit_id, &seller, &refund_amount);
 
     // Verify balances
     let token_client = TokenClient::new(&env, &token_contract_id);
-    assert_eq!(token_client.balance(&seller), 900);
-    assert_eq!(token_client.balance(&buyer), 100);
+    assert_eq!(token_client.balance(&seller), 0);
+    assert_eq!(token_client.balance(&buyer), 1000);
+
+    // Verify the deposit is fully refunded
+    let record = client.get_deposit(&deposit_id);
+    assert_eq!(record.refunded_amount, 100);
+    assert_eq!(record.status, DepositStatus::Refunded);
+}
+
+#[test]
+fn test_process_partial_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Register the contract admin and accept the token
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Mint tokens to both parties and pay the seller
+    token.mint(&buyer, &1000);
+    token.mint(&seller, &1000);
+    let order_ref = String::from_str(&env, "ORDER-2");
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
+
+    // Refund part of the deposit
+    client.process_refund(&deposit_id, &seller, &30);
+    let record = client.get_deposit(&deposit_id);
+    assert_eq!(record.refunded_amount, 30);
+    assert_eq!(record.status, DepositStatus::PartiallyRefunded);
+
+    // Refunds cannot exceed the un-refunded remainder
+    assert_eq!(
+        client.try_process_refund(&deposit_id, &seller, &71),
+        Err(Ok(RefundError::ExceedsRemaining))
+    );
+
+    // Refund the rest
+    client.process_refund(&deposit_id, &seller, &70);
+    assert_eq!(client.get_deposit(&deposit_id).status, DepositStatus::Refunded);
+    assert_eq!(
+        client.try_process_refund(&deposit_id, &seller, &1),
+        Err(Ok(RefundError::ExceedsRemaining))
+    );
+
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&buyer), 1000);
+    assert_eq!(token_client.balance(&seller), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_process_refund_unknown_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let seller = Address::generate(&env);
+
+    client.process_refund(&7, &seller, &100);
 }
 
 #[test]
@@ -249,22 +348,31 @@ fn test_process_refund_to_self() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(RefundContract, ());
-    let client = RefundContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
 
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
 
+    // Register the contract admin and accept the token
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
-    // Setup account
+    // Setup accounts
     let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
 
-    // Mint tokens to the seller
-    token.mint(&seller, &1000);
+    // Mint tokens to the buyer and pay the seller
+    token.mint(&buyer, &1000);
+    let order_ref = String::from_str(&env, "ORDER-3");
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
 
-    client.process_refund(&token_contract_id, &seller, &seller, &100);
+    // The payer cannot refund themselves
+    client.process_refund(&deposit_id, &buyer, &100);
 }
 
 #[test]
@@ -273,23 +381,35 @@ fn test_process_refund_insufficient_funds() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(RefundContract, ());
-    let client = RefundContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
 
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
 
+    // Register the contract admin and accept the token
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
 
     // Setup accounts
     let seller = Address::generate(&env);
     let buyer = Address::generate(&env);
+    let supplier = Address::generate(&env);
 
-    // Mint tokens to the seller (insufficient)
-    token.mint(&seller, &50);
+    // Mint tokens to the buyer and pay the seller
+    token.mint(&buyer, &1000);
+    let order_ref = String::from_str(&env, "ORDER-4");
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
 
-    client.process_refund(&token_contract_id, &seller, &buyer, &100);
+    // The seller spends most of the deposit (insufficient)
+    token_client.transfer(&seller, &supplier, &50);
+
+    client.process_refund(&deposit_id, &seller, &100);
 }
 
 #[test]
@@ -298,23 +418,30 @@ fn test_process_refund_invalid_amount() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(RefundContract, ());
-    let client = RefundContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
 
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
 
+    // Register the contract admin and accept the token
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
     let seller = Address::generate(&env);
     let buyer = Address::generate(&env);
 
-    // Mint tokens to the seller
-    token.mint(&seller, &50);
+    // Mint tokens to the buyer and pay the seller
+    token.mint(&buyer, &50);
+    let order_ref = String::from_str(&env, "ORDER-5");
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &50, &order_ref);
 
-    client.process_refund(&token_contract_id, &seller, &buyer, &0);
+    client.process_refund(&deposit_id, &seller, &0);
 }
 
 #[test]
@@ -322,8 +449,8 @@ fn test_process_refund_invalid_amount() {
 fn test_panic_resolve_dispute_not_authenticated() {
     // Initialize the environment and contract
     let env = Env::default();
-    let contract_id = env.register(DisputeContract, ());
-    let client = DisputeContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
 
     // Generate test addresses for different roles
     let admin = Address::generate(&env);
@@ -332,32 +459,38 @@ fn test_panic_resolve_dispute_not_authenticated() {
     let arbitrator = Address::generate(&env);
     let refund_amount = 1000i128;
 
-    // Set up the token contract and mint tokens to arbitrator
+    // Register the arbitrator with the contract admin
+    client.mock_all_auths().initialize(&admin);
+    client.mock_all_auths().add_arbitrator(&arbitrator);
+
+    // Set up the token contract and mint tokens to the buyer
     let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
     let token_address = token_contract.address();
 
+    // Accept the token for payments
+    client.mock_all_auths().add_token(&token_address, &1, &1_000_000);
+
     let _token_client = TokenClient::new(&env, &token_address);
     let token_asset_client = TokenAdmin::new(&env, &token_address);
-    token_asset_client.mint(&arbitrator, &refund_amount);
+    token_asset_client.mint(&buyer, &refund_amount);
+
+    // The buyer's payment to the seller is held by the contract
+    let order_ref = String::from_str(&env, "ORDER-6");
+    let deposit_id = client
+        .mock_all_auths()
+        .process_escrow_deposit(&token_address, &buyer, &seller, &refund_amount, &order_ref);
 
     // This should fail because we haven't mocked the authentication
-    client.resolve_dispute(
-        &token_address,
-        &arbitrator,
-        &buyer,
-        &seller,
-        &refund_amount,
-        &DisputeDecision::RefundBuyer,
-    );
+    client.resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::RefundBuyer);
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #1)")]
-fn test_resolve_dispute_insufficient_funds() {
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_resolve_dispute_released_deposit() {
     // Initialize environment with mocked authentication
     let env = Env::default();
-    let contract_id = env.register(DisputeContract, ());
-    let client = DisputeContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
     env.mock_all_auths();
 
     // Set up test addresses and amounts
@@ -365,35 +498,41 @@ fn test_resolve_dispute_insufficient_funds() {
     let buyer = Address::generate(&env);
     let seller = Address::generate(&env);
     let arbitrator = Address::generate(&env);
-    let refund_amount = 1000i128; // Trying to refund 1000 tokens
+    let refund_amount = 1000i128;
+
+    // Register the arbitrator with the contract admin
+    client.initialize(&admin);
+    client.add_arbitrator(&arbitrator);
 
     // Set up token contract
     let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
     let token_address = token_contract.address();
-    let _token_client = TokenClient::new(&env, &token_address);
+
+    // Accept the token for payments
+    client.add_token(&token_address, &1, &1_000_000);
+
+    let token_client = TokenClient::new(&env, &token_address);
     let token_asset_client = TokenAdmin::new(&env, &token_address);
+    token_asset_client.mint(&buyer, &refund_amount);
 
-    // Mint only 100 tokens - less than the refund amount
-    token_asset_client.mint(&arbitrator, &100i128);
+    // The buyer's payment is held, then released to the seller
+    let order_ref = String::from_str(&env, "ORDER-7");
+    let deposit_id =
+        client.process_escrow_deposit(&token_address, &buyer, &seller, &refund_amount, &order_ref);
+    client.release_deposit(&deposit_id, &buyer);
+    assert_eq!(token_client.balance(&seller), refund_amount);
 
-    // Simulate resolving a dispute in favor of the buyer
-    client.resolve_dispute(
-        &token_address,
-        &arbitrator,
-        &buyer,
-        &seller,
-        &refund_amount,
-        &DisputeDecision::RefundBuyer,
-    );
+    // The contract no longer holds the deposit, so it cannot be disputed
+    client.resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::RefundBuyer);
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #3)")]
-fn test_resolve_dispute_invalid_amount() {
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_resolve_dispute_already_resolved() {
     // Initialize environment with mocked authentication
     let env = Env::default();
-    let contract_id = env.register(DisputeContract, ());
-    let client = DisputeContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
     env.mock_all_auths();
 
     // Set up test addresses
@@ -401,33 +540,76 @@ fn test_resolve_dispute_invalid_amount() {
     let buyer = Address::generate(&env);
     let seller = Address::generate(&env);
     let arbitrator = Address::generate(&env);
-    let invalid_refund_amount = 0i128; // Invalid amount (zero)
+
+    // Register the arbitrator with the contract admin
+    client.initialize(&admin);
+    client.add_arbitrator(&arbitrator);
 
     // Set up token contract
     let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
     let token_address = token_contract.address();
 
+    // Accept the token for payments
+    client.add_token(&token_address, &1, &1_000_000);
+
     let _token_client = TokenClient::new(&env, &token_address);
     let token_asset_client = TokenAdmin::new(&env, &token_address);
-    token_asset_client.mint(&arbitrator, &invalid_refund_amount);
+    token_asset_client.mint(&buyer, &1000);
 
-    // This should fail due to invalid amount (zero)
-    client.resolve_dispute(
-        &token_address,
-        &arbitrator,
-        &buyer,
-        &seller,
-        &invalid_refund_amount,
-        &DisputeDecision::RefundBuyer,
-    );
+    // The buyer's payment to the seller is held by the contract
+    let order_ref = String::from_str(&env, "ORDER-8");
+    let deposit_id = client.process_escrow_deposit(&token_address, &buyer, &seller, &1000, &order_ref);
+
+    // The first decision settles the deposit
+    client.resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::PaySeller);
+
+    // This should fail because the deposit is already settled
+    client.resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::RefundBuyer);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_resolve_dispute_direct_deposit() {
+    // Initialize environment with mocked authentication
+    let env = Env::default();
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+
+    // Set up test addresses
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+
+    // Register the arbitrator with the contract admin
+    client.initialize(&admin);
+    client.add_arbitrator(&arbitrator);
+
+    // Set up token contract
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_address = token_contract.address();
+
+    // Accept the token for payments
+    client.add_token(&token_address, &1, &1_000_000);
+
+    let token_asset_client = TokenAdmin::new(&env, &token_address);
+    token_asset_client.mint(&buyer, &1000);
+
+    // The buyer pays the seller directly
+    let order_ref = String::from_str(&env, "ORDER-9");
+    let deposit_id = client.process_deposit(&token_address, &buyer, &seller, &1000, &order_ref);
+
+    // The contract does not hold this deposit and cannot pay it out
+    client.resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::RefundBuyer);
 }
 
 #[test]
 fn test_resolve_dispute_refund_buyer() {
     // Initialize environment with mocked authentication
     let env = Env::default();
-    let contract_id = env.register(DisputeContract, ());
-    let client = DisputeContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
     env.mock_all_auths();
 
     // Set up test addresses and amount
@@ -437,17 +619,29 @@ fn test_resolve_dispute_refund_buyer() {
     let arbitrator = <Address>::generate(&env);
     let refund_amount = 1000i128;
 
+    // Register the arbitrator with the contract admin
+    client.initialize(&admin);
+    client.add_arbitrator(&arbitrator);
+
     // Mock the token client for transferring tokens
     let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
     let token_address = token_contract.address();
 
+    // Accept the token for payments
+    client.add_token(&token_address, &1, &1_000_000);
+
     let token_client = TokenClient::new(&env, &token_address);
     let token_asset_client = TokenAdmin::new(&env, &token_address);
-    token_asset_client.mint(&arbitrator, &refund_amount);
+    token_asset_client.mint(&buyer, &refund_amount);
+
+    // The buyer's payment to the seller is held by the contract
+    let order_ref = String::from_str(&env, "ORDER-10");
+    let deposit_id =
+        client.process_escrow_deposit(&token_address, &buyer, &seller, &refund_amount, &order_ref);
 
     // Verify initial balances
-    let arbitrator_balance_before = token_client.balance(&arbitrator);
-    assert_eq!(arbitrator_balance_before, refund_amount);
+    let contract_balance_before = token_client.balance(&contract_id);
+    assert_eq!(contract_balance_before, refund_amount);
 
     let buyer_balance_before = token_client.balance(&buyer);
     assert_eq!(buyer_balance_before, 0);
@@ -456,16 +650,9 @@ fn test_resolve_dispute_refund_buyer() {
     assert_eq!(seller_balance_before, 0);
 
     // Simulate resolving a dispute in favor of the buyer
-    client.resolve_dispute(
-        &token_address.clone(),
-        &arbitrator,
-        &buyer,
-        &seller,
-        &refund_amount,
-        &DisputeDecision::RefundBuyer,
-    );
+    client.resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::RefundBuyer);
 
-    // Verify signed transactions
+    // Verify signed transactions: the payout comes from the contract's custody
     assert_eq!(
         env.auths(),
         std::vec![(
@@ -474,31 +661,18 @@ fn test_resolve_dispute_refund_buyer() {
                 function: AuthorizedFunction::Contract((
                     contract_id.clone(),
                     Symbol::new(&env, "resolve_dispute"),
-                    (
-                        token_address.clone(),
-                        arbitrator.clone(),
-                        buyer.clone(),
-                        seller.clone(),
-                        refund_amount,
-                        DisputeDecision::RefundBuyer as u32
-                    )
+                    (deposit_id, arbitrator.clone(), DisputeDecision::RefundBuyer as u32)
                         .into_val(&env),
                 )),
-                sub_invocations: std::vec![AuthorizedInvocation {
-                    function: AuthorizedFunction::Contract((
-                        token_address.clone(),
-                        symbol_short!("transfer"),
-                        (arbitrator.clone(), buyer.clone(), refund_amount).into_val(&env),
-                    )),
-                    sub_invocations: std::vec![],
-                }]
+                sub_invocations: std::vec![]
             }
         )]
     );
 
     // Check the balance after the transfer
-    let arbitrator_balance_after = token_client.balance(&arbitrator);
-    assert_eq!(arbitrator_balance_after, 0);
+    let contract_balance_after = token_client.balance(&contract_id);
+    assert_eq!(contract_balance_after, 0);
+    assert_eq!(token_client.balance(&arbitrator), 0);
 
     // Check the buyer's balance
     let buyer_balance_after = token_client.balance(&buyer);
@@ -507,14 +681,19 @@ fn test_resolve_dispute_refund_buyer() {
     // Check the seller's balance
     let seller_balance_after = token_client.balance(&seller);
     assert_eq!(seller_balance_after, 0);
+
+    // Verify the deposit is settled
+    let deposit = client.get_deposit(&deposit_id);
+    assert_eq!(deposit.status, DepositStatus::Resolved);
+    assert_eq!(deposit.refunded_amount, refund_amount);
 }
 
 #[test]
 fn test_resolve_dispute_pay_seller() {
     // Initialize environment with mocked authentication
     let env = Env::default();
-    let contract_id = env.register(DisputeContract, ());
-    let client = DisputeContractClient::new(&env, &contract_id);
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
     env.mock_all_auths();
 
     // Set up test addresses and amount
@@ -524,17 +703,29 @@ fn test_resolve_dispute_pay_seller() {
     let arbitrator = <Address>::generate(&env);
     let refund_amount = 1000i128;
 
+    // Register the arbitrator with the contract admin
+    client.initialize(&admin);
+    client.add_arbitrator(&arbitrator);
+
     // Mock the token client for transferring tokens
     let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
     let token_address = token_contract.address();
 
+    // Accept the token for payments
+    client.add_token(&token_address, &1, &1_000_000);
+
     let token_client = TokenClient::new(&env, &token_address);
     let token_asset_client = TokenAdmin::new(&env, &token_address);
-    token_asset_client.mint(&arbitrator, &refund_amount);
+    token_asset_client.mint(&buyer, &refund_amount);
+
+    // The buyer's payment to the seller is held by the contract
+    let order_ref = String::from_str(&env, "ORDER-11");
+    let deposit_id =
+        client.process_escrow_deposit(&token_address, &buyer, &seller, &refund_amount, &order_ref);
 
     // Verify initial balances
-    let arbitrator_balance_before = token_client.balance(&arbitrator);
-    assert_eq!(arbitrator_balance_before, refund_amount);
+    let contract_balance_before = token_client.balance(&contract_id);
+    assert_eq!(contract_balance_before, refund_amount);
 
     let buyer_balance_before = token_client.balance(&buyer);
     assert_eq!(buyer_balance_before, 0);
@@ -543,16 +734,9 @@ fn test_resolve_dispute_pay_seller() {
     assert_eq!(seller_balance_before, 0);
 
     // Simulate resolving a dispute in favor of the seller
-    client.resolve_dispute(
-        &token_address.clone(),
-        &arbitrator,
-        &buyer,
-        &seller,
-        &refund_amount,
-        &DisputeDecision::PaySeller,
-    );
+    client.resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::PaySeller);
 
-    // Verify signed transactions
+    // Verify signed transactions: the payout comes from the contract's custody
     assert_eq!(
         env.auths(),
         std::vec![(
@@ -561,31 +745,18 @@ fn test_resolve_dispute_pay_seller() {
                 function: AuthorizedFunction::Contract((
                     contract_id.clone(),
                     Symbol::new(&env, "resolve_dispute"),
-                    (
-                        token_address.clone(),
-                        arbitrator.clone(),
-                        buyer.clone(),
-                        seller.clone(),
-                        refund_amount,
-                        DisputeDecision::PaySeller as u32
-                    )
+                    (deposit_id, arbitrator.clone(), DisputeDecision::PaySeller as u32)
                         .into_val(&env),
                 )),
-                sub_invocations: std::vec![AuthorizedInvocation {
-                    function: AuthorizedFunction::Contract((
-                        token_address.clone(),
-                        symbol_short!("transfer"),
-                        (arbitrator.clone(), seller.clone(), refund_amount).into_val(&env),
-                    )),
-                    sub_invocations: std::vec![],
-                }]
+                sub_invocations: std::vec![]
             }
         )]
     );
 
     // Check the balance after the transfer
-    let arbitrator_balance_after = token_client.balance(&arbitrator);
-    assert_eq!(arbitrator_balance_after, 0);
+    let contract_balance_after = token_client.balance(&contract_id);
+    assert_eq!(contract_balance_after, 0);
+    assert_eq!(token_client.balance(&arbitrator), 0);
 
     // Check the buyer's balance
     let buyer_balance_after = token_client.balance(&buyer);
@@ -594,4 +765,904 @@ fn test_resolve_dispute_pay_seller() {
     // Check the seller's balance
     let seller_balance_after = token_client.balance(&seller);
     assert_eq!(seller_balance_after, refund_amount);
+
+    // Verify the deposit is settled
+    let deposit = client.get_deposit(&deposit_id);
+    assert_eq!(deposit.status, DepositStatus::Resolved);
+    assert_eq!(deposit.refunded_amount, 0);
+}
+
+#[test]
+fn test_escrow_deposit_held_until_released() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Set up token contract
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_address = token_contract.address();
+
+    // Accept the token for payments
+    client.add_token(&token_address, &1, &1_000_000);
+
+    let token_client = TokenClient::new(&env, &token_address);
+    TokenAdmin::new(&env, &token_address).mint(&buyer, &1000);
+
+    // The contract holds the payment for the seller
+    let order_ref = String::from_str(&env, "ORDER-12");
+    let deposit_id = client.process_escrow_deposit(&token_address, &buyer, &seller, &400, &order_ref);
+    assert_eq!(token_client.balance(&contract_id), 400);
+    assert_eq!(token_client.balance(&seller), 0);
+    let record = client.get_deposit(&deposit_id);
+    assert_eq!(record.payee, seller);
+    assert_eq!(record.status, DepositStatus::Held);
+
+    // The seller never received it, so it cannot be refunded yet
+    assert_eq!(
+        client.try_process_refund(&deposit_id, &seller, &100),
+        Err(Ok(RefundError::DepositHeld))
+    );
+
+    // Only the payer releases it
+    assert_eq!(
+        client.try_release_deposit(&deposit_id, &seller),
+        Err(Ok(TransactionError::UnauthorizedAccess))
+    );
+    client.release_deposit(&deposit_id, &buyer);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(token_client.balance(&seller), 400);
+    assert_eq!(client.get_deposit(&deposit_id).status, DepositStatus::Completed);
+    assert_eq!(
+        client.try_release_deposit(&deposit_id, &buyer),
+        Err(Ok(TransactionError::NotHeld))
+    );
+
+    // Once released it is refundable by the seller like any deposit
+    client.process_refund(&deposit_id, &seller, &100);
+    assert_eq!(token_client.balance(&buyer), 700);
+}
+
+#[test]
+fn test_pause_blocks_deposits_and_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for payments
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let order_ref = String::from_str(&env, "ORDER-12");
+
+    // Mint tokens to the buyer and pay the seller
+    token.mint(&buyer, &1000);
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
+
+    // Pause the contract
+    client.pause();
+    assert!(client.is_paused());
+    assert_eq!(
+        client.try_process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref),
+        Err(Ok(TransactionError::ContractPaused))
+    );
+    assert_eq!(
+        client.try_process_refund(&deposit_id, &seller, &100),
+        Err(Ok(RefundError::ContractPaused))
+    );
+
+    // Resume the contract
+    client.unpause();
+    assert!(!client.is_paused());
+    client.process_refund(&deposit_id, &seller, &100);
+    assert_eq!(client.get_deposit(&deposit_id).status, DepositStatus::Refunded);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized function call for address")]
+fn test_pause_requires_admin() {
+    let env = Env::default();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.mock_all_auths().initialize(&admin);
+
+    // This should fail because the admin has not authorized the call
+    client.pause();
+}
+
+#[test]
+fn test_refund_by_payee_or_admin_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for payments
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let order_ref = String::from_str(&env, "ORDER-13");
+
+    // Mint tokens and pay the seller
+    token.mint(&buyer, &1000);
+    token.mint(&stranger, &1000);
+    token.mint(&admin, &1000);
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
+
+    // A third party cannot issue refunds against the deposit
+    assert_eq!(
+        client.try_process_refund(&deposit_id, &stranger, &50),
+        Err(Ok(RefundError::UnauthorizedAccess))
+    );
+
+    // The admin can refund on the seller's behalf
+    client.process_refund(&deposit_id, &admin, &50);
+    assert_eq!(token_client.balance(&admin), 950);
+    assert_eq!(token_client.balance(&buyer), 950);
+    assert_eq!(client.get_deposit(&deposit_id).refunded_amount, 50);
+}
+
+#[test]
+fn test_resolve_dispute_requires_registered_arbitrator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Set up token contract
+    let token_contract = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_address = token_contract.address();
+
+    // Accept the token for payments
+    client.add_token(&token_address, &1, &1_000_000);
+
+    let token_asset_client = TokenAdmin::new(&env, &token_address);
+    token_asset_client.mint(&buyer, &1000);
+
+    // The buyer's payment to the seller is held by the contract
+    let order_ref = String::from_str(&env, "ORDER-14");
+    let deposit_id = client.process_escrow_deposit(&token_address, &buyer, &seller, &1000, &order_ref);
+
+    // An unregistered address cannot resolve the dispute
+    assert_eq!(
+        client.try_resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::PaySeller),
+        Err(Ok(DisputeError::NotArbitrator))
+    );
+
+    // Registration is managed by the admin
+    client.add_arbitrator(&arbitrator);
+    assert!(client.is_arbitrator(&arbitrator));
+    assert_eq!(
+        client.try_add_arbitrator(&arbitrator),
+        Err(Ok(PaymentError::ArbitratorAlreadyExists))
+    );
+
+    // A removed arbitrator loses the role
+    client.remove_arbitrator(&arbitrator);
+    assert!(!client.is_arbitrator(&arbitrator));
+    assert_eq!(
+        client.try_resolve_dispute(&deposit_id, &arbitrator, &DisputeDecision::PaySeller),
+        Err(Ok(DisputeError::NotArbitrator))
+    );
+    assert_eq!(
+        client.try_remove_arbitrator(&arbitrator),
+        Err(Ok(PaymentError::ArbitratorNotFound))
+    );
+}
+
+#[test]
+fn test_deposit_enforces_token_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let order_ref = String::from_str(&env, "ORDER-15");
+    token.mint(&buyer, &10_000);
+
+    // Unregistered tokens are rejected
+    assert_eq!(
+        client.try_process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref),
+        Err(Ok(TransactionError::TokenNotAllowed))
+    );
+
+    // Register the token with limits
+    client.add_token(&token_contract_id, &10, &500);
+    assert_eq!(client.get_tokens(), soroban_sdk::vec![&env, token_contract_id.clone()]);
+    assert_eq!(
+        client.get_token_config(&token_contract_id),
+        Some(TokenConfig {
+            min_amount: 10,
+            max_amount: 500,
+            enabled: true,
+        })
+    );
+
+    // Amounts outside the limits are rejected
+    assert_eq!(
+        client.try_process_deposit(&token_contract_id, &buyer, &seller, &9, &order_ref),
+        Err(Ok(TransactionError::AmountOutOfRange))
+    );
+    assert_eq!(
+        client.try_process_deposit(&token_contract_id, &buyer, &seller, &501, &order_ref),
+        Err(Ok(TransactionError::AmountOutOfRange))
+    );
+    client.process_deposit(&token_contract_id, &buyer, &seller, &500, &order_ref);
+
+    // Disabled tokens are rejected until re-enabled
+    client.set_token_enabled(&token_contract_id, &false);
+    assert_eq!(
+        client.try_process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref),
+        Err(Ok(TransactionError::TokenNotAllowed))
+    );
+    client.set_token_enabled(&token_contract_id, &true);
+    client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
+
+    // Updating limits does not register the token twice
+    client.add_token(&token_contract_id, &1, &1_000);
+    assert_eq!(client.get_tokens().len(), 1);
+}
+
+#[test]
+fn test_refund_rejected_for_disabled_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let order_ref = String::from_str(&env, "ORDER-16");
+    token.mint(&buyer, &1000);
+
+    let deposit_id = client.process_deposit(&token_contract_id, &buyer, &seller, &100, &order_ref);
+
+    // Refunds are blocked while the token is disabled
+    client.set_token_enabled(&token_contract_id, &false);
+    assert_eq!(
+        client.try_process_refund(&deposit_id, &seller, &100),
+        Err(Ok(RefundError::TokenNotAllowed))
+    );
+}
+
+#[test]
+fn test_token_registry_admin_errors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = Address::generate(&env);
+
+    // Limits must be positive and ordered
+    assert_eq!(
+        client.try_add_token(&token, &0, &100),
+        Err(Ok(PaymentError::InvalidTokenLimits))
+    );
+    assert_eq!(
+        client.try_add_token(&token, &100, &99),
+        Err(Ok(PaymentError::InvalidTokenLimits))
+    );
+
+    // Unregistered tokens cannot be toggled
+    assert_eq!(
+        client.try_set_token_enabled(&token, &false),
+        Err(Ok(PaymentError::TokenNotFound))
+    );
+    assert_eq!(client.get_token_config(&token), None);
+}
+
+#[test]
+fn test_schedule_executes_due_installments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let payer = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let order_ref = String::from_str(&env, "PLAN-1");
+    token.mint(&payer, &1000);
+
+    // The payer authorizes three daily installments and the matching allowance
+    let day = 24 * 60 * 60;
+    let schedule_id = client.create_schedule(&payer, &payee, &token_contract_id, &100, &day, &3, &order_ref);
+    token_client.approve(&payer, &contract_id, &300, &1000);
+
+    // The first installment is due immediately
+    assert_eq!(client.execute_schedule(&schedule_id), 1);
+    assert_eq!(
+        client.try_execute_schedule(&schedule_id),
+        Err(Ok(ScheduleError::NothingDue))
+    );
+
+    // Two days later both remaining installments are due
+    env.ledger().with_mut(|li| li.timestamp += 2 * day);
+    assert_eq!(client.execute_schedule(&schedule_id), 2);
+
+    let schedule = client.get_schedule(&schedule_id);
+    assert_eq!(schedule.paid_installments, 3);
+    assert_eq!(schedule.status, ScheduleStatus::Completed);
+    assert_eq!(token_client.balance(&payer), 700);
+    assert_eq!(token_client.balance(&payee), 300);
+
+    // Each installment is recorded in the deposit ledger
+    assert_eq!(client.get_schedule_execution_count(&schedule_id), 3);
+    let executions = client.get_schedule_executions(&schedule_id, &0, &10);
+    assert_eq!(executions.len(), 3);
+    assert_eq!(client.get_schedule_executions(&schedule_id, &2, &10), vec![&env, executions.get(2).unwrap()]);
+    let deposit = client.get_deposit(&executions.get(2).unwrap().deposit_id.unwrap());
+    assert_eq!(deposit.payer, payer);
+    assert_eq!(deposit.amount, 100);
+    assert_eq!(deposit.order_ref, order_ref);
+
+    // A completed schedule cannot run again
+    assert_eq!(
+        client.try_execute_schedule(&schedule_id),
+        Err(Ok(ScheduleError::NotActive))
+    );
+}
+
+#[test]
+fn test_schedule_records_uncovered_installment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let payer = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let order_ref = String::from_str(&env, "PLAN-2");
+    token.mint(&payer, &1000);
+
+    // The allowance only covers the first installment
+    let day = 24 * 60 * 60;
+    let schedule_id = client.create_schedule(&payer, &payee, &token_contract_id, &100, &day, &2, &order_ref);
+    token_client.approve(&payer, &contract_id, &100, &1000);
+    assert_eq!(client.execute_schedule(&schedule_id), 1);
+
+    // The second installment fails gracefully and stays due
+    env.ledger().with_mut(|li| li.timestamp += day);
+    assert_eq!(client.execute_schedule(&schedule_id), 0);
+    assert_eq!(client.execute_schedule(&schedule_id), 0);
+
+    let executions = client.get_schedule_executions(&schedule_id, &0, &10);
+    assert_eq!(executions.len(), 2);
+    assert_eq!(executions.get(1).unwrap().installment, 2);
+    assert_eq!(executions.get(1).unwrap().deposit_id, None);
+    assert_eq!(client.get_schedule(&schedule_id).status, ScheduleStatus::Active);
+
+    // Topping up the allowance lets the retry succeed
+    token_client.approve(&payer, &contract_id, &100, &1000);
+    assert_eq!(client.execute_schedule(&schedule_id), 1);
+    let executions = client.get_schedule_executions(&schedule_id, &0, &10);
+    assert_eq!(executions.len(), 3);
+    assert!(executions.get(2).unwrap().deposit_id.is_some());
+    assert_eq!(client.get_schedule(&schedule_id).status, ScheduleStatus::Completed);
+    assert_eq!(token_client.balance(&payee), 200);
+}
+
+#[test]
+fn test_cancel_schedule_by_either_party() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let payer = Address::generate(&env);
+    let payee = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let order_ref = String::from_str(&env, "PLAN-3");
+
+    // Invalid plans are rejected
+    assert_eq!(
+        client.try_create_schedule(&payer, &payee, &token_contract_id, &100, &0, &3, &order_ref),
+        Err(Ok(ScheduleError::InvalidSchedule))
+    );
+    assert_eq!(
+        client.try_create_schedule(&payer, &payee, &token_contract_id, &100, &60, &(MAX_INSTALLMENTS + 1), &order_ref),
+        Err(Ok(ScheduleError::InvalidSchedule))
+    );
+
+    let schedule_id = client.create_schedule(&payer, &payee, &token_contract_id, &100, &60, &3, &order_ref);
+
+    // Only the payer or payee can cancel
+    assert_eq!(
+        client.try_cancel_schedule(&schedule_id, &stranger),
+        Err(Ok(ScheduleError::UnauthorizedAccess))
+    );
+    client.cancel_schedule(&schedule_id, &payee);
+    assert_eq!(client.get_schedule(&schedule_id).status, ScheduleStatus::Cancelled);
+
+    // Cancelled schedules no longer execute
+    assert_eq!(
+        client.try_execute_schedule(&schedule_id),
+        Err(Ok(ScheduleError::NotActive))
+    );
+    assert_eq!(
+        client.try_cancel_schedule(&schedule_id, &payer),
+        Err(Ok(ScheduleError::NotActive))
+    );
+}
+
+#[test]
+fn test_batch_payout_reports_per_recipient_results() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    client.add_token(&token_contract_id, &10, &500);
+
+    // Setup test accounts
+    let marketplace = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let seller_3 = Address::generate(&env);
+    let order_ref = String::from_str(&env, "SETTLE-1");
+    token.mint(&marketplace, &1000);
+
+    let payouts = vec![
+        &env,
+        Payout { recipient: seller_1.clone(), amount: 300 },
+        Payout { recipient: seller_2.clone(), amount: 0 },
+        Payout { recipient: seller_3.clone(), amount: 600 },
+        Payout { recipient: marketplace.clone(), amount: 50 },
+        Payout { recipient: seller_2.clone(), amount: 200 },
+    ];
+    let results = client.process_batch(&token_contract_id, &marketplace, &payouts, &order_ref);
+
+    // Rejected payouts are reported without aborting the batch
+    assert_eq!(results.len(), 5);
+    assert_eq!(results.get(0).unwrap().status, PayoutStatus::Paid);
+    assert_eq!(results.get(1).unwrap().status, PayoutStatus::InvalidAmount);
+    assert_eq!(results.get(2).unwrap().status, PayoutStatus::AmountOutOfRange);
+    assert_eq!(results.get(3).unwrap().status, PayoutStatus::UnauthorizedAccess);
+    assert_eq!(results.get(4).unwrap().status, PayoutStatus::Paid);
+    assert_eq!(results.get(1).unwrap().deposit_id, None);
+
+    // Verify balances
+    assert_eq!(token_client.balance(&marketplace), 500);
+    assert_eq!(token_client.balance(&seller_1), 300);
+    assert_eq!(token_client.balance(&seller_2), 200);
+    assert_eq!(token_client.balance(&seller_3), 0);
+
+    // Each paid payout is recorded in the deposit ledger
+    assert_eq!(client.get_deposit_count(), 2);
+    let record = client.get_deposit(&results.get(4).unwrap().deposit_id.unwrap());
+    assert_eq!(record.payer, marketplace);
+    assert_eq!(record.payee, seller_2);
+    assert_eq!(record.amount, 200);
+    assert_eq!(record.order_ref, order_ref);
+}
+
+#[test]
+fn test_batch_payout_rejects_underfunded_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let marketplace = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let order_ref = String::from_str(&env, "SETTLE-2");
+    token.mint(&marketplace, &500);
+
+    let payouts = vec![
+        &env,
+        Payout { recipient: seller_1.clone(), amount: 300 },
+        Payout { recipient: seller_2.clone(), amount: 300 },
+    ];
+
+    // Unregistered tokens are rejected before any payout
+    assert_eq!(
+        client.try_process_batch(&token_contract_id, &marketplace, &payouts, &order_ref),
+        Err(Ok(BatchError::TokenNotAllowed))
+    );
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // The batch total exceeds the balance, so nothing is paid
+    assert_eq!(
+        client.try_process_batch(&token_contract_id, &marketplace, &payouts, &order_ref),
+        Err(Ok(BatchError::InsufficientFunds))
+    );
+    assert_eq!(
+        client.try_process_batch(&token_contract_id, &marketplace, &vec![&env], &order_ref),
+        Err(Ok(BatchError::EmptyBatch))
+    );
+    assert_eq!(token_client.balance(&marketplace), 500);
+    assert_eq!(client.get_deposit_count(), 0);
+
+    // Paused contracts reject batches
+    client.pause();
+    assert_eq!(
+        client.try_process_batch(&token_contract_id, &marketplace, &payouts, &order_ref),
+        Err(Ok(BatchError::ContractPaused))
+    );
+}
+
+#[test]
+fn test_batch_payout_size_fits_in_one_transaction() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&token_contract_id, &1, &1_000_000);
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let marketplace = Address::generate(&env);
+    let order_ref = String::from_str(&env, "SETTLE-4");
+    token.mint(&marketplace, &10_000);
+
+    let mut payouts = Vec::new(&env);
+    for _ in 0..MAX_BATCH_SIZE {
+        payouts.push_back(Payout { recipient: Address::generate(&env), amount: 100 });
+    }
+
+    // A full batch stays within the per-transaction ledger write limit
+    client.process_batch(&token_contract_id, &marketplace, &payouts, &order_ref);
+    assert!(env.cost_estimate().resources().write_entries <= 25);
+    assert_eq!(client.get_deposit_count(), MAX_BATCH_SIZE as u64);
+
+    // One more payout is rejected
+    payouts.push_back(Payout { recipient: Address::generate(&env), amount: 100 });
+    assert_eq!(
+        client.try_process_batch(&token_contract_id, &marketplace, &payouts, &order_ref),
+        Err(Ok(BatchError::BatchTooLarge))
+    );
+}
+
+#[test]
+fn test_batch_payout_reports_failed_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let marketplace = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let frozen_seller = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let order_ref = String::from_str(&env, "SETTLE-3");
+    token.mint(&marketplace, &1000);
+
+    // The frozen seller's balance can no longer receive the token
+    stellar_asset.issuer().set_flag(IssuerFlags::RevocableFlag);
+    token.mint(&frozen_seller, &0);
+    token.set_authorized(&frozen_seller, &false);
+
+    let payouts = vec![
+        &env,
+        Payout { recipient: seller_1.clone(), amount: 300 },
+        Payout { recipient: frozen_seller.clone(), amount: 200 },
+        Payout { recipient: seller_2.clone(), amount: 100 },
+    ];
+    let results = client.process_batch(&token_contract_id, &marketplace, &payouts, &order_ref);
+
+    // The failed transfer is reported and the other payouts still go through
+    assert_eq!(results.get(0).unwrap().status, PayoutStatus::Paid);
+    assert_eq!(results.get(1).unwrap().status, PayoutStatus::TransferFailed);
+    assert_eq!(results.get(1).unwrap().deposit_id, None);
+    assert_eq!(results.get(2).unwrap().status, PayoutStatus::Paid);
+
+    // Verify balances
+    assert_eq!(token_client.balance(&marketplace), 600);
+    assert_eq!(token_client.balance(&seller_1), 300);
+    assert_eq!(token_client.balance(&frozen_seller), 0);
+    assert_eq!(token_client.balance(&seller_2), 100);
+
+    // Only the paid payouts are recorded in the deposit ledger
+    assert_eq!(client.get_deposit_count(), 2);
+}
+
+#[test]
+fn test_pay_invoice_by_named_buyer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let memo_hash = BytesN::from_array(&env, &[7u8; 32]);
+    token.mint(&buyer, &1000);
+    token.mint(&stranger, &1000);
+
+    let invoice_id = client.create_invoice(
+        &seller,
+        &Some(buyer.clone()),
+        &token_contract_id,
+        &250,
+        &(env.ledger().timestamp() + 3600),
+        &memo_hash,
+    );
+
+    // Only the named buyer can settle the invoice
+    assert_eq!(
+        client.try_pay_invoice(&invoice_id, &stranger),
+        Err(Ok(InvoiceError::UnauthorizedAccess))
+    );
+    let deposit_id = client.pay_invoice(&invoice_id, &buyer);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.paid_by, Some(buyer.clone()));
+    assert_eq!(invoice.deposit_id, Some(deposit_id));
+    assert_eq!(invoice.memo_hash, memo_hash);
+    assert_eq!(token_client.balance(&buyer), 750);
+    assert_eq!(token_client.balance(&seller), 250);
+
+    // The payment goes through the deposit ledger
+    let record = client.get_deposit(&deposit_id);
+    assert_eq!(record.payer, buyer);
+    assert_eq!(record.payee, seller);
+    assert_eq!(record.amount, 250);
+    assert_eq!(record.order_ref, String::from_str(&env, "INV-1"));
+
+    // An invoice can only be paid once
+    assert_eq!(
+        client.try_pay_invoice(&invoice_id, &buyer),
+        Err(Ok(InvoiceError::NotOpen))
+    );
+    assert_eq!(client.get_seller_invoices(&seller, &0, &10), vec![&env, invoice_id]);
+    assert_eq!(client.get_buyer_invoices(&buyer, &0, &10), vec![&env, invoice_id]);
+}
+
+#[test]
+fn test_open_invoice_overdue_detection() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let seller = Address::generate(&env);
+    let payer = Address::generate(&env);
+    let memo_hash = BytesN::from_array(&env, &[1u8; 32]);
+    let due_date = env.ledger().timestamp() + 3600;
+    token.mint(&payer, &1000);
+
+    // Due dates must lie in the future
+    assert_eq!(
+        client.try_create_invoice(&seller, &None, &token_contract_id, &100, &env.ledger().timestamp(), &memo_hash),
+        Err(Ok(InvoiceError::InvalidDueDate))
+    );
+    let invoice_id = client.create_invoice(&seller, &None, &token_contract_id, &100, &due_date, &memo_hash);
+    assert!(!client.is_invoice_overdue(&invoice_id));
+
+    // Past the due date the invoice is overdue but still payable
+    env.ledger().with_mut(|li| li.timestamp = due_date + 1);
+    assert!(client.is_invoice_overdue(&invoice_id));
+
+    // Open invoices can be paid by anyone and are indexed under the payer
+    assert_eq!(client.get_buyer_invoice_count(&payer), 0);
+    client.pay_invoice(&invoice_id, &payer);
+    assert!(!client.is_invoice_overdue(&invoice_id));
+    assert_eq!(client.get_buyer_invoices(&payer, &0, &10), vec![&env, invoice_id]);
+}
+
+#[test]
+fn test_cancel_invoice_by_seller_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentContract, ());
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let memo_hash = BytesN::from_array(&env, &[2u8; 32]);
+    let due_date = env.ledger().timestamp() + 3600;
+
+    let first = client.create_invoice(&seller, &Some(buyer.clone()), &token_contract_id, &100, &due_date, &memo_hash);
+    let second = client.create_invoice(&seller, &None, &token_contract_id, &200, &due_date, &memo_hash);
+    assert_eq!(client.get_seller_invoices(&seller, &0, &10), vec![&env, first, second]);
+    assert_eq!(client.get_seller_invoice_count(&seller), 2);
+
+    // Pages skip `start` ids and stop at the end of the list
+    assert_eq!(client.get_seller_invoices(&seller, &1, &10), vec![&env, second]);
+    assert_eq!(client.get_seller_invoices(&seller, &0, &1), vec![&env, first]);
+    assert_eq!(client.get_seller_invoices(&seller, &2, &10).len(), 0);
+    assert_eq!(client.get_buyer_invoices(&buyer, &0, &10), vec![&env, first]);
+
+    // Only the issuing seller can cancel
+    assert_eq!(
+        client.try_cancel_invoice(&first, &buyer),
+        Err(Ok(InvoiceError::UnauthorizedAccess))
+    );
+    client.cancel_invoice(&first, &seller);
+    assert_eq!(client.get_invoice(&first).status, InvoiceStatus::Cancelled);
+
+    // Cancelled invoices cannot be paid or cancelled again
+    assert_eq!(
+        client.try_pay_invoice(&first, &buyer),
+        Err(Ok(InvoiceError::NotOpen))
+    );
+    assert_eq!(
+        client.try_cancel_invoice(&first, &seller),
+        Err(Ok(InvoiceError::NotOpen))
+    );
+    assert!(!client.is_invoice_overdue(&first));
+    assert_eq!(
+        client.try_get_invoice(&99),
+        Err(Ok(InvoiceError::InvoiceNotFound))
+    );
 }
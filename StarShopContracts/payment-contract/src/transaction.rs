@@ -1,5 +1,8 @@
+use crate::registry::{check_payment, TokenRejection};
+use crate::storage::DataKey;
+use crate::{PaymentContract, PaymentContractArgs, PaymentContractClient};
 use soroban_sdk::token::Client as TokenClient;
-use soroban_sdk::{contract, contracterror, contractimpl, symbol_short, Address, Env};
+use soroban_sdk::{contracterror, contractimpl, contracttype, symbol_short, Address, Env, String};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -8,47 +11,282 @@ pub enum TransactionError {
     TransferFailed = 2,
     InvalidAmount = 3,
     UnauthorizedAccess = 4,
+    DepositNotFound = 5,
+    ContractPaused = 6,
+    TokenNotAllowed = 7,
+    AmountOutOfRange = 8,
+    NotHeld = 9,
 }
 
-#[contract]
-pub struct TransactionContract;
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DepositStatus {
+    Completed,         // Paid to the payee, nothing refunded
+    PartiallyRefunded, // Part of the deposit returned to the payer
+    Refunded,          // Whole deposit returned to the payer
+    Resolved,          // Settled by an arbitrator's dispute decision
+    Held,              // Held by the contract until the payer releases it or a dispute settles it
+}
+
+/// Ledger entry recorded for every processed deposit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositRecord {
+    pub id: u64,
+    pub payer: Address,
+    pub payee: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub refunded_amount: i128,
+    pub status: DepositStatus,
+    pub timestamp: u64,
+    pub order_ref: String,
+}
+
+impl DepositRecord {
+    /// Amount that can still be refunded or settled by a dispute
+    pub fn remaining(&self) -> i128 {
+        match self.status {
+            DepositStatus::Completed | DepositStatus::PartiallyRefunded | DepositStatus::Held => {
+                self.amount - self.refunded_amount
+            }
+            DepositStatus::Refunded | DepositStatus::Resolved => 0,
+        }
+    }
+}
 
 #[contractimpl]
-impl TransactionContract {
+impl PaymentContract {
+    /// Transfers funds from the signer to the payee and records the deposit
     pub fn process_deposit(
         e: Env,
         token_id: Address,
         signer: Address,
         to: Address,
         amount_to_deposit: i128,
-    ) -> Result<(), TransactionError> {
+        order_ref: String,
+    ) -> Result<u64, TransactionError> {
         // Check authorization
         signer.require_auth();
 
-        // Input validations
-        if amount_to_deposit <= 0 {
-            return Err(TransactionError::InvalidAmount);
-        }
+        transfer_and_record(&e, &token_id, &signer, &to, amount_to_deposit, order_ref)
+    }
 
-        if signer == to {
-            return Err(TransactionError::UnauthorizedAccess);
-        }
+    /// Transfers funds from the signer into the contract's custody for the payee
+    ///
+    /// The deposit stays disputable until the payer releases it to the payee
+    /// or a registered arbitrator settles it with `resolve_dispute`.
+    pub fn process_escrow_deposit(
+        e: Env,
+        token_id: Address,
+        signer: Address,
+        to: Address,
+        amount_to_deposit: i128,
+        order_ref: String,
+    ) -> Result<u64, TransactionError> {
+        // Check authorization
+        signer.require_auth();
+
+        check_deposit(&e, &token_id, &signer, &to, amount_to_deposit)?;
 
+        // Process transfer into the contract's custody
         let token = TokenClient::new(&e, &token_id);
+        token.transfer(&signer, &e.current_contract_address(), &amount_to_deposit);
+
+        // Record the deposit in the ledger
+        let deposit_id = record_deposit(
+            &e,
+            &signer,
+            &to,
+            &token_id,
+            amount_to_deposit,
+            order_ref,
+            DepositStatus::Held,
+        );
+
+        // Emit event
+        e.events().publish(
+            (symbol_short!("escrow"), deposit_id),
+            (signer, to, amount_to_deposit),
+        );
+
+        Ok(deposit_id)
+    }
+
+    /// Releases a deposit held by the contract to its payee
+    ///
+    /// Only the deposit's payer may release it.
+    pub fn release_deposit(
+        e: Env,
+        deposit_id: u64,
+        payer: Address,
+    ) -> Result<(), TransactionError> {
+        // Check authorization
+        payer.require_auth();
 
-        // Check balance
-        let signer_balance = token.balance(&signer);
-        if signer_balance < amount_to_deposit {
-            return Err(TransactionError::InsufficientFunds);
+        if Self::is_paused(e.clone()) {
+            return Err(TransactionError::ContractPaused);
         }
 
-        // Process transfer
-        token.transfer(&signer, &to, &amount_to_deposit);
+        let deposit = load_deposit(&e, deposit_id).ok_or(TransactionError::DepositNotFound)?;
+        if deposit.payer != payer {
+            return Err(TransactionError::UnauthorizedAccess);
+        }
+        if deposit.status != DepositStatus::Held {
+            return Err(TransactionError::NotHeld);
+        }
+
+        // Process transfer out of the contract's custody
+        let token = TokenClient::new(&e, &deposit.token);
+        token.transfer(
+            &e.current_contract_address(),
+            &deposit.payee,
+            &deposit.amount,
+        );
+
+        // Update the ledger
+        let payee = deposit.payee.clone();
+        let amount = deposit.amount;
+        save_deposit(
+            &e,
+            &DepositRecord {
+                status: DepositStatus::Completed,
+                ..deposit
+            },
+        );
 
         // Emit event
-        e.events()
-            .publish((symbol_short!("deposit"),), (signer, to, amount_to_deposit));
+        e.events().publish(
+            (symbol_short!("release"), deposit_id),
+            (payer, payee, amount),
+        );
 
         Ok(())
     }
+
+    /// Returns the ledger record of a deposit
+    pub fn get_deposit(e: Env, deposit_id: u64) -> Result<DepositRecord, TransactionError> {
+        load_deposit(&e, deposit_id).ok_or(TransactionError::DepositNotFound)
+    }
+
+    /// Returns the number of deposits recorded so far
+    pub fn get_deposit_count(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::DepositCounter)
+            .unwrap_or(0)
+    }
+}
+
+/// Validates and performs a deposit whose signer has already authorized it
+pub(crate) fn transfer_and_record(
+    e: &Env,
+    token_id: &Address,
+    signer: &Address,
+    to: &Address,
+    amount_to_deposit: i128,
+    order_ref: String,
+) -> Result<u64, TransactionError> {
+    check_deposit(e, token_id, signer, to, amount_to_deposit)?;
+
+    let token = TokenClient::new(e, token_id);
+
+    // Process transfer
+    token.transfer(signer, to, &amount_to_deposit);
+
+    // Record the deposit in the ledger
+    let deposit_id = record_deposit(
+        e,
+        signer,
+        to,
+        token_id,
+        amount_to_deposit,
+        order_ref,
+        DepositStatus::Completed,
+    );
+
+    // Emit event
+    e.events().publish(
+        (symbol_short!("deposit"), deposit_id),
+        (signer.clone(), to.clone(), amount_to_deposit),
+    );
+
+    Ok(deposit_id)
+}
+
+/// Validates a deposit whose signer has already authorized it
+fn check_deposit(
+    e: &Env,
+    token_id: &Address,
+    signer: &Address,
+    to: &Address,
+    amount_to_deposit: i128,
+) -> Result<(), TransactionError> {
+    if PaymentContract::is_paused(e.clone()) {
+        return Err(TransactionError::ContractPaused);
+    }
+
+    // Input validations
+    if amount_to_deposit <= 0 {
+        return Err(TransactionError::InvalidAmount);
+    }
+
+    if signer == to {
+        return Err(TransactionError::UnauthorizedAccess);
+    }
+
+    // Only registered, enabled tokens within their limits are accepted
+    check_payment(e, token_id, amount_to_deposit).map_err(|rejection| match rejection {
+        TokenRejection::NotAllowed => TransactionError::TokenNotAllowed,
+        TokenRejection::OutOfRange => TransactionError::AmountOutOfRange,
+    })?;
+
+    let token = TokenClient::new(e, token_id);
+
+    // Check balance
+    let signer_balance = token.balance(signer);
+    if signer_balance < amount_to_deposit {
+        return Err(TransactionError::InsufficientFunds);
+    }
+
+    Ok(())
+}
+
+/// Adds a transfer to the ledger and returns its deposit id
+pub(crate) fn record_deposit(
+    e: &Env,
+    payer: &Address,
+    payee: &Address,
+    token: &Address,
+    amount: i128,
+    order_ref: String,
+    status: DepositStatus,
+) -> u64 {
+    let deposit_id = PaymentContract::get_deposit_count(e.clone()) + 1;
+    let record = DepositRecord {
+        id: deposit_id,
+        payer: payer.clone(),
+        payee: payee.clone(),
+        token: token.clone(),
+        amount,
+        refunded_amount: 0,
+        status,
+        timestamp: e.ledger().timestamp(),
+        order_ref,
+    };
+    e.storage()
+        .instance()
+        .set(&DataKey::DepositCounter, &deposit_id);
+    save_deposit(e, &record);
+    deposit_id
+}
+
+pub(crate) fn load_deposit(e: &Env, deposit_id: u64) -> Option<DepositRecord> {
+    e.storage().persistent().get(&DataKey::Deposit(deposit_id))
+}
+
+pub(crate) fn save_deposit(e: &Env, record: &DepositRecord) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::Deposit(record.id), record);
 }
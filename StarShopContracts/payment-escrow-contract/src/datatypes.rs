@@ -1,8 +1,57 @@
 use soroban_sdk::{
-    Address,
-    contracterror, contracttype, String
+    Address, BytesN,
+    contracterror, contracttype, String, Vec
 };
 
+/// Basis point denominator used for percentage based splits
+pub const MAX_BPS: u32 = 10_000;
+
+/// Default time a buyer has to inspect a delivered order (3 days)
+pub const DEFAULT_INSPECTION_PERIOD: u64 = 3 * 24 * 60 * 60;
+
+/// Maximum configurable inspection period (30 days)
+pub const MAX_INSPECTION_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+/// Policy id of the built-in default escrow policy
+pub const DEFAULT_POLICY_ID: u32 = 0;
+
+/// Upper bound for any registered policy's maximum expiry (365 days)
+/// 
+/// The built-in default policy keeps the original unbounded expiry.
+pub const MAX_EXPIRY_DAYS: u32 = 365;
+
+/// Maximum number of payments returned by a single paginated query
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of line items in a single order
+pub const MAX_ORDER_ITEMS: u32 = 20;
+
+/// Maximum number of evidence entries that can be attached to one dispute
+pub const MAX_EVIDENCE_ENTRIES: u32 = 20;
+
+/// Maximum length in bytes of an evidence URI
+pub const MAX_EVIDENCE_URI_LEN: u32 = 256;
+
+/// Time an arbitrator panel has to reach quorum before any registered arbitrator can rule (7 days)
+pub const PANEL_VOTING_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+/// Storage schema version written by this contract code
+/// 
+/// Version 1 stored payments under their raw `u128` id; version 2 stores them
+/// under `DataKey::Payment`.
+pub const PAYMENT_SCHEMA_VERSION: u32 = 2;
+
+/// Maximum number of payments rewritten by a single migration call
+pub const MAX_MIGRATION_BATCH: u32 = 50;
+
+/// Expected seconds between ledgers, used to convert deadlines into TTLs
+pub const LEDGER_CLOSE_SECONDS: u64 = 5;
+
+/// Time full payment records are retained after their last deadline or closure (90 days)
+pub const RETENTION_PERIOD: u64 = 90 * 24 * 60 * 60;
+
+/// Extra lifetime after the retention period during which a closed payment can be archived (30 days)
+pub const ARCHIVE_GRACE_PERIOD: u64 = 30 * 24 * 60 * 60;
 
 #[derive(Clone)]
 #[contracttype]
@@ -15,6 +64,29 @@ pub enum DataKey {
     ResolvedDisputes,
     SellerRegId(Address),
     PaymentCounter,
+    FeeConfig,
+    TokenFeeBps(Address),
+    AccruedFees(Address),
+    Evidence(u128),
+    PanelConfig,
+    DisputePanel(u128),
+    InspectionPeriod,
+    IndexLen(PaymentIndex),
+    IndexEntry(PaymentIndex, u32),
+    IndexSlots(u128),
+    Order(u128),
+    OrderCounter,
+    Policy(u32),
+    CancellationRequest(u128),
+    Amendment(u128),
+    TokenConfig(Address),
+    Tokens,
+    Payment(u128),
+    SchemaVersion,
+    MigrationCursor,
+    PaymentClosedAt(u128),
+    ArchivedPayment(u128),
+    Admin,
 }
 
 // Error definitions
@@ -41,6 +113,30 @@ pub enum PaymentEscrowError {
     NotSeller = 17,
     ArbitratorAlreadyExists = 18,
     PaymentDisputed = 19,
+    TrancheNotFound = 20,
+    TrancheAlreadyReleased = 21,
+    InvalidSplit = 22,
+    InvalidFee = 23,
+    FeeNotConfigured = 24,
+    EvidenceLimitReached = 25,
+    EvidenceTooLarge = 26,
+    NotPanelArbitrator = 27,
+    AlreadyVoted = 28,
+    InvalidPanelConfig = 29,
+    DeliveredNotClaimable = 30,
+    InspectionPeriodActive = 31,
+    InvalidPeriod = 32,
+    TooManyItems = 33,
+    InvalidPolicy = 34,
+    PolicyNotFound = 35,
+    RequestNotFound = 36,
+    RequestAlreadyOpen = 37,
+    TokenNotAccepted = 38,
+    AmountOutOfRange = 39,
+    InvalidBatchSize = 40,
+    PaymentNotTerminal = 41,
+    RetentionActive = 42,
+    NotAdmin = 43,
 }
 
 // Status Enum
@@ -48,20 +144,47 @@ pub enum PaymentEscrowError {
 #[derive(Clone, PartialEq, Debug)]
 pub enum PaymentStatus {
     Pending,      // Funds held in contract
+    PartiallyReleased, // Some tranches released, remainder held
     Delivered,    // Buyer has confirmed delivery
     Completed,    // Funds released to seller
     Disputed,     // Funds locked, awaiting resolution
     Refunded,     // Funds returned to buyer
     Expired,      // Auto-refunded due to timeout
+    SplitResolved, // Funds divided between buyer and seller by arbitrator
+    Cancelled,    // Cancelled by mutual consent before shipping, funds returned to buyer
+}
+
+
+
+// Payment listing indexes
+// Each index is an append-only list of slots stored one payment id per key,
+// so writes never rewrite the whole list and slot numbers never shift
+#[contracttype]
+#[derive(Clone, PartialEq, Debug)]
+pub enum PaymentIndex {
+    Buyer(Address),
+    Seller(Address),
+    Status(PaymentStatus),
+}
+
+
+// Slots a payment occupies in its buyer, seller and current status index
+#[contracttype]
+#[derive(Clone, PartialEq, Debug)]
+pub struct PaymentIndexSlots {
+    pub buyer: u32,
+    pub seller: u32,
+    pub status: u32,
 }
 
 
 
 #[contracttype]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum DisputeDecision {
-    RefundBuyer = 0,
-    PaySeller = 1,
+    RefundBuyer,
+    PaySeller,
+    Split(u32), // Basis points of the remainder refunded to the buyer
 }
 
 
@@ -80,6 +203,74 @@ pub struct DisputeResolvedEvent {
     pub order_id: u128,
     pub resolution: DisputeDecision,
     pub admin: Address,
+    pub buyer_amount: i128,
+    pub seller_amount: i128,
+    pub evidence_count: u32,
+    pub arbitrator_fee: i128,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CancellationRequest {
+    pub requested_by: Address,
+    pub reason: String,
+    pub requested_at: u64,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CancellationEvent {
+    pub payment_id: u128,
+    pub buyer: Address,
+    pub seller: Address,
+    pub refunded_amount: i128,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmendmentProposal {
+    pub proposer: Address,
+    pub new_amount: i128,
+    pub new_expiry: u64,
+    pub escrowed_top_up: i128, // Increase already pulled from a proposing buyer
+    pub proposed_at: u64,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmendmentEvent {
+    pub payment_id: u128,
+    pub proposer: Address,
+    pub approver: Address,
+    pub old_amount: i128,
+    pub new_amount: i128,
+    pub old_expiry: u64,
+    pub new_expiry: u64,
+    pub top_up: i128,
+    pub refund: i128,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evidence {
+    pub submitter: Address,
+    pub content_hash: BytesN<32>,
+    pub uri: String,
+    pub timestamp: u64,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReleaseTranche {
+    pub amount: i128,
+    pub description: String,
+    pub released: bool,
 }
 
 
@@ -96,6 +287,150 @@ pub struct Payment {
     pub expiry: u64,
     pub dispute_deadline: u64,
     pub description: String,
+    pub tranches: Vec<ReleaseTranche>,
+    pub released_amount: i128,
+    pub remaining_amount: i128,
+    pub fee_bps: u32,
+    pub inspection_deadline: u64,
+    pub order_id: Option<u128>,
+    pub policy_id: u32,
+    pub inspection_period: u64,
+}
+
+
+// Payment record as written by schema version 1, under its raw `u128` id
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyPayment {
+    pub id: u128,
+    pub buyer: Address,
+    pub seller: Address,
+    pub amount: i128,
+    pub token: Address,
+    pub status: PaymentStatus,
+    pub created_at: u64,
+    pub expiry: u64,
+    pub dispute_deadline: u64,
+    pub description: String,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OrderItem {
+    pub seller: Address,
+    pub amount: i128,
+    pub description: String,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Order {
+    pub id: u128,
+    pub buyer: Address,
+    pub token: Address,
+    pub total_amount: i128,
+    pub payment_ids: Vec<u128>, // One escrow payment per line item
+    pub created_at: u64,
+    pub expiry: u64,
+    pub dispute_deadline: u64,
+    pub description: String,
+    pub policy_id: u32,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DisputeWindow {
+    AfterCreation(u32), // Disputes accepted for this many days after creation, capped at expiry
+    BeforeExpiry(u32),  // Disputes close this many days before expiry; shorter payments keep their full period
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyProfile {
+    pub default_expiry_days: u32,  // Expiry used when a payment requests 0 days
+    pub max_expiry_days: u32,      // Longest expiry a payment may request
+    pub dispute_window: DisputeWindow, // When disputes stop being accepted
+    pub inspection_period: u64,    // Seconds the buyer has to inspect a delivered order
+}
+
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenConfig {
+    pub min_amount: i128,  // Smallest payment accepted in this token
+    pub max_amount: i128,  // Largest payment accepted in this token
+    pub enabled: bool,     // Whether new payments may use this token
+}
+
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArchivedPayment {
+    pub id: u128,
+    pub buyer: Address,
+    pub seller: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub released_amount: i128, // Total paid out to the seller
+    pub status: PaymentStatus, // Final status of the payment
+    pub created_at: u64,
+    pub closed_at: u64,
+    pub order_id: Option<u128>,
+}
+
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationStatus {
+    pub schema_version: u32,   // Layout all payments are guaranteed to use
+    pub migrated_up_to: u128,  // Highest payment id checked by the migration so far
+    pub payment_count: u128,   // Payments created so far
+    pub complete: bool,        // Whether every payment uses the current layout
+}
+
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeConfig {
+    pub fee_bps: u32,
+    pub recipient: Address,
+}
+
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanelConfig {
+    pub min_amount: i128,          // Disputed remainder at which a panel is assigned
+    pub panel_size: u32,           // Number of arbitrators assigned to each panel
+    pub arbitrator_fee_bps: u32,   // Share of the remainder paid to voting arbitrators
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanelVote {
+    pub arbitrator: Address,
+    pub decision: DisputeDecision,
+}
+
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputePanel {
+    pub arbitrators: Vec<Address>,
+    pub votes: Vec<PanelVote>,
+    pub quorum: u32,
+    pub arbitrator_fee_bps: u32,
+    pub voting_deadline: u64,
 }
 
 
@@ -124,4 +459,8 @@ pub struct DeliveryDetails {
     pub created_at: u64,
     pub expiry: u64,
     pub description: String,
+    pub inspection_deadline: u64,
+    pub policy_id: u32,
+    pub dispute_deadline: u64,
+    pub inspection_period: u64,
 }
\ No newline at end of file
@@ -0,0 +1,505 @@
+use crate::{
+    datatypes::{
+        AmendmentEvent, AmendmentProposal, CancellationEvent, CancellationRequest, DataKey, Order,
+        Payment, PaymentEscrowError, PaymentStatus,
+    },
+    implementations::{
+        policy::dispute_deadline, query::{read_payment, save_payment}, registry::check_token_amount,
+        retention::extend_payment_ttl,
+    },
+    interface::{AmendmentInterface, PolicyInterface},
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::token::Client as TokenClient;
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, String};
+
+/// Implementation of the AmendmentInterface trait for PaymentEscrowContract
+/// This module handles mutual-consent changes to pending escrows: cancelling
+/// a payment before the seller ships, and amending its amount or expiry.
+#[contractimpl]
+impl AmendmentInterface for PaymentEscrowContract {
+
+    /// Buyer asks the seller to cancel a pending payment
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `buyer` - The buyer requesting the cancellation
+    /// * `reason` - Human-readable reason for the cancellation
+    ///
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    ///
+    /// # Security
+    /// * Requires buyer authentication
+    /// * Only pending payments can be cancelled
+    /// * Only one cancellation request can be open per payment
+    fn request_cancellation(
+        env: Env,
+        payment_id: u128,
+        buyer: Address,
+        reason: String,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - buyer must authorize this transaction
+        buyer.require_auth();
+
+        let payment = load_payment(&env, payment_id)?;
+
+        // Authorization check: only the buyer can request a cancellation
+        if payment.buyer != buyer {
+            return Err(PaymentEscrowError::UnauthorizedAccess);
+        }
+
+        // Status validation: once shipped, disputes replace cancellation
+        if payment.status != PaymentStatus::Pending {
+            return Err(PaymentEscrowError::NotValid);
+        }
+
+        let key = DataKey::CancellationRequest(payment_id);
+        if env.storage().persistent().has(&key) {
+            return Err(PaymentEscrowError::RequestAlreadyOpen);
+        }
+
+        let request = CancellationRequest {
+            requested_by: buyer,
+            reason,
+            requested_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&key, &request);
+        extend_payment_ttl(&env, &payment);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("cncl_req"), payment_id), request);
+
+        Ok(())
+    }
+
+    /// Seller accepts the buyer's cancellation request and the buyer is refunded
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `seller` - The seller accepting the cancellation
+    ///
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    ///
+    /// # Business Logic
+    /// * Refunds the escrowed amount to the buyer immediately
+    /// * Refunds any top-up the buyer escrowed for an open amendment
+    /// * Marks the payment as Cancelled
+    ///
+    /// # Security
+    /// * Requires seller authentication
+    /// * Only pending payments with an open request can be cancelled
+    fn accept_cancellation(env: Env, payment_id: u128, seller: Address) -> Result<(), PaymentEscrowError> {
+        // Authentication - seller must authorize this transaction
+        seller.require_auth();
+
+        let payment = load_payment(&env, payment_id)?;
+
+        // Authorization check: only the seller can accept a cancellation
+        if payment.seller != seller {
+            return Err(PaymentEscrowError::NotSeller);
+        }
+
+        // Status validation: the seller may have shipped since the request
+        if payment.status != PaymentStatus::Pending {
+            return Err(PaymentEscrowError::NotValid);
+        }
+
+        let key = DataKey::CancellationRequest(payment_id);
+        if !env.storage().persistent().has(&key) {
+            return Err(PaymentEscrowError::RequestNotFound);
+        }
+        env.storage().persistent().remove(&key);
+
+        // A cancelled payment can no longer be amended
+        // Return any top-up the buyer escrowed for a pending amendment
+        let top_up = take_amendment(&env, payment_id)
+            .map(|proposal| proposal.escrowed_top_up)
+            .unwrap_or(0);
+        let refunded_amount = payment.remaining_amount + top_up;
+
+        // Transfer the escrowed funds back to the buyer
+        let token_client = TokenClient::new(&env, &payment.token);
+        token_client.transfer(&env.current_contract_address(), &payment.buyer, &refunded_amount);
+
+        let updated_payment = Payment {
+            status: PaymentStatus::Cancelled,
+            remaining_amount: 0,
+            ..payment
+        };
+        save_payment(&env, &updated_payment);
+        extend_payment_ttl(&env, &updated_payment);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events().publish(
+            (symbol_short!("cancelled"), payment_id),
+            CancellationEvent {
+                payment_id,
+                buyer: updated_payment.buyer,
+                seller,
+                refunded_amount,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Closes an open cancellation request without cancelling
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `caller` - The seller declining, or the buyer withdrawing, the request
+    ///
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    fn reject_cancellation(env: Env, payment_id: u128, caller: Address) -> Result<(), PaymentEscrowError> {
+        // Authentication - caller must authorize this transaction
+        caller.require_auth();
+
+        let payment = load_payment(&env, payment_id)?;
+
+        // Authorization check: only the buyer or seller can close the request
+        if caller != payment.buyer && caller != payment.seller {
+            return Err(PaymentEscrowError::UnauthorizedAccess);
+        }
+
+        let key = DataKey::CancellationRequest(payment_id);
+        if !env.storage().persistent().has(&key) {
+            return Err(PaymentEscrowError::RequestNotFound);
+        }
+        env.storage().persistent().remove(&key);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("cncl_rej"), payment_id), caller);
+
+        Ok(())
+    }
+
+    /// Retrieves the open cancellation request for a payment, if any
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    ///
+    /// # Returns
+    /// * `Option<CancellationRequest>` - The open request, if any
+    fn get_cancellation_request(env: Env, payment_id: u128) -> Option<CancellationRequest> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CancellationRequest(payment_id))
+    }
+
+    /// Proposes a new amount and expiry for a pending payment
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `proposer` - The buyer or seller proposing the change
+    /// * `new_amount` - The proposed escrow amount
+    /// * `new_expiry` - The proposed expiry timestamp
+    ///
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    ///
+    /// # Business Logic
+    /// * A buyer proposing a higher amount escrows the increase immediately
+    /// * The increase is returned if the proposal is rejected
+    /// * The new expiry must be in the future and within the payment's policy maximum
+    ///
+    /// # Security
+    /// * Requires proposer authentication
+    /// * Only pending payments can be amended
+    /// * Only one amendment can be open per payment
+    fn propose_amendment(
+        env: Env,
+        payment_id: u128,
+        proposer: Address,
+        new_amount: i128,
+        new_expiry: u64,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - proposer must authorize this transaction
+        proposer.require_auth();
+
+        let payment = load_payment(&env, payment_id)?;
+
+        // Authorization check: only the parties to the payment can propose changes
+        if proposer != payment.buyer && proposer != payment.seller {
+            return Err(PaymentEscrowError::UnauthorizedAccess);
+        }
+
+        // Status validation: amendments apply to unshipped payments only
+        if payment.status != PaymentStatus::Pending {
+            return Err(PaymentEscrowError::NotValid);
+        }
+
+        let key = DataKey::Amendment(payment_id);
+        if env.storage().persistent().has(&key) {
+            return Err(PaymentEscrowError::RequestAlreadyOpen);
+        }
+
+        validate_amendment(&env, &payment, new_amount, new_expiry)?;
+
+        // A proposing buyer signs for the top-up now
+        // This lets the seller approve without a second buyer signature
+        let increase = new_amount - payment.amount;
+        let escrowed_top_up = if proposer == payment.buyer && increase > 0 {
+            pull_from_buyer(&env, &payment, increase)?;
+            increase
+        } else {
+            0
+        };
+
+        let proposal = AmendmentProposal {
+            proposer,
+            new_amount,
+            new_expiry,
+            escrowed_top_up,
+            proposed_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&key, &proposal);
+        extend_payment_ttl(&env, &payment);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("amd_prop"), payment_id), proposal);
+
+        Ok(())
+    }
+
+    /// Counterparty approves an open amendment and the payment terms are updated
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `approver` - The party that did not propose the amendment
+    ///
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    ///
+    /// # Business Logic
+    /// * Increases not yet escrowed are pulled from an approving buyer
+    /// * Decreases are refunded to the buyer
+    /// * The amount difference is applied to the final release tranche
+    /// * The dispute deadline is recomputed from the policy window and new expiry
+    ///
+    /// # Security
+    /// * Requires approver authentication
+    /// * The proposer cannot approve their own amendment
+    /// * Terms are re-validated at approval time
+    fn accept_amendment(env: Env, payment_id: u128, approver: Address) -> Result<(), PaymentEscrowError> {
+        // Authentication - approver must authorize this transaction
+        approver.require_auth();
+
+        let mut payment = load_payment(&env, payment_id)?;
+
+        let proposal: AmendmentProposal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Amendment(payment_id))
+            .ok_or(PaymentEscrowError::RequestNotFound)?;
+
+        // Authorization check: both parties must sign off on the change
+        let counterparty = if proposal.proposer == payment.buyer {
+            payment.seller.clone()
+        } else {
+            payment.buyer.clone()
+        };
+        if approver != counterparty {
+            return Err(PaymentEscrowError::UnauthorizedAccess);
+        }
+
+        // Status validation: the seller may have shipped since the proposal
+        if payment.status != PaymentStatus::Pending {
+            return Err(PaymentEscrowError::NotValid);
+        }
+
+        // Re-validate in case time has passed since the proposal
+        validate_amendment(&env, &payment, proposal.new_amount, proposal.new_expiry)?;
+        env.storage().persistent().remove(&DataKey::Amendment(payment_id));
+
+        // Settle the amount difference with the buyer
+        let delta = proposal.new_amount - payment.amount;
+        let (top_up, refund) = if delta > 0 { (delta, 0) } else { (0, -delta) };
+        if top_up > proposal.escrowed_top_up {
+            pull_from_buyer(&env, &payment, top_up - proposal.escrowed_top_up)?;
+        }
+        if refund > 0 {
+            let token_client = TokenClient::new(&env, &payment.token);
+            token_client.transfer(&env.current_contract_address(), &payment.buyer, &refund);
+        }
+
+        // Apply the difference to the final tranche
+        // All tranches are unreleased while the payment is pending
+        let last_index = payment.tranches.len() - 1;
+        let mut last_tranche = payment.tranches.get(last_index).unwrap();
+        last_tranche.amount += delta;
+        payment.tranches.set(last_index, last_tranche);
+
+        // Keep the order total in step with its line items
+        if let Some(order_id) = payment.order_id {
+            let order_key = DataKey::Order(order_id);
+            if let Some(order) = env.storage().persistent().get::<_, Order>(&order_key) {
+                let updated_order = Order {
+                    total_amount: order.total_amount + delta,
+                    ..order
+                };
+                env.storage().persistent().set(&order_key, &updated_order);
+            }
+        }
+
+        let event = AmendmentEvent {
+            payment_id,
+            proposer: proposal.proposer,
+            approver,
+            old_amount: payment.amount,
+            new_amount: proposal.new_amount,
+            old_expiry: payment.expiry,
+            new_expiry: proposal.new_expiry,
+            top_up,
+            refund,
+        };
+
+        // Recompute the dispute deadline under the payment's policy
+        let policy = PaymentEscrowContract::get_policy(env.clone(), payment.policy_id)?;
+        let dispute_deadline = dispute_deadline(&policy, payment.created_at, proposal.new_expiry);
+
+        let updated_payment = Payment {
+            amount: proposal.new_amount,
+            remaining_amount: proposal.new_amount,
+            expiry: proposal.new_expiry,
+            dispute_deadline,
+            ..payment
+        };
+        save_payment(&env, &updated_payment);
+        extend_payment_ttl(&env, &updated_payment);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("amended"), payment_id), event);
+
+        Ok(())
+    }
+
+    /// Closes an open amendment without applying it
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `caller` - The buyer or seller closing the proposal
+    ///
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    ///
+    /// # Business Logic
+    /// * Any top-up escrowed by the buyer is returned
+    /// * Works in any payment status so an escrowed top-up is never stranded
+    fn reject_amendment(env: Env, payment_id: u128, caller: Address) -> Result<(), PaymentEscrowError> {
+        // Authentication - caller must authorize this transaction
+        caller.require_auth();
+
+        let payment = load_payment(&env, payment_id)?;
+
+        // Authorization check: only the buyer or seller can close the proposal
+        if caller != payment.buyer && caller != payment.seller {
+            return Err(PaymentEscrowError::UnauthorizedAccess);
+        }
+
+        let proposal = take_amendment(&env, payment_id).ok_or(PaymentEscrowError::RequestNotFound)?;
+
+        // Return the buyer's escrowed top-up
+        if proposal.escrowed_top_up > 0 {
+            let token_client = TokenClient::new(&env, &payment.token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &payment.buyer,
+                &proposal.escrowed_top_up,
+            );
+        }
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("amd_rej"), payment_id), caller);
+
+        Ok(())
+    }
+
+    /// Retrieves the open amendment for a payment, if any
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    ///
+    /// # Returns
+    /// * `Option<AmendmentProposal>` - The open amendment, if any
+    fn get_amendment(env: Env, payment_id: u128) -> Option<AmendmentProposal> {
+        env.storage().persistent().get(&DataKey::Amendment(payment_id))
+    }
+}
+
+/// Loads a payment or fails with NotFound
+fn load_payment(env: &Env, payment_id: u128) -> Result<Payment, PaymentEscrowError> {
+    read_payment(env, payment_id).ok_or(PaymentEscrowError::NotFound)
+}
+
+/// Removes and returns the open amendment for a payment, if any
+fn take_amendment(env: &Env, payment_id: u128) -> Option<AmendmentProposal> {
+    let key = DataKey::Amendment(payment_id);
+    let proposal = env.storage().persistent().get(&key);
+    if proposal.is_some() {
+        env.storage().persistent().remove(&key);
+    }
+    proposal
+}
+
+/// Checks proposed terms against the payment and its policy
+fn validate_amendment(
+    env: &Env,
+    payment: &Payment,
+    new_amount: i128,
+    new_expiry: u64,
+) -> Result<(), PaymentEscrowError> {
+    // Input validation: something must change
+    if new_amount == payment.amount && new_expiry == payment.expiry {
+        return Err(PaymentEscrowError::NotValid);
+    }
+
+    // Input validation: the final tranche absorbs the difference and must stay positive
+    let last_tranche = payment.tranches.get(payment.tranches.len() - 1).unwrap();
+    if new_amount <= 0 || last_tranche.amount + (new_amount - payment.amount) <= 0 {
+        return Err(PaymentEscrowError::InvalidAmount);
+    }
+
+    // Token validation: the amended amount must stay within the token limits
+    if new_amount != payment.amount {
+        check_token_amount(env, &payment.token, new_amount)?;
+    }
+
+    // Input validation: expiry must be in the future and within the policy maximum
+    let policy = PaymentEscrowContract::get_policy(env.clone(), payment.policy_id)?;
+    let max_expiry = payment.created_at + policy.max_expiry_days as u64 * 24 * 60 * 60;
+    if new_expiry <= env.ledger().timestamp() || new_expiry > max_expiry {
+        return Err(PaymentEscrowError::InvalidPeriod);
+    }
+
+    Ok(())
+}
+
+/// Pulls an amendment top-up from the buyer into escrow
+fn pull_from_buyer(env: &Env, payment: &Payment, amount: i128) -> Result<(), PaymentEscrowError> {
+    let token_client = TokenClient::new(env, &payment.token);
+
+    // Validate buyer has sufficient funds before transfer
+    if token_client.balance(&payment.buyer) < amount {
+        return Err(PaymentEscrowError::InsufficientFunds);
+    }
+
+    token_client.transfer(&payment.buyer, &env.current_contract_address(), &amount);
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use crate::{
-    datatypes::{DataKey, PaymentEscrowError},
+    datatypes::{DataKey, DisputePanel, PanelConfig, Payment, PaymentEscrowError, MAX_BPS, PANEL_VOTING_PERIOD},
     interface::ArbitratorInterface,
     PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
 };
@@ -15,24 +15,25 @@ impl ArbitratorInterface for PaymentEscrowContract {
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `arbitrator` - The existing arbitrator who is authorizing this transaction
+    /// * `admin` - The contract admin who is authorizing this transaction
     /// * `new_arbitrator` - The new arbitrator address to be added
     /// 
     /// # Returns
     /// * `Result<(), PaymentEscrowError>` - Success or error
     /// 
     /// # Security
-    /// * Only existing arbitrators can add new arbitrators
+    /// * Only the admin can add new arbitrators, so a single arbitrator
+    ///   cannot pack the set that dispute panels are drawn from
     /// * Prevents duplicate arbitrators
-    /// * Requires authentication from the existing arbitrator
+    /// * Requires authentication from the admin
     fn add_arbitrator(
         env: Env,
-        arbitrator: Address,
+        admin: Address,
         new_arbitrator: Address,
     ) -> Result<(), PaymentEscrowError> {
-        // Authentication - existing arbitrator must authorize this transaction
-        // This ensures only authorized arbitrators can add new ones
-        arbitrator.require_auth();
+        // Authentication - admin must authorize this transaction
+        admin.require_auth();
+        require_admin(&env, &admin)?;
 
         // Retrieve the current list of arbitrators from persistent storage
         // This list contains all authorized arbitrators who can resolve disputes
@@ -42,12 +43,6 @@ impl ArbitratorInterface for PaymentEscrowContract {
             .get(&DataKey::Arbitrator)
             .ok_or(PaymentEscrowError::NotFound)?;
 
-        // Authorization check: verify that the caller is an existing arbitrator
-        // Only current arbitrators can add new ones, preventing unauthorized additions
-        if !arbitrators.contains(&arbitrator) {
-            return Err(PaymentEscrowError::NotArbitrator);
-        }
-
         // Duplicate prevention: check if the new arbitrator already exists
         // This prevents adding the same arbitrator multiple times
         if arbitrators.contains(&new_arbitrator) {
@@ -68,12 +63,55 @@ impl ArbitratorInterface for PaymentEscrowContract {
         // This allows external systems to track arbitrator additions
         env.events().publish(
             (symbol_short!("new_arb"), new_arbitrator.clone()),
-            arbitrator.clone(),
+            admin.clone(),
         );
 
         Ok(())
     }
 
+    /// Retrieves the contract admin
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Result<Address, PaymentEscrowError>` - Admin address, or error if not initialized
+    fn get_admin(env: Env) -> Result<Address, PaymentEscrowError> {
+        read_admin(&env)
+    }
+
+    /// Hands the admin role to another address
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - The current admin
+    /// * `new_admin` - The address that becomes admin
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from both the current and the new admin,
+    ///   so the role cannot be handed to an address nobody controls
+    fn transfer_admin(
+        env: Env,
+        admin: Address,
+        new_admin: Address,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - both sides of the handover must sign
+        admin.require_auth();
+        new_admin.require_auth();
+        require_admin(&env, &admin)?;
+
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("xfer_adm"), admin), new_admin);
+
+        Ok(())
+    }
+
     /// Retrieves the current list of all authorized arbitrators
     /// 
     /// # Arguments
@@ -179,4 +217,168 @@ impl ArbitratorInterface for PaymentEscrowContract {
 
         Ok(())
     }
+
+    /// Configures panel mode for high-value disputes
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `min_amount` - Disputed remainder at or above which a panel is assigned
+    /// * `panel_size` - Number of arbitrators assigned to each panel (at least 2)
+    /// * `arbitrator_fee_bps` - Share of the remainder paid to voting panel arbitrators
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from an existing arbitrator
+    /// * Validates panel size, threshold and fee bounds
+    fn set_panel_config(
+        env: Env,
+        arbitrator: Address,
+        min_amount: i128,
+        panel_size: u32,
+        arbitrator_fee_bps: u32,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - arbitrator must authorize this transaction
+        arbitrator.require_auth();
+        require_arbitrator(&env, &arbitrator)?;
+
+        // Input validation: a panel needs at least two members and a positive threshold
+        if panel_size < 2 || min_amount <= 0 || arbitrator_fee_bps > MAX_BPS {
+            return Err(PaymentEscrowError::InvalidPanelConfig);
+        }
+
+        let panel_config = PanelConfig {
+            min_amount,
+            panel_size,
+            arbitrator_fee_bps,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::PanelConfig, &panel_config);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("panel_cfg"), arbitrator), panel_config);
+
+        Ok(())
+    }
+
+    /// Retrieves the panel mode configuration
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Option<PanelConfig>` - Panel configuration, or None if panel mode is disabled
+    fn get_panel_config(env: Env) -> Option<PanelConfig> {
+        env.storage().persistent().get(&DataKey::PanelConfig)
+    }
+
+    /// Retrieves the arbitrator panel assigned to a disputed payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<DisputePanel, PaymentEscrowError>` - Panel and votes cast, or error if none assigned
+    fn get_dispute_panel(env: Env, payment_id: u128) -> Result<DisputePanel, PaymentEscrowError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DisputePanel(payment_id))
+            .ok_or(PaymentEscrowError::NotFound)
+    }
+}
+
+/// Verifies that an address is a registered arbitrator
+pub(crate) fn require_arbitrator(env: &Env, arbitrator: &Address) -> Result<(), PaymentEscrowError> {
+    let arbitrators: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Arbitrator)
+        .ok_or(PaymentEscrowError::NotInitialized)?;
+
+    if !arbitrators.contains(arbitrator) {
+        return Err(PaymentEscrowError::NotArbitrator);
+    }
+
+    Ok(())
+}
+
+/// Reads the contract admin
+/// 
+/// Contracts initialized before the admin role existed fall back to the first
+/// registered arbitrator, which is the one the contract was initialized with
+/// unless it has since transferred its rights.
+fn read_admin(env: &Env) -> Result<Address, PaymentEscrowError> {
+    if let Some(admin) = env.storage().persistent().get(&DataKey::Admin) {
+        return Ok(admin);
+    }
+    let arbitrators: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Arbitrator)
+        .ok_or(PaymentEscrowError::NotInitialized)?;
+    arbitrators.first().ok_or(PaymentEscrowError::NotInitialized)
+}
+
+/// Verifies that an address is the contract admin
+pub(crate) fn require_admin(env: &Env, admin: &Address) -> Result<(), PaymentEscrowError> {
+    if read_admin(env)? != *admin {
+        return Err(PaymentEscrowError::NotAdmin);
+    }
+
+    Ok(())
+}
+
+/// Assigns an arbitrator panel to a newly disputed payment when panel mode applies
+/// 
+/// Panel mode applies when it is configured, the disputed remainder reaches the
+/// configured threshold and at least two arbitrators are registered. Members are
+/// drawn from the registered set with the host PRNG, which the network seeds at
+/// execution time, so neither party can steer the panel through the payment id
+/// or by choosing when to raise the dispute. Low-value disputes get no panel and keep
+/// the single-arbitrator flow. A panel that has not reached quorum within
+/// PANEL_VOTING_PERIOD falls back to the single-arbitrator flow.
+pub(crate) fn assign_panel(env: &Env, payment: &Payment) {
+    let panel_config: PanelConfig = match env.storage().persistent().get(&DataKey::PanelConfig) {
+        Some(panel_config) => panel_config,
+        None => return,
+    };
+
+    if payment.remaining_amount < panel_config.min_amount {
+        return;
+    }
+
+    let mut registered: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Arbitrator)
+        .unwrap_or(Vec::new(env));
+    let registered_count = registered.len();
+    if registered_count < 2 {
+        return;
+    }
+
+    // Draw the panel at random from the registered set
+    let panel_size = panel_config.panel_size.min(registered_count);
+    env.prng().shuffle(&mut registered);
+    let arbitrators = registered.slice(0..panel_size);
+
+    let panel = DisputePanel {
+        arbitrators: arbitrators.clone(),
+        votes: Vec::new(env),
+        quorum: panel_size / 2 + 1, // Simple majority of the panel
+        arbitrator_fee_bps: panel_config.arbitrator_fee_bps,
+        voting_deadline: env.ledger().timestamp() + PANEL_VOTING_PERIOD,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::DisputePanel(payment.id), &panel);
+
+    // Emit an event so assigned arbitrators can pick up the dispute
+    env.events()
+        .publish((symbol_short!("panel"), payment.id), arbitrators);
 }
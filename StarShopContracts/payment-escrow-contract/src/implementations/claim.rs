@@ -1,5 +1,6 @@
 use crate::{
     datatypes::{Payment, PaymentEscrowError, PaymentStatus},
+    implementations::{query::{read_payment, save_payment}, retention::{extend_payment_ttl, is_terminal}},
     interface::ClaimInterface,
     PaymentEscrowContract, PaymentEscrowContractClient, PaymentEscrowContractArgs
 };
@@ -26,12 +27,13 @@ impl ClaimInterface for PaymentEscrowContract {
     /// * Only expired payments can be claimed
     /// * Only the buyer can claim expired payments
     /// * Disputed payments cannot be claimed (must be resolved first)
-    /// * Claims transfer funds back to the buyer and mark payment as Refunded
+    /// * Delivered payments cannot be claimed (seller is paid after inspection)
+    /// * Claims transfer the unreleased remainder back to the buyer and mark payment as Refunded
     /// 
     /// # Security
     /// * Requires authentication from the claimer
     /// * Validates payment expiration
-    /// * Prevents claiming of disputed payments
+    /// * Prevents claiming of disputed or already finalized payments
     /// * Ensures only the buyer can claim
     fn claim_payment(env: Env, payment_id: u128, claimer: Address) -> Result<(), PaymentEscrowError> {
         // Authentication - claimer must authorize this transaction
@@ -40,11 +42,7 @@ impl ClaimInterface for PaymentEscrowContract {
 
         // Retrieve the payment details from persistent storage
         // This contains all payment information including status, amounts, and timestamps
-        let payment: Payment = env
-            .storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)?;
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
 
         // Expiration check: verify that the payment has actually expired
         // This prevents premature claims and ensures the escrow period has passed
@@ -59,6 +57,17 @@ impl ClaimInterface for PaymentEscrowContract {
             return Err(PaymentEscrowError::PaymentDisputed);
         }
 
+        // Delivery check: delivered and undisputed orders belong to the seller
+        // Those funds are released through release_after_inspection instead
+        if payment.status == PaymentStatus::Delivered {
+            return Err(PaymentEscrowError::DeliveredNotClaimable);
+        }
+
+        // Status validation: finalized payments have nothing left to refund
+        if is_terminal(&payment.status) {
+            return Err(PaymentEscrowError::NotValid);
+        }
+
         // Authorization check: ensure only the buyer can claim expired payments
         // This prevents unauthorized parties from claiming funds
         if payment.buyer != claimer {
@@ -69,23 +78,23 @@ impl ClaimInterface for PaymentEscrowContract {
         // This enables interaction with the token contract to transfer funds
         let token_client = TokenClient::new(&env, &payment.token);
 
-        // Transfer funds from the escrow contract back to the buyer
-        // This executes the actual refund of the escrowed amount
+        // Transfer the unreleased remainder from the escrow contract back to the buyer
+        // Tranches already released to the seller are not refunded
         token_client.transfer(&env.current_contract_address(), &payment.buyer,
-            &payment.amount);
+            &payment.remaining_amount);
 
         // Update payment status to Refunded to reflect the completed claim
         // This prevents double-claiming and provides clear payment state
         let updated_payment = Payment {
             status: PaymentStatus::Refunded,
+            remaining_amount: 0,
             ..payment
         };
         
         // Persist the updated payment status to storage
         // This ensures the claim is permanent and the payment state is updated
-        env.storage()
-            .persistent()
-            .set(&payment_id, &updated_payment);
+        save_payment(&env, &updated_payment);
+        extend_payment_ttl(&env, &updated_payment);
 
         // Emit an event for transparency and off-chain tracking
         // This allows external systems to track successful claims
@@ -1,10 +1,11 @@
 use crate::{
-    datatypes::{DataKey, Payment, PaymentEscrowError, PaymentStatus},
-    interface::PaymentInterface,
+    datatypes::{DataKey, Payment, PaymentEscrowError, PaymentStatus, ReleaseTranche},
+    implementations::{fee::fee_bps_for, policy::dispute_deadline, query::{read_payment, save_payment}, registry::check_token_amount, retention::extend_payment_ttl},
+    interface::{PaymentInterface, PolicyInterface},
     PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
 };
 use soroban_sdk::token::Client as TokenClient;
-use soroban_sdk::{contractimpl, symbol_short, Address, Env, String};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, String, Vec};
 
 /// Implementation of the PaymentInterface trait for PaymentEscrowContract
 /// This module handles payment creation and retrieval functionality, including
@@ -41,7 +42,8 @@ impl PaymentInterface for PaymentEscrowContract {
     /// * `seller` - The address of the seller (recipient)
     /// * `amount` - The payment amount in token units
     /// * `token` - The token contract address for the payment
-    /// * `expiry_days` - Number of days until payment expires (0 = 30 days default)
+    /// * `expiry_days` - Number of days until payment expires (0 = policy default)
+    /// * `policy_id` - Escrow policy profile setting expiry, dispute and inspection terms (0 = default)
     /// * `description` - Human-readable description of the payment
     /// 
     /// # Returns
@@ -50,7 +52,7 @@ impl PaymentInterface for PaymentEscrowContract {
     /// # Business Logic
     /// * Transfers funds from buyer to escrow contract
     /// * Creates unique payment ID and stores payment details
-    /// * Sets up dispute deadline based on expiry period
+    /// * Sets up expiry and dispute deadline from the selected policy
     /// * Validates all inputs and buyer authorization
     /// 
     /// # Security
@@ -58,6 +60,8 @@ impl PaymentInterface for PaymentEscrowContract {
     /// * Validates buyer has sufficient funds
     /// * Prevents self-payment (buyer != seller)
     /// * Ensures positive payment amounts
+    /// * Requires an accepted token and an amount within its limits
+    #[allow(clippy::too_many_arguments)]
     fn create_payment(
         env: Env,
         buyer: Address,
@@ -65,99 +69,55 @@ impl PaymentInterface for PaymentEscrowContract {
         amount: i128,
         token: Address,
         expiry_days: u32,
+        policy_id: u32,
         description: String,
     ) -> Result<u128, PaymentEscrowError> {
-        // Authentication - buyer must authorize this transaction
-        // This ensures only the intended buyer can create payments
-        buyer.require_auth();
+        // A regular payment is a milestone payment with a single tranche
+        // covering the full amount, released on buyer confirmation
+        let mut tranches = Vec::new(&env);
+        tranches.push_back((amount, description.clone()));
 
-        // Input validation: ensure payment amount is positive
-        // This prevents creating payments with zero or negative amounts
-        if amount <= 0 {
-            return Err(PaymentEscrowError::InvalidAmount);
-        }
-
-        // Self-payment prevention: buyer and seller must be different addresses
-        // This prevents users from creating payments to themselves
-        if buyer == seller {
-            return Err(PaymentEscrowError::CannotPaySelf);
-        }
-
-        // Generate unique payment ID by incrementing the payment counter
-        // This ensures each payment has a unique identifier for tracking
-        let mut payment_counter = Self::get_payment_count(&env);
-        payment_counter += 1;
-        let payment_id = payment_counter;
-
-        // Calculate payment timestamps for lifecycle management
-        let current_ledger = env.ledger().timestamp();
-        
-        // Set expiry period with default fallback
-        // If no expiry is specified (0), default to 30 days for safety
-        let expiry_days = if expiry_days == 0 { 30 } else { expiry_days };
-        
-        // Calculate expiry timestamp by adding days to current time
-        // Convert days to seconds for precise timestamp calculation
-        let expiry_timestamp = current_ledger + (expiry_days as u64 * 24 * 60 * 60);
-        
-        // Calculate dispute deadline based on payment duration
-        // For long-term payments (7+ days): dispute deadline = expiry - 7 days
-        // For short-term payments (<7 days): dispute deadline = expiry time
-        // This ensures disputes are possible throughout the payment period
-        let dispute_deadline = if expiry_days >= 7 {
-            expiry_timestamp - (7 * 24 * 60 * 60) // 7 days before expiry for long payments
-        } else {
-            expiry_timestamp // Full payment period for short payments
-        };
-
-        // Create token client for fund transfer operations
-        // This enables interaction with the specified token contract
-        let token_client = TokenClient::new(&env, &token);
-
-        // Validate buyer has sufficient funds before transfer
-        // This prevents failed transfers and ensures payment feasibility
-        let buyer_balance = token_client.balance(&buyer);
-        if buyer_balance < amount {
-            return Err(PaymentEscrowError::InsufficientFunds);
-        }
-
-        // Transfer funds from buyer to escrow contract
-        // This locks the funds in escrow until payment completion or expiry
-        token_client.transfer(&buyer, &env.current_contract_address(), &amount);
-
-        // Create payment struct with all relevant details
-        // This contains all information needed for payment lifecycle management
-        let payment = Payment {
-            id: payment_id,
-            buyer,
-            seller,
-            amount,
-            token,
-            status: PaymentStatus::Pending, // Initial status: funds held in escrow
-            created_at: current_ledger,
-            expiry: expiry_timestamp,
-            dispute_deadline,
-            description,
-        };
-
-        // Update the payment counter in persistent storage
-        // This ensures the next payment gets a unique ID
-        env.storage()
-            .persistent()
-            .set(&DataKey::PaymentCounter, &payment_id);
-
-        // Store the complete payment details in persistent storage
-        // This enables retrieval and management of the payment throughout its lifecycle
-        env.storage().persistent().set(&payment_id, &payment);
-
-        // Emit an event for transparency and off-chain tracking
-        // This allows external systems to monitor payment creation
-        env.events().publish(
-            (DataKey::PaymentCounter, symbol_short!("payment")),
-            payment_id,
-        );
+        let terms = EscrowTerms::new(&env, buyer, token, expiry_days, policy_id, None)?;
+        store_new_payment(&env, terms, seller, tranches, description)
+    }
 
-        Ok(payment_id as u128)
+    /// Creates a new escrow payment released to the seller in tranches
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - The address of the buyer (payer)
+    /// * `seller` - The address of the seller (recipient)
+    /// * `token` - The token contract address for the payment
+    /// * `tranches` - Ordered list of (amount, description) release tranches
+    /// * `expiry_days` - Number of days until payment expires (0 = policy default)
+    /// * `policy_id` - Escrow policy profile setting expiry, dispute and inspection terms (0 = default)
+    /// * `description` - Human-readable description of the payment
+    /// 
+    /// # Returns
+    /// * `Result<u128, PaymentEscrowError>` - Payment ID on success, error on failure
+    /// 
+    /// # Business Logic
+    /// * Escrows the sum of all tranche amounts in a single transfer
+    /// * Each tranche can later be released individually by the buyer
+    /// * Disputes and expiry claims only act on the unreleased remainder
+    /// 
+    /// # Security
+    /// * Requires buyer authentication
+    /// * Requires at least one tranche, each with a positive amount
+    /// * Prevents self-payment (buyer != seller)
+    #[allow(clippy::too_many_arguments)]
+    fn create_milestone_payment(
+        env: Env,
+        buyer: Address,
+        seller: Address,
+        token: Address,
+        tranches: Vec<(i128, String)>,
+        expiry_days: u32,
+        policy_id: u32,
+        description: String,
+    ) -> Result<u128, PaymentEscrowError> {
+        let terms = EscrowTerms::new(&env, buyer, token, expiry_days, policy_id, None)?;
+        store_new_payment(&env, terms, seller, tranches, description)
     }
 
     /// Retrieves a specific payment by its unique identifier
@@ -176,9 +136,216 @@ impl PaymentInterface for PaymentEscrowContract {
     fn get_a_payment(env: Env, payment_id: u128) -> Result<Payment, PaymentEscrowError> {
         // Retrieve payment details from persistent storage
         // Returns the complete payment struct or NotFound error
-        env.storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)
+        read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)
     }
 }
+
+/// Escrow terms shared by every payment created in one call
+/// 
+/// A standalone payment has its own terms; all line items of an order share
+/// the order's buyer, token, policy, expiry and dispute deadline.
+pub(crate) struct EscrowTerms {
+    pub buyer: Address,
+    pub token: Address,
+    pub created_at: u64,
+    pub expiry: u64,
+    pub dispute_deadline: u64,
+    pub order_id: Option<u128>,
+    pub policy_id: u32,
+    pub inspection_period: u64,
+}
+
+impl EscrowTerms {
+    /// Computes the expiry and dispute deadline for a new escrow from its policy
+    pub(crate) fn new(
+        env: &Env,
+        buyer: Address,
+        token: Address,
+        expiry_days: u32,
+        policy_id: u32,
+        order_id: Option<u128>,
+    ) -> Result<Self, PaymentEscrowError> {
+        // Look up the selected policy profile
+        let policy = PaymentEscrowContract::get_policy(env.clone(), policy_id)?;
+
+        // Calculate payment timestamps for lifecycle management
+        let current_ledger = env.ledger().timestamp();
+
+        // Set expiry period with the policy default as fallback
+        // Requested expiries must stay within the policy maximum
+        let expiry_days = if expiry_days == 0 { policy.default_expiry_days } else { expiry_days };
+        if expiry_days > policy.max_expiry_days {
+            return Err(PaymentEscrowError::InvalidPeriod);
+        }
+
+        // Calculate expiry timestamp by adding days to current time
+        // Convert days to seconds for precise timestamp calculation
+        let expiry = current_ledger + (expiry_days as u64 * 24 * 60 * 60);
+
+        // Calculate dispute deadline from the policy dispute window
+        // Disputes are never accepted after the payment expires
+        let dispute_deadline = dispute_deadline(&policy, current_ledger, expiry);
+
+        Ok(EscrowTerms {
+            buyer,
+            token,
+            created_at: current_ledger,
+            expiry,
+            dispute_deadline,
+            order_id,
+            policy_id,
+            inspection_period: policy.inspection_period,
+        })
+    }
+}
+
+/// Pulls `amount` from the buyer into escrow after checking their balance
+pub(crate) fn fund_escrow(env: &Env, terms: &EscrowTerms, amount: i128) -> Result<(), PaymentEscrowError> {
+    // Create token client for fund transfer operations
+    // This enables interaction with the specified token contract
+    let token_client = TokenClient::new(env, &terms.token);
+
+    // Validate buyer has sufficient funds before transfer
+    // This prevents failed transfers and ensures payment feasibility
+    let buyer_balance = token_client.balance(&terms.buyer);
+    if buyer_balance < amount {
+        return Err(PaymentEscrowError::InsufficientFunds);
+    }
+
+    // Transfer funds from buyer to escrow contract
+    // This locks the funds in escrow until payment completion or expiry
+    token_client.transfer(&terms.buyer, &env.current_contract_address(), &amount);
+
+    Ok(())
+}
+
+/// Validates a list of (amount, description) tranche specs
+/// 
+/// Returns the stored tranche list together with the payment total.
+pub(crate) fn build_tranches(
+    env: &Env,
+    tranche_specs: Vec<(i128, String)>,
+) -> Result<(Vec<ReleaseTranche>, i128), PaymentEscrowError> {
+    // Input validation: a payment needs at least one tranche to release
+    if tranche_specs.is_empty() {
+        return Err(PaymentEscrowError::InvalidAmount);
+    }
+
+    // Input validation: ensure every tranche amount is positive
+    // and build the stored tranche list alongside the payment total
+    let mut tranches = Vec::new(env);
+    let mut amount: i128 = 0;
+    for (tranche_amount, tranche_description) in tranche_specs.iter() {
+        if tranche_amount <= 0 {
+            return Err(PaymentEscrowError::InvalidAmount);
+        }
+        amount = amount
+            .checked_add(tranche_amount)
+            .ok_or(PaymentEscrowError::InvalidAmount)?;
+        tranches.push_back(ReleaseTranche {
+            amount: tranche_amount,
+            description: tranche_description,
+            released: false,
+        });
+    }
+
+    Ok((tranches, amount))
+}
+
+/// Stores a new, already funded payment under a fresh payment ID
+pub(crate) fn record_payment(
+    env: &Env,
+    terms: &EscrowTerms,
+    seller: Address,
+    tranches: Vec<ReleaseTranche>,
+    amount: i128,
+    description: String,
+) -> u128 {
+    // Generate unique payment ID by incrementing the payment counter
+    // This ensures each payment has a unique identifier for tracking
+    let payment_id = PaymentEscrowContract::get_payment_count(env) + 1;
+
+    // Snapshot the platform fee so later schedule changes do not
+    // affect funds that are already escrowed
+    let fee_bps = fee_bps_for(env, &terms.token);
+
+    // Create payment struct with all relevant details
+    // This contains all information needed for payment lifecycle management
+    let payment = Payment {
+        id: payment_id,
+        buyer: terms.buyer.clone(),
+        seller,
+        amount,
+        token: terms.token.clone(),
+        status: PaymentStatus::Pending, // Initial status: funds held in escrow
+        created_at: terms.created_at,
+        expiry: terms.expiry,
+        dispute_deadline: terms.dispute_deadline,
+        description,
+        tranches,
+        released_amount: 0,
+        remaining_amount: amount,
+        fee_bps,
+        inspection_deadline: 0, // Set once the seller confirms delivery
+        order_id: terms.order_id,
+        policy_id: terms.policy_id,
+        inspection_period: terms.inspection_period,
+    };
+
+    // Update the payment counter in persistent storage
+    // This ensures the next payment gets a unique ID
+    env.storage()
+        .persistent()
+        .set(&DataKey::PaymentCounter, &payment_id);
+
+    // Store the complete payment details in persistent storage and index it
+    // This enables retrieval and management of the payment throughout its lifecycle
+    save_payment(env, &payment);
+
+    // Emit an event for transparency and off-chain tracking
+    // This allows external systems to monitor payment creation
+    env.events().publish(
+        (DataKey::PaymentCounter, symbol_short!("payment")),
+        payment_id,
+    );
+
+    payment_id
+}
+
+/// Shared escrow setup for single and milestone payments
+/// 
+/// Validates the tranche list, pulls the total amount from the buyer into
+/// escrow and stores the new payment under a fresh payment ID.
+fn store_new_payment(
+    env: &Env,
+    terms: EscrowTerms,
+    seller: Address,
+    tranche_specs: Vec<(i128, String)>,
+    description: String,
+) -> Result<u128, PaymentEscrowError> {
+    // Authentication - buyer must authorize this transaction
+    // This ensures only the intended buyer can create payments
+    terms.buyer.require_auth();
+
+    let (tranches, amount) = build_tranches(env, tranche_specs)?;
+
+    // Token validation: only accepted tokens within their limits can be escrowed
+    check_token_amount(env, &terms.token, amount)?;
+
+    // Self-payment prevention: buyer and seller must be different addresses
+    // This prevents users from creating payments to themselves
+    if terms.buyer == seller {
+        return Err(PaymentEscrowError::CannotPaySelf);
+    }
+
+    fund_escrow(env, &terms, amount)?;
+
+    let payment_id = record_payment(env, &terms, seller, tranches, amount, description);
+
+    // Keep the configuration the payment is settled under alive with it
+    if let Some(payment) = read_payment(env, payment_id) {
+        extend_payment_ttl(env, &payment);
+    }
+
+    Ok(payment_id)
+}
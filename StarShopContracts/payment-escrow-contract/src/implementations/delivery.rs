@@ -1,10 +1,10 @@
 use crate::{
-    datatypes::{DeliveryDetails, Payment, PaymentEscrowError, PaymentStatus},
+    datatypes::{DataKey, DeliveryDetails, Payment, PaymentEscrowError, PaymentStatus, ReleaseTranche, DEFAULT_INSPECTION_PERIOD, MAX_INSPECTION_PERIOD},
+    implementations::{arbitrator::require_arbitrator, fee::pay_seller, query::{read_payment, save_payment}, retention::extend_payment_ttl},
     interface::DeliveryInterface,
     PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
 };
-use soroban_sdk::token::Client as TokenClient;
-use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
 
 /// Implementation of the DeliveryInterface trait for PaymentEscrowContract
 /// This module handles delivery confirmation and status management for escrow payments,
@@ -24,7 +24,7 @@ impl DeliveryInterface for PaymentEscrowContract {
     /// 
     /// # Business Logic
     /// * Only works after seller has confirmed delivery (status = Delivered)
-    /// * Transfers funds from escrow to seller
+    /// * Transfers all unreleased tranches from escrow to seller, less the platform fee
     /// * Marks payment as Completed
     /// * Final step in successful escrow completion
     /// 
@@ -44,11 +44,7 @@ impl DeliveryInterface for PaymentEscrowContract {
 
         // Retrieve the payment details from persistent storage
         // This contains all payment information including current status and participants
-        let payment: Payment = env
-            .storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)?;
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
 
         // Authorization check: verify that the caller is actually the buyer
         // This prevents unauthorized parties from confirming delivery
@@ -68,30 +64,29 @@ impl DeliveryInterface for PaymentEscrowContract {
             return Err(PaymentEscrowError::PaymentDisputed);
         }
 
-        // Create token client for fund transfer operations
-        // This enables interaction with the token contract to release funds
-        let token_client = TokenClient::new(&env, &payment.token);
+        // Transfer the unreleased remainder from escrow contract to the seller,
+        // withholding the platform fee. Tranches already released individually
+        // are not paid twice
+        pay_seller(&env, &payment, payment.remaining_amount);
 
-        // Transfer funds from escrow contract to the seller
-        // This releases the escrowed funds to complete the payment
-        token_client.transfer(
-            &env.current_contract_address(),
-            &payment.seller,
-            &payment.amount,
-        );
+        // Mark every outstanding tranche as released
+        // This keeps the tranche list consistent with the released balance
+        let tranches = all_tranches_released(&payment);
 
         // Update payment status to Completed to reflect successful delivery
         // This marks the payment as successfully completed and prevents further actions
         let updated_payment = Payment {
             status: PaymentStatus::Completed,
+            tranches,
+            released_amount: payment.amount,
+            remaining_amount: 0,
             ..payment
         };
 
         // Persist the updated payment status to storage
         // This ensures the completion is permanent and the payment state is updated
-        env.storage()
-            .persistent()
-            .set(&payment_id, &updated_payment);
+        save_payment(&env, &updated_payment);
+        extend_payment_ttl(&env, &updated_payment);
 
         // Emit an event for transparency and off-chain tracking
         // This allows external systems to track successful payment completions
@@ -101,6 +96,107 @@ impl DeliveryInterface for PaymentEscrowContract {
         Ok(())
     }
 
+    /// Allows the buyer to release a single tranche of the payment to the seller
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `buyer` - The address of the buyer releasing the tranche
+    /// * `tranche_index` - Index of the tranche to release
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Transfers the tranche amount from escrow to the seller, less the platform fee
+    /// * Marks payment as PartiallyReleased while funds remain in escrow
+    /// * Keeps a Delivered payment Delivered until the last tranche is released
+    /// * Marks payment as Completed once every tranche has been released
+    /// 
+    /// # Security
+    /// * Requires buyer authentication
+    /// * Ensures only buyer can release tranches
+    /// * Prevents releases on disputed or finalized payments
+    /// * Prevents releasing the same tranche twice
+    fn release_tranche(
+        env: Env,
+        payment_id: u128,
+        buyer: Address,
+        tranche_index: u32,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - buyer must authorize this transaction
+        // This ensures only the intended buyer can release funds
+        buyer.require_auth();
+
+        // Retrieve the payment details from persistent storage
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
+
+        // Authorization check: verify that the caller is actually the buyer
+        if payment.buyer != buyer {
+            return Err(PaymentEscrowError::UnauthorizedAccess);
+        }
+
+        // Dispute check: disputed funds stay locked until an arbitrator rules
+        if payment.status == PaymentStatus::Disputed {
+            return Err(PaymentEscrowError::PaymentDisputed);
+        }
+
+        // Status validation: only payments with funds still in escrow can release
+        if payment.status != PaymentStatus::Pending
+            && payment.status != PaymentStatus::PartiallyReleased
+            && payment.status != PaymentStatus::Delivered
+        {
+            return Err(PaymentEscrowError::NotValid);
+        }
+
+        // Look up the requested tranche and ensure it is still outstanding
+        let mut tranches = payment.tranches.clone();
+        let mut tranche = tranches
+            .get(tranche_index)
+            .ok_or(PaymentEscrowError::TrancheNotFound)?;
+        if tranche.released {
+            return Err(PaymentEscrowError::TrancheAlreadyReleased);
+        }
+
+        // Transfer the tranche amount from escrow to the seller,
+        // withholding the platform fee
+        pay_seller(&env, &payment, tranche.amount);
+
+        tranche.released = true;
+        let released_amount = payment.released_amount + tranche.amount;
+        let remaining_amount = payment.remaining_amount - tranche.amount;
+        tranches.set(tranche_index, tranche);
+
+        // Completed once nothing is left in escrow, otherwise keep the
+        // Delivered marker so the buyer can still confirm the remainder
+        let status = if remaining_amount == 0 {
+            PaymentStatus::Completed
+        } else if payment.status == PaymentStatus::Delivered {
+            PaymentStatus::Delivered
+        } else {
+            PaymentStatus::PartiallyReleased
+        };
+
+        let updated_payment = Payment {
+            status,
+            tranches,
+            released_amount,
+            remaining_amount,
+            ..payment
+        };
+
+        // Persist the updated payment to storage
+        save_payment(&env, &updated_payment);
+        extend_payment_ttl(&env, &updated_payment);
+
+        // Emit an event for transparency and off-chain tracking
+        // This allows external systems to track individual tranche releases
+        env.events()
+            .publish((symbol_short!("released"), payment_id), tranche_index);
+
+        Ok(())
+    }
+
     /// Allows the seller to confirm that delivery has been made
     /// 
     /// # Arguments
@@ -112,7 +208,8 @@ impl DeliveryInterface for PaymentEscrowContract {
     /// * `Result<(), PaymentEscrowError>` - Success or error
     /// 
     /// # Business Logic
-    /// * Changes payment status from Pending to Delivered
+    /// * Changes payment status from Pending (or PartiallyReleased) to Delivered
+    /// * Opens the buyer's inspection window
     /// * First step in the delivery confirmation process
     /// * Enables buyer to then confirm and release funds
     /// 
@@ -132,15 +229,13 @@ impl DeliveryInterface for PaymentEscrowContract {
 
         // Retrieve the payment details from persistent storage
         // This contains all payment information including current status and participants
-        let payment: Payment = env
-            .storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)?;
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
 
-        // Status validation: ensure payment is in Pending status
+        // Status validation: ensure payment is Pending or PartiallyReleased
         // Seller can only confirm delivery for payments that haven't been processed yet
-        if payment.status != PaymentStatus::Pending {
+        if payment.status != PaymentStatus::Pending
+            && payment.status != PaymentStatus::PartiallyReleased
+        {
             return Err(PaymentEscrowError::NotValid);
         }
 
@@ -156,18 +251,23 @@ impl DeliveryInterface for PaymentEscrowContract {
             return Err(PaymentEscrowError::UnauthorizedAccess);
         }
 
+        // Open the buyer's inspection window, as set by the payment's policy
+        // If the buyer neither confirms nor disputes before it closes,
+        // the funds can be released to the seller
+        let inspection_deadline = env.ledger().timestamp() + payment.inspection_period;
+
         // Update payment status to Delivered to indicate delivery confirmation
         // This enables the buyer to then confirm and release funds
         let updated_payment = Payment {
             status: PaymentStatus::Delivered,
+            inspection_deadline,
             ..payment
         };
 
         // Persist the updated payment status to storage
         // This ensures the delivery confirmation is permanent
-        env.storage()
-            .persistent()
-            .set(&payment_id, &updated_payment);
+        save_payment(&env, &updated_payment);
+        extend_payment_ttl(&env, &updated_payment);
 
         // Emit an event for transparency and off-chain tracking
         // This allows external systems to track delivery confirmations
@@ -196,11 +296,7 @@ impl DeliveryInterface for PaymentEscrowContract {
     ) -> Result<PaymentStatus, PaymentEscrowError> {
         // Retrieve the payment details from persistent storage
         // This contains the current status of the payment
-        let payment: Payment = env
-            .storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)?;
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
 
         // Return the current payment status
         // This indicates where the payment is in its lifecycle
@@ -226,11 +322,7 @@ impl DeliveryInterface for PaymentEscrowContract {
     ) -> Result<DeliveryDetails, PaymentEscrowError> {
         // Retrieve the payment details from persistent storage
         // This contains all payment information needed for delivery details
-        let payment: Payment = env
-            .storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)?;
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
 
         // Create delivery details struct with relevant information
         // This provides a clean interface for delivery-related data
@@ -242,8 +334,123 @@ impl DeliveryInterface for PaymentEscrowContract {
             created_at: payment.created_at,
             expiry: payment.expiry,
             description: payment.description,
+            inspection_deadline: payment.inspection_deadline,
+            policy_id: payment.policy_id,
+            dispute_deadline: payment.dispute_deadline,
+            inspection_period: payment.inspection_period,
         };
 
         Ok(delivery_details)
     }
+
+    /// Releases a delivered payment to the seller once the inspection window has passed
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the delivered payment
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Callable by the seller or any keeper, no authorization required
+    /// * Only applies to Delivered payments the buyer neither confirmed nor disputed
+    /// * Transfers the unreleased remainder to the seller, less the platform fee
+    /// * Marks payment as Completed
+    fn release_after_inspection(env: Env, payment_id: u128) -> Result<(), PaymentEscrowError> {
+        // Retrieve the payment details from persistent storage
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
+
+        // Status validation: only delivered and undisputed payments qualify
+        if payment.status != PaymentStatus::Delivered {
+            return Err(PaymentEscrowError::NotDelivered);
+        }
+
+        // Timing check: the buyer keeps the full inspection window
+        if env.ledger().timestamp() <= payment.inspection_deadline {
+            return Err(PaymentEscrowError::InspectionPeriodActive);
+        }
+
+        // Transfer the unreleased remainder to the seller, withholding the platform fee
+        pay_seller(&env, &payment, payment.remaining_amount);
+
+        // Mark every outstanding tranche as released
+        let tranches = all_tranches_released(&payment);
+
+        let updated_payment = Payment {
+            status: PaymentStatus::Completed,
+            tranches,
+            released_amount: payment.amount,
+            remaining_amount: 0,
+            ..payment
+        };
+
+        // Persist the updated payment to storage
+        save_payment(&env, &updated_payment);
+        extend_payment_ttl(&env, &updated_payment);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("auto_rel"), payment_id), payment_id);
+
+        Ok(())
+    }
+
+    /// Sets the inspection window used by the default escrow policy
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `period` - Inspection window length in seconds
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from an existing arbitrator
+    /// * Period must be positive and at most MAX_INSPECTION_PERIOD
+    fn set_inspection_period(env: Env, arbitrator: Address, period: u64) -> Result<(), PaymentEscrowError> {
+        // Authentication - arbitrator must authorize this transaction
+        arbitrator.require_auth();
+        require_arbitrator(&env, &arbitrator)?;
+
+        if period == 0 || period > MAX_INSPECTION_PERIOD {
+            return Err(PaymentEscrowError::InvalidPeriod);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::InspectionPeriod, &period);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("insp_per"), arbitrator), period);
+
+        Ok(())
+    }
+
+    /// Retrieves the inspection window used by the default escrow policy
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `u64` - Inspection window length in seconds (DEFAULT_INSPECTION_PERIOD if unset)
+    fn get_inspection_period(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::InspectionPeriod)
+            .unwrap_or(DEFAULT_INSPECTION_PERIOD)
+    }
+}
+
+/// Returns the payment's tranche list with every tranche marked as released
+fn all_tranches_released(payment: &Payment) -> Vec<ReleaseTranche> {
+    let mut tranches = payment.tranches.clone();
+    for i in 0..tranches.len() {
+        let mut tranche = tranches.get(i).unwrap();
+        tranche.released = true;
+        tranches.set(i, tranche);
+    }
+    tranches
 }
@@ -1,10 +1,11 @@
 use crate::{
-    datatypes::{DataKey, Payment, PaymentEscrowError, PaymentStatus, DisputeEvent, DisputeResolvedEvent, DisputeDecision},
+    datatypes::{DataKey, MAX_BPS, MAX_EVIDENCE_ENTRIES, MAX_EVIDENCE_URI_LEN, Payment, PaymentEscrowError, PaymentStatus, DisputeEvent, DisputePanel, DisputeResolvedEvent, DisputeDecision, Evidence, PanelVote},
+    implementations::{arbitrator::assign_panel, fee::pay_seller, query::{read_payment, save_payment}, retention::{extend_payment_ttl, is_terminal}},
     interface::DisputeInterface,
     PaymentEscrowContract, PaymentEscrowContractClient, PaymentEscrowContractArgs
 };
 use soroban_sdk::token::Client as TokenClient;
-use soroban_sdk::{symbol_short, String, Address, Env, Vec, contractimpl};
+use soroban_sdk::{symbol_short, String, Address, BytesN, Env, Vec, contractimpl};
 
 /// Implementation of the DisputeInterface trait for PaymentEscrowContract
 /// This module handles dispute creation and resolution for escrow payments,
@@ -33,7 +34,7 @@ impl DisputeInterface for PaymentEscrowContract {
     /// * Requires disputer authentication
     /// * Validates disputer is buyer or seller
     /// * Prevents duplicate disputes
-    /// * Enforces dispute deadline
+    /// * Enforces dispute deadline (extended to the inspection deadline for delivered orders)
     /// * Prevents disputes on completed/expired payments
     fn dispute_payment(env: Env, payment_id: u128, disputer: Address, reason: String) -> Result<(), PaymentEscrowError> {
         // Authentication - disputer must authorize this transaction
@@ -42,11 +43,7 @@ impl DisputeInterface for PaymentEscrowContract {
 
         // Retrieve the payment details from persistent storage
         // This contains all payment information including status, participants, and timestamps
-        let payment: Payment = env
-            .storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)?;
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
 
         // Authorization check: verify that the disputer is either the buyer or seller
         // Only payment participants can initiate disputes, preventing unauthorized disputes
@@ -61,15 +58,21 @@ impl DisputeInterface for PaymentEscrowContract {
         }
 
         // Status validation: ensure payment is in a disputable state
-        // Completed or expired payments cannot be disputed
-        if payment.status == PaymentStatus::Completed || payment.status == PaymentStatus::Expired {
+        // Payments that reached a final status, including split rulings, cannot be disputed
+        if is_terminal(&payment.status) {
             return Err(PaymentEscrowError::NotValid);
         }
 
         // Deadline check: verify that the dispute period has not expired
         // This ensures disputes are only possible within the allowed timeframe
+        // A delivered order stays disputable until its inspection window closes
         let current_timestamp = env.ledger().timestamp();
-        if current_timestamp > payment.dispute_deadline {
+        let deadline = if payment.status == PaymentStatus::Delivered {
+            payment.dispute_deadline.max(payment.inspection_deadline)
+        } else {
+            payment.dispute_deadline
+        };
+        if current_timestamp > deadline {
             return Err(PaymentEscrowError::DisputePeriodExpired);
         }
 
@@ -82,9 +85,11 @@ impl DisputeInterface for PaymentEscrowContract {
 
         // Persist the updated payment status to storage
         // This ensures the dispute is permanent and funds are locked
-        env.storage()
-            .persistent()
-            .set(&payment_id, &updated_payment);
+        save_payment(&env, &updated_payment);
+
+        // High-value disputes are assigned an arbitrator panel
+        // Low-value disputes keep the single-arbitrator flow
+        assign_panel(&env, &updated_payment);
 
         // Create dispute event for record keeping
         // This provides transparency and enables off-chain dispute tracking
@@ -99,6 +104,7 @@ impl DisputeInterface for PaymentEscrowContract {
         env.storage()
             .persistent()
             .set(&(DataKey::DisputedPayments, payment_id), &dispute_event);
+        extend_payment_ttl(&env, &updated_payment);
 
         // Emit an event for transparency and off-chain tracking
         // This allows external systems to monitor dispute creation
@@ -113,23 +119,33 @@ impl DisputeInterface for PaymentEscrowContract {
     /// * `env` - The Soroban environment
     /// * `payment_id` - The unique identifier of the disputed payment
     /// * `arbitrator` - The address of the arbitrator resolving the dispute
-    /// * `decision` - The arbitrator's decision (PaySeller or RefundBuyer)
+    /// * `decision` - The arbitrator's decision (PaySeller, RefundBuyer or Split)
     /// * `reason` - Human-readable reason for the decision
     /// 
     /// # Returns
     /// * `Result<(), PaymentEscrowError>` - Success or error
     /// 
     /// # Business Logic
-    /// * Transfers funds based on arbitrator decision
-    /// * Marks payment as Completed (seller wins) or Refunded (buyer wins)
-    /// * Records resolution details for transparency
+    /// * For panel disputes, records the arbitrator's vote and only executes once
+    ///   a majority of the panel agrees on the same decision
+    /// * A panel without quorum by its voting deadline falls back to a ruling by
+    ///   any registered arbitrator, with no panel fee
+    /// * Panel arbitrators who voted share the configured arbitrator fee
+    /// * Transfers the unreleased remainder based on arbitrator decision
+    /// * Split decisions refund the buyer's basis points and pay the seller the rest
+    /// * Seller payouts are subject to the platform fee
+    /// * Marks payment as Completed (seller wins), Refunded (buyer wins) or SplitResolved
+    /// * Records resolution details, including the evidence considered, for transparency
     /// * Finalizes the dispute resolution process
     /// 
     /// # Security
     /// * Requires arbitrator authentication
-    /// * Validates arbitrator authorization
+    /// * Validates arbitrator authorization, and panel membership for panel disputes
+    ///   still within their voting period
+    /// * Prevents panel arbitrators from voting twice
     /// * Ensures payment is in disputed status
     /// * Requires non-empty resolution reason
+    /// * Rejects split rulings that leave either party with nothing, before any vote is recorded
     /// * Prevents unauthorized dispute resolution
     fn resolve_dispute(env: Env, payment_id: u128, arbitrator: Address, decision: DisputeDecision, reason: String) -> Result<(), PaymentEscrowError> {
         // Authentication - arbitrator must authorize this transaction
@@ -138,11 +154,7 @@ impl DisputeInterface for PaymentEscrowContract {
 
         // Retrieve the payment details from persistent storage
         // This contains all payment information needed for resolution
-        let payment: Payment = env
-            .storage()
-            .persistent()
-            .get(&payment_id)
-            .ok_or(PaymentEscrowError::NotFound)?;
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
 
         // Status validation: ensure payment is in disputed status
         // Only disputed payments can be resolved by arbitrators
@@ -170,77 +182,123 @@ impl DisputeInterface for PaymentEscrowContract {
             return Err(PaymentEscrowError::NotValid);
         }
 
+        // Decision validation: a split must leave something for both parties
+        // Checked before any panel vote is recorded
+        if let DisputeDecision::Split(buyer_bps) = decision {
+            if buyer_bps == 0 || buyer_bps >= MAX_BPS {
+                return Err(PaymentEscrowError::InvalidSplit);
+            }
+        }
+
+        // Panel disputes: record this arbitrator's vote and only execute the
+        // ruling once a majority of the panel agrees on the same decision
+        // Once the voting deadline passes without quorum, the panel is
+        // deadlocked and any registered arbitrator rules without a panel fee
+        let mut payment = payment;
+        let mut arbitrator_fee: i128 = 0;
+        if let Some(mut panel) = env
+            .storage()
+            .persistent()
+            .get::<_, DisputePanel>(&DataKey::DisputePanel(payment_id))
+            .filter(|panel| env.ledger().timestamp() <= panel.voting_deadline)
+        {
+            if !panel.arbitrators.contains(&arbitrator) {
+                return Err(PaymentEscrowError::NotPanelArbitrator);
+            }
+            if panel.votes.iter().any(|vote| vote.arbitrator == arbitrator) {
+                return Err(PaymentEscrowError::AlreadyVoted);
+            }
+
+            panel.votes.push_back(PanelVote {
+                arbitrator: arbitrator.clone(),
+                decision: decision.clone(),
+            });
+            env.storage()
+                .persistent()
+                .set(&DataKey::DisputePanel(payment_id), &panel);
+            extend_payment_ttl(&env, &payment);
+
+            // Emit an event for transparency and off-chain tracking
+            env.events().publish(
+                (symbol_short!("vote"), payment_id),
+                (arbitrator.clone(), decision.clone()),
+            );
+
+            let matching_votes = panel
+                .votes
+                .iter()
+                .filter(|vote| vote.decision == decision)
+                .count() as u32;
+            if matching_votes < panel.quorum {
+                return Ok(());
+            }
+
+            // Quorum reached: pay every arbitrator who voted an equal share of
+            // the arbitrator fee, taken from the escrowed remainder
+            arbitrator_fee = pay_panel_fee(&env, &payment, &panel);
+            payment.remaining_amount -= arbitrator_fee;
+        }
+
+        // Record how much of the evidence log was on file at resolution time
+        // The log is append-only and closes once the payment leaves Disputed
+        let evidence_count = Self::get_evidence(env.clone(), payment_id).len();
+
+        // Work out how the unreleased remainder is divided by the decision
+        // Full rulings send everything to one party, split rulings divide it
+        // by basis points with any rounding dust going to the seller
+        let remaining = payment.remaining_amount;
+        let (buyer_amount, seller_amount, status) = match decision {
+            DisputeDecision::PaySeller => (0, remaining, PaymentStatus::Completed),
+            DisputeDecision::RefundBuyer => (remaining, 0, PaymentStatus::Refunded),
+            DisputeDecision::Split(buyer_bps) => {
+                let buyer_amount = remaining * buyer_bps as i128 / MAX_BPS as i128;
+                (buyer_amount, remaining - buyer_amount, PaymentStatus::SplitResolved)
+            }
+        };
+
         // Create token client for fund transfer operations
         // This enables interaction with the token contract to distribute funds
         let token_client = TokenClient::new(&env, &payment.token);
 
-        // Execute the arbitrator's decision using pattern matching
-        // This handles both possible outcomes: PaySeller or RefundBuyer
-        match decision {
-            DisputeDecision::PaySeller => {
-                // Transfer funds from escrow to the seller
-                // This awards the payment to the seller as decided by the arbitrator
-                let transfer_result = token_client.transfer(
-                    &env.current_contract_address(),
-                    &payment.seller,
-                    &payment.amount,
-                );
-                
-                // Validate the transfer was successful
-                // This ensures the fund distribution actually occurred
-                if transfer_result != () {
-                    return Err(PaymentEscrowError::TransferFailed);
-                }
-
-                // Update payment status to Completed to reflect seller victory
-                // This marks the payment as successfully completed
-                let updated_payment = Payment {
-                    status: PaymentStatus::Completed,
-                    ..payment
-                };
-                
-                // Persist the updated payment status to storage
-                // This ensures the resolution is permanent
-                env.storage()
-                    .persistent()
-                    .set(&payment_id, &updated_payment);
-            },
-            DisputeDecision::RefundBuyer => {
-                // Transfer funds from escrow back to the buyer
-                // This refunds the payment to the buyer as decided by the arbitrator
-                let transfer_result = token_client.transfer(
-                    &env.current_contract_address(),
-                    &payment.buyer,
-                    &payment.amount,
-                );
-                
-                // Validate the transfer was successful
-                // This ensures the refund actually occurred
-                if transfer_result != () {
-                    return Err(PaymentEscrowError::TransferFailed);
-                }
-
-                // Update payment status to Refunded to reflect buyer victory
-                // This marks the payment as refunded to the buyer
-                let updated_payment = Payment {
-                    status: PaymentStatus::Refunded,
-                    ..payment
-                };
-                
-                // Persist the updated payment status to storage
-                // This ensures the resolution is permanent
-                env.storage()
-                    .persistent()
-                    .set(&payment_id, &updated_payment);
-            }
+        // Transfer the buyer's share of the remainder back to the buyer
+        // Tranches already released to the seller are not clawed back
+        if buyer_amount > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &payment.buyer,
+                &buyer_amount,
+            );
         }
 
+        // Transfer the seller's share of the remainder to the seller,
+        // withholding the platform fee
+        if seller_amount > 0 {
+            pay_seller(&env, &payment, seller_amount);
+        }
+
+        // Update payment status to reflect the ruling
+        // Completed (seller wins), Refunded (buyer wins) or SplitResolved
+        let updated_payment = Payment {
+            status,
+            released_amount: payment.released_amount + seller_amount,
+            remaining_amount: 0,
+            ..payment
+        };
+
+        // Persist the updated payment status to storage
+        // This ensures the resolution is permanent
+        save_payment(&env, &updated_payment);
+
         // Create dispute resolved event for record keeping
         // This provides transparency and enables off-chain resolution tracking
         let dispute_resolved_event = DisputeResolvedEvent {
             order_id: payment_id,
             resolution: decision,
             admin: arbitrator,
+            buyer_amount,
+            seller_amount,
+            evidence_count,
+            arbitrator_fee,
         };
 
         // Store dispute resolved event in persistent storage
@@ -248,6 +306,7 @@ impl DisputeInterface for PaymentEscrowContract {
         env.storage()
             .persistent()
             .set(&(DataKey::ResolvedDisputes, payment_id), &dispute_resolved_event);
+        extend_payment_ttl(&env, &updated_payment);
 
         // Emit an event for transparency and off-chain tracking
         // This allows external systems to monitor dispute resolutions
@@ -255,4 +314,127 @@ impl DisputeInterface for PaymentEscrowContract {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Allows the buyer or seller to append an evidence entry to a disputed payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the disputed payment
+    /// * `submitter` - The address submitting the evidence (must be buyer or seller)
+    /// * `content_hash` - Hash of the evidence content stored off-chain
+    /// * `uri` - Location of the evidence content
+    /// 
+    /// # Returns
+    /// * `Result<u32, PaymentEscrowError>` - Index of the new entry or error
+    /// 
+    /// # Security
+    /// * Requires submitter authentication
+    /// * Only accepted while the payment is Disputed
+    /// * Bounded to MAX_EVIDENCE_ENTRIES entries and MAX_EVIDENCE_URI_LEN bytes per URI
+    fn submit_evidence(
+        env: Env,
+        payment_id: u128,
+        submitter: Address,
+        content_hash: BytesN<32>,
+        uri: String,
+    ) -> Result<u32, PaymentEscrowError> {
+        // Authentication - submitter must authorize this transaction
+        submitter.require_auth();
+
+        // Retrieve the payment details from persistent storage
+        let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
+
+        // Authorization check: only payment participants can submit evidence
+        if payment.buyer != submitter && payment.seller != submitter {
+            return Err(PaymentEscrowError::UnauthorizedAccess);
+        }
+
+        // Status validation: the evidence log is only open during a dispute
+        if payment.status != PaymentStatus::Disputed {
+            return Err(PaymentEscrowError::NotValid);
+        }
+
+        // Input validation: bound the size of each entry
+        if uri.is_empty() || uri.len() > MAX_EVIDENCE_URI_LEN {
+            return Err(PaymentEscrowError::EvidenceTooLarge);
+        }
+
+        // Input validation: bound the number of entries per dispute
+        let mut evidence = Self::get_evidence(env.clone(), payment_id);
+        if evidence.len() >= MAX_EVIDENCE_ENTRIES {
+            return Err(PaymentEscrowError::EvidenceLimitReached);
+        }
+
+        let index = evidence.len();
+        evidence.push_back(Evidence {
+            submitter: submitter.clone(),
+            content_hash,
+            uri,
+            timestamp: env.ledger().timestamp(),
+        });
+
+        // Persist the updated evidence log to storage
+        env.storage()
+            .persistent()
+            .set(&DataKey::Evidence(payment_id), &evidence);
+        extend_payment_ttl(&env, &payment);
+
+        // Emit an event so the counterparty and arbitrators can follow the log
+        env.events()
+            .publish((symbol_short!("evidence"), payment_id), (submitter, index));
+
+        Ok(index)
+    }
+
+    /// Retrieves the evidence log attached to a payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Vec<Evidence>` - Evidence entries in submission order (empty if none)
+    fn get_evidence(env: Env, payment_id: u128) -> Vec<Evidence> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Evidence(payment_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Retrieves the arbitrator ruling recorded for a resolved dispute
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the resolved payment
+    /// 
+    /// # Returns
+    /// * `Result<DisputeResolvedEvent, PaymentEscrowError>` - Ruling details or error if not found
+    fn get_dispute_resolution(env: Env, payment_id: u128) -> Result<DisputeResolvedEvent, PaymentEscrowError> {
+        env.storage()
+            .persistent()
+            .get(&(DataKey::ResolvedDisputes, payment_id))
+            .ok_or(PaymentEscrowError::NotFound)
+    }
+}
+
+/// Pays the panel arbitrator fee for a payment out of its escrowed remainder
+/// 
+/// The fee is split equally between every arbitrator who cast a vote; any
+/// rounding dust stays with the remainder. Returns the total amount paid.
+fn pay_panel_fee(env: &Env, payment: &Payment, panel: &DisputePanel) -> i128 {
+    let voters = panel.votes.len() as i128;
+    let total_fee = payment.remaining_amount * panel.arbitrator_fee_bps as i128 / MAX_BPS as i128;
+    let share = total_fee / voters;
+    if share <= 0 {
+        return 0;
+    }
+
+    let token_client = TokenClient::new(env, &payment.token);
+    for vote in panel.votes.iter() {
+        token_client.transfer(&env.current_contract_address(), &vote.arbitrator, &share);
+        env.events()
+            .publish((symbol_short!("arb_fee"), payment.id), (vote.arbitrator, share));
+    }
+
+    share * voters
+}
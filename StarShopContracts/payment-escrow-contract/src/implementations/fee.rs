@@ -0,0 +1,241 @@
+use crate::{
+    datatypes::{DataKey, FeeConfig, Payment, PaymentEscrowError, MAX_BPS},
+    implementations::arbitrator::{require_admin, require_arbitrator},
+    interface::FeeInterface,
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::token::Client as TokenClient;
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+/// Implementation of the FeeInterface trait for PaymentEscrowContract
+/// This module handles the marketplace fee schedule, including fee configuration
+/// by the admin, per-token overrides, fee accrual and fee withdrawal.
+#[contractimpl]
+impl FeeInterface for PaymentEscrowContract {
+
+    /// Sets the default platform fee and the address that receives accrued fees
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - The contract admin authorizing the change
+    /// * `fee_bps` - Default fee in basis points deducted from seller payouts
+    /// * `recipient` - The address allowed to withdraw accrued fees
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from the admin, since the recipient can
+    ///   withdraw every fee accrued so far
+    /// * Rejects fees above 100% (10,000 basis points)
+    fn set_fee_config(
+        env: Env,
+        admin: Address,
+        fee_bps: u32,
+        recipient: Address,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - admin must authorize this transaction
+        admin.require_auth();
+        require_admin(&env, &admin)?;
+
+        // Input validation: a fee can never exceed the payout itself
+        if fee_bps > MAX_BPS {
+            return Err(PaymentEscrowError::InvalidFee);
+        }
+
+        let fee_config = FeeConfig { fee_bps, recipient };
+        env.storage()
+            .persistent()
+            .set(&DataKey::FeeConfig, &fee_config);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("fee_cfg"), admin), fee_config);
+
+        Ok(())
+    }
+
+    /// Sets or clears the fee override for a specific token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `token` - The token contract address the override applies to
+    /// * `fee_bps` - Fee in basis points for this token, or None to use the default
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from an existing arbitrator
+    /// * Rejects fees above 100% (10,000 basis points)
+    fn set_token_fee(
+        env: Env,
+        arbitrator: Address,
+        token: Address,
+        fee_bps: Option<u32>,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - arbitrator must authorize this transaction
+        arbitrator.require_auth();
+        require_arbitrator(&env, &arbitrator)?;
+
+        match fee_bps {
+            Some(fee_bps) => {
+                // Input validation: a fee can never exceed the payout itself
+                if fee_bps > MAX_BPS {
+                    return Err(PaymentEscrowError::InvalidFee);
+                }
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::TokenFeeBps(token.clone()), &fee_bps);
+            }
+            None => {
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::TokenFeeBps(token.clone()));
+            }
+        }
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("token_fee"), token), fee_bps);
+
+        Ok(())
+    }
+
+    /// Retrieves the default fee schedule
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Result<FeeConfig, PaymentEscrowError>` - Fee configuration or error if not configured
+    fn get_fee_config(env: Env) -> Result<FeeConfig, PaymentEscrowError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FeeConfig)
+            .ok_or(PaymentEscrowError::FeeNotConfigured)
+    }
+
+    /// Retrieves the fee in basis points applied to new payments in a token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `u32` - The token override if set, otherwise the default fee (0 if unconfigured)
+    fn get_token_fee_bps(env: Env, token: Address) -> u32 {
+        fee_bps_for(&env, &token)
+    }
+
+    /// Retrieves the fees accrued in a token and not yet withdrawn
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `i128` - The accrued fee balance held by the contract
+    fn get_accrued_fees(env: Env, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AccruedFees(token))
+            .unwrap_or(0)
+    }
+
+    /// Withdraws all accrued fees in a token to the fee recipient
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `Result<i128, PaymentEscrowError>` - The withdrawn amount or error
+    /// 
+    /// # Security
+    /// * Requires authentication from the configured fee recipient
+    /// * Only moves fees, never escrowed payment funds
+    fn withdraw_fees(env: Env, token: Address) -> Result<i128, PaymentEscrowError> {
+        let fee_config = Self::get_fee_config(env.clone())?;
+
+        // Authentication - only the fee recipient can withdraw
+        fee_config.recipient.require_auth();
+
+        let accrued = Self::get_accrued_fees(env.clone(), token.clone());
+        if accrued <= 0 {
+            return Err(PaymentEscrowError::InsufficientFunds);
+        }
+
+        // Reset the accrued balance before transferring out
+        env.storage()
+            .persistent()
+            .set(&DataKey::AccruedFees(token.clone()), &0_i128);
+
+        let token_client = TokenClient::new(&env, &token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &fee_config.recipient,
+            &accrued,
+        );
+
+        // Emit an event for transparency and off-chain tracking
+        env.events().publish(
+            (symbol_short!("fees_wd"), token),
+            (fee_config.recipient, accrued),
+        );
+
+        Ok(accrued)
+    }
+}
+
+/// Resolves the fee in basis points for new payments in a token
+/// 
+/// A per-token override takes precedence over the default schedule.
+/// Without any configuration no fee is charged.
+pub(crate) fn fee_bps_for(env: &Env, token: &Address) -> u32 {
+    if let Some(fee_bps) = env
+        .storage()
+        .persistent()
+        .get(&DataKey::TokenFeeBps(token.clone()))
+    {
+        return fee_bps;
+    }
+
+    env.storage()
+        .persistent()
+        .get::<_, FeeConfig>(&DataKey::FeeConfig)
+        .map(|config| config.fee_bps)
+        .unwrap_or(0)
+}
+
+/// Pays part of an escrowed payment out to the seller, withholding the platform fee
+/// 
+/// The fee is computed from the rate snapshotted on the payment at creation,
+/// kept in the contract and added to the accrued balance for the token.
+/// Returns the fee withheld.
+pub(crate) fn pay_seller(env: &Env, payment: &Payment, gross_amount: i128) -> i128 {
+    let fee = gross_amount * payment.fee_bps as i128 / MAX_BPS as i128;
+    let net_amount = gross_amount - fee;
+
+    let token_client = TokenClient::new(env, &payment.token);
+    if net_amount > 0 {
+        token_client.transfer(
+            &env.current_contract_address(),
+            &payment.seller,
+            &net_amount,
+        );
+    }
+
+    if fee > 0 {
+        let key = DataKey::AccruedFees(payment.token.clone());
+        let accrued: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(accrued + fee));
+
+        // Emit an event so off-chain systems can track fee revenue
+        env.events()
+            .publish((symbol_short!("fee"), payment.id), fee);
+    }
+
+    fee
+}
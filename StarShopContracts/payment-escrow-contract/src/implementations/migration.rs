@@ -0,0 +1,177 @@
+use crate::{
+    datatypes::{
+        DataKey, LegacyPayment, MigrationStatus, Payment, PaymentEscrowError, PaymentStatus,
+        ReleaseTranche, DEFAULT_POLICY_ID, MAX_MIGRATION_BATCH, PAYMENT_SCHEMA_VERSION,
+    },
+    implementations::{
+        arbitrator::require_arbitrator,
+        query::save_payment,
+        retention::{extend_config_ttl, payment_ttl_ledgers},
+    },
+    interface::{DeliveryInterface, MigrationInterface, PaymentInterface},
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+/// Schema version assumed for contracts initialized before versioning existed
+const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+/// Implementation of the MigrationInterface trait for PaymentEscrowContract
+/// This module moves payments written by older contract code to the current
+/// storage layout after an upgrade.
+#[contractimpl]
+impl MigrationInterface for PaymentEscrowContract {
+
+    /// Moves the next batch of payments to the current storage layout
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the migration
+    /// * `batch_size` - Number of payment ids to process (at most MAX_MIGRATION_BATCH)
+    /// 
+    /// # Returns
+    /// * `Result<MigrationStatus, PaymentEscrowError>` - Progress after this batch
+    /// 
+    /// # Security
+    /// * Requires authentication from an existing arbitrator
+    /// * Bounded batches keep each call within transaction resource limits
+    fn migrate_payments(env: Env, arbitrator: Address, batch_size: u32) -> Result<MigrationStatus, PaymentEscrowError> {
+        // Authentication - arbitrator must authorize this transaction
+        arbitrator.require_auth();
+        require_arbitrator(&env, &arbitrator)?;
+
+        if batch_size == 0 || batch_size > MAX_MIGRATION_BATCH {
+            return Err(PaymentEscrowError::InvalidBatchSize);
+        }
+
+        // Nothing to do once the stored layout is current
+        if schema_version(&env) >= PAYMENT_SCHEMA_VERSION {
+            return Ok(Self::get_migration_status(env));
+        }
+
+        let payment_count = PaymentEscrowContract::get_payment_count(&env);
+        let cursor = migration_cursor(&env);
+        let end = cursor.saturating_add(batch_size as u128).min(payment_count);
+
+        // Rewrite every legacy record in the batch in the current layout,
+        // adding it to the buyer, seller and status indexes on the way
+        let mut migrated: u32 = 0;
+        let mut ledgers: u32 = 0;
+        let mut tokens: Vec<Address> = Vec::new(&env);
+        for payment_id in (cursor + 1)..=end {
+            if let Some(legacy) = env.storage().persistent().get::<_, LegacyPayment>(&payment_id) {
+                let payment = upgrade_legacy_payment(&env, legacy);
+                save_payment(&env, &payment);
+                ledgers = ledgers.max(payment_ttl_ledgers(&env, &payment));
+                if !tokens.contains(&payment.token) {
+                    tokens.push_back(payment.token);
+                }
+                migrated += 1;
+            }
+        }
+
+        // Extend the configuration once per token rather than once per payment
+        for token in tokens.iter() {
+            extend_config_ttl(&env, &token, DEFAULT_POLICY_ID, ledgers);
+        }
+
+        if end >= payment_count {
+            // Every payment has been visited, record the new layout
+            env.storage()
+                .persistent()
+                .set(&DataKey::SchemaVersion, &PAYMENT_SCHEMA_VERSION);
+            env.storage().persistent().remove(&DataKey::MigrationCursor);
+        } else {
+            env.storage()
+                .persistent()
+                .set(&DataKey::MigrationCursor, &end);
+        }
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("migrate"), arbitrator), (end, migrated));
+
+        Ok(Self::get_migration_status(env))
+    }
+
+    /// Retrieves the progress of the payment storage migration
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `MigrationStatus` - Stored schema version and migration progress
+    fn get_migration_status(env: Env) -> MigrationStatus {
+        let schema_version = schema_version(&env);
+        let payment_count = PaymentEscrowContract::get_payment_count(&env);
+        let complete = schema_version >= PAYMENT_SCHEMA_VERSION;
+
+        MigrationStatus {
+            schema_version,
+            migrated_up_to: if complete { payment_count } else { migration_cursor(&env) },
+            payment_count,
+            complete,
+        }
+    }
+}
+
+/// Stored schema version, or the legacy version if none was ever recorded
+pub(crate) fn schema_version(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SchemaVersion)
+        .unwrap_or(LEGACY_SCHEMA_VERSION)
+}
+
+/// Converts a schema version 1 record into the current payment layout
+/// 
+/// Version 1 payments were released in full with no platform fee, so they get
+/// a single tranche covering the whole amount and a zero fee. Funds are still escrowed while the payment is
+/// Pending, Delivered or Disputed; otherwise they were paid to the seller
+/// (Completed) or returned to the buyer. Version 1 had no inspection window, so
+/// a Delivered payment keeps the buyer protected until its original expiry,
+/// and a Pending one gets the default policy's inspection period on delivery.
+pub(crate) fn upgrade_legacy_payment(env: &Env, legacy: LegacyPayment) -> Payment {
+    let escrowed = matches!(
+        legacy.status,
+        PaymentStatus::Pending | PaymentStatus::Delivered | PaymentStatus::Disputed
+    );
+    let released_amount = if legacy.status == PaymentStatus::Completed { legacy.amount } else { 0 };
+    let inspection_deadline = if legacy.status == PaymentStatus::Delivered { legacy.expiry } else { 0 };
+
+    // Amendments and releases work on the tranche list, so it must never be empty
+    let mut tranches = Vec::new(env);
+    tranches.push_back(ReleaseTranche {
+        amount: legacy.amount,
+        description: legacy.description.clone(),
+        released: legacy.status == PaymentStatus::Completed,
+    });
+
+    Payment {
+        id: legacy.id,
+        buyer: legacy.buyer,
+        seller: legacy.seller,
+        amount: legacy.amount,
+        token: legacy.token,
+        status: legacy.status,
+        created_at: legacy.created_at,
+        expiry: legacy.expiry,
+        dispute_deadline: legacy.dispute_deadline,
+        description: legacy.description,
+        tranches,
+        released_amount,
+        remaining_amount: if escrowed { legacy.amount } else { 0 },
+        fee_bps: 0,
+        inspection_deadline,
+        order_id: None,
+        policy_id: DEFAULT_POLICY_ID,
+        inspection_period: PaymentEscrowContract::get_inspection_period(env.clone()),
+    }
+}
+
+fn migration_cursor(env: &Env) -> u128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::MigrationCursor)
+        .unwrap_or(0)
+}
@@ -2,4 +2,12 @@ pub mod create;
 pub mod delivery;
 pub mod dispute;
 pub mod claim;
-pub mod arbitrator;
\ No newline at end of file
+pub mod arbitrator;
+pub mod fee;
+pub mod query;
+pub mod order;
+pub mod policy;
+pub mod amendment;
+pub mod registry;
+pub mod migration;
+pub mod retention;
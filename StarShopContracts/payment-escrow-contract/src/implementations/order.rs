@@ -0,0 +1,179 @@
+use crate::{
+    datatypes::{DataKey, Order, OrderItem, Payment, PaymentEscrowError, ReleaseTranche, MAX_ORDER_ITEMS},
+    implementations::{
+        create::{fund_escrow, record_payment, EscrowTerms},
+        query::read_payment,
+        registry::check_token_amount,
+        retention::extend_payment_ttl,
+    },
+    interface::OrderInterface,
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, String, Vec};
+
+/// Implementation of the OrderInterface trait for PaymentEscrowContract
+/// This module handles multi-seller orders: one buyer transfer funds a set of
+/// line items, each escrowed as its own payment under shared order terms.
+#[contractimpl]
+impl OrderInterface for PaymentEscrowContract {
+
+    /// Creates an order escrowing several sellers' line items in one transfer
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - The address of the buyer (payer)
+    /// * `token` - The token contract address for the order
+    /// * `items` - Line items, each with its seller, amount and description
+    /// * `expiry_days` - Number of days until the order expires (0 = policy default)
+    /// * `policy_id` - Escrow policy profile shared by every line item (0 = default)
+    /// * `description` - Human-readable description of the order
+    /// 
+    /// # Returns
+    /// * `Result<u128, PaymentEscrowError>` - Order ID on success, error on failure
+    /// 
+    /// # Business Logic
+    /// * Transfers the order total from buyer to escrow in a single transfer
+    /// * Creates one payment per line item sharing the order's expiry and dispute deadline
+    /// * Each seller confirms, and each line is disputed or refunded, through its own payment
+    /// 
+    /// # Security
+    /// * Requires buyer authentication
+    /// * Validates every line item amount against the token limits and prevents self-payment
+    /// * Bounds the number of line items to MAX_ORDER_ITEMS
+    fn create_order(
+        env: Env,
+        buyer: Address,
+        token: Address,
+        items: Vec<OrderItem>,
+        expiry_days: u32,
+        policy_id: u32,
+        description: String,
+    ) -> Result<u128, PaymentEscrowError> {
+        // Authentication - buyer must authorize this transaction
+        buyer.require_auth();
+
+        // Input validation: an order needs at least one and a bounded number of items
+        if items.is_empty() {
+            return Err(PaymentEscrowError::InvalidAmount);
+        }
+        if items.len() > MAX_ORDER_ITEMS {
+            return Err(PaymentEscrowError::TooManyItems);
+        }
+
+        // Input validation: every line item must be payable to someone else
+        let mut total_amount: i128 = 0;
+        for item in items.iter() {
+            if item.amount <= 0 {
+                return Err(PaymentEscrowError::InvalidAmount);
+            }
+            if item.seller == buyer {
+                return Err(PaymentEscrowError::CannotPaySelf);
+            }
+            // Token validation: each line item is a payment bound by the token limits
+            check_token_amount(&env, &token, item.amount)?;
+            total_amount = total_amount
+                .checked_add(item.amount)
+                .ok_or(PaymentEscrowError::InvalidAmount)?;
+        }
+
+        // Generate unique order ID by incrementing the order counter
+        let order_id: u128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::OrderCounter)
+            .unwrap_or(0u128)
+            + 1;
+
+        // Fund the whole order with one buyer transfer
+        let terms = EscrowTerms::new(&env, buyer.clone(), token.clone(), expiry_days, policy_id, Some(order_id))?;
+        fund_escrow(&env, &terms, total_amount)?;
+
+        // Escrow each line item as its own payment under the shared terms
+        let mut payment_ids = Vec::new(&env);
+        for item in items.iter() {
+            let mut tranches = Vec::new(&env);
+            tranches.push_back(ReleaseTranche {
+                amount: item.amount,
+                description: item.description.clone(),
+                released: false,
+            });
+            let payment_id = record_payment(
+                &env,
+                &terms,
+                item.seller,
+                tranches,
+                item.amount,
+                item.description,
+            );
+            payment_ids.push_back(payment_id);
+        }
+
+        let order = Order {
+            id: order_id,
+            buyer,
+            token,
+            total_amount,
+            payment_ids,
+            created_at: terms.created_at,
+            expiry: terms.expiry,
+            dispute_deadline: terms.dispute_deadline,
+            description,
+            policy_id,
+        };
+
+        // Persist the order and the updated order counter
+        env.storage()
+            .persistent()
+            .set(&DataKey::OrderCounter, &order_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+
+        // Line items share the order's deadlines and configuration, so extending
+        // one of them keeps the order and the configuration alive
+        if let Some(payment) = read_payment(&env, order.payment_ids.get(0).unwrap()) {
+            extend_payment_ttl(&env, &payment);
+        }
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("order"), order_id), order.payment_ids);
+
+        Ok(order_id)
+    }
+
+    /// Retrieves an order by its unique identifier
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `order_id` - The unique identifier of the order
+    /// 
+    /// # Returns
+    /// * `Result<Order, PaymentEscrowError>` - Order details or error if not found
+    fn get_order(env: Env, order_id: u128) -> Result<Order, PaymentEscrowError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .ok_or(PaymentEscrowError::NotFound)
+    }
+
+    /// Retrieves the line item payments of an order
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `order_id` - The unique identifier of the order
+    /// 
+    /// # Returns
+    /// * `Result<Vec<Payment>, PaymentEscrowError>` - Line item payments in order, or error if not found
+    fn get_order_payments(env: Env, order_id: u128) -> Result<Vec<Payment>, PaymentEscrowError> {
+        let order = Self::get_order(env.clone(), order_id)?;
+
+        let mut payments = Vec::new(&env);
+        for payment_id in order.payment_ids.iter() {
+            let payment: Payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
+            payments.push_back(payment);
+        }
+
+        Ok(payments)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    datatypes::{DataKey, DisputeWindow, PaymentEscrowError, PolicyProfile, DEFAULT_POLICY_ID, MAX_EXPIRY_DAYS, MAX_INSPECTION_PERIOD},
+    implementations::arbitrator::require_arbitrator,
+    interface::{DeliveryInterface, PolicyInterface},
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+/// Implementation of the PolicyInterface trait for PaymentEscrowContract
+/// This module handles the escrow policy profiles that set expiry, dispute
+/// and inspection terms for different product categories.
+#[contractimpl]
+impl PolicyInterface for PaymentEscrowContract {
+
+    /// Creates or replaces an escrow policy profile
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `policy_id` - Identifier payments use to select the profile (0 is reserved)
+    /// * `profile` - Expiry, dispute window and inspection terms
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from an existing arbitrator
+    /// * The built-in default policy cannot be replaced
+    /// * Validates every term against the contract bounds
+    fn set_policy(
+        env: Env,
+        arbitrator: Address,
+        policy_id: u32,
+        profile: PolicyProfile,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - arbitrator must authorize this transaction
+        arbitrator.require_auth();
+        require_arbitrator(&env, &arbitrator)?;
+
+        if policy_id == DEFAULT_POLICY_ID {
+            return Err(PaymentEscrowError::InvalidPolicy);
+        }
+
+        // Input validation: expiry terms must be ordered and bounded
+        if profile.default_expiry_days == 0
+            || profile.default_expiry_days > profile.max_expiry_days
+            || profile.max_expiry_days > MAX_EXPIRY_DAYS
+        {
+            return Err(PaymentEscrowError::InvalidPolicy);
+        }
+
+        // Input validation: disputes must be possible and the window must fit the longest expiry
+        let window_valid = match profile.dispute_window {
+            DisputeWindow::AfterCreation(days) => days > 0 && days <= profile.max_expiry_days,
+            DisputeWindow::BeforeExpiry(days) => days < profile.max_expiry_days,
+        };
+        if !window_valid {
+            return Err(PaymentEscrowError::InvalidPolicy);
+        }
+
+        // Input validation: inspection window must be positive and bounded
+        if profile.inspection_period == 0 || profile.inspection_period > MAX_INSPECTION_PERIOD {
+            return Err(PaymentEscrowError::InvalidPolicy);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Policy(policy_id), &profile);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("policy"), policy_id), profile);
+
+        Ok(())
+    }
+
+    /// Retrieves an escrow policy profile
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `policy_id` - The policy identifier (0 for the built-in default)
+    /// 
+    /// # Returns
+    /// * `Result<PolicyProfile, PaymentEscrowError>` - The profile or error if not found
+    fn get_policy(env: Env, policy_id: u32) -> Result<PolicyProfile, PaymentEscrowError> {
+        if policy_id == DEFAULT_POLICY_ID {
+            return Ok(default_policy(&env));
+        }
+
+        env.storage()
+            .persistent()
+            .get(&DataKey::Policy(policy_id))
+            .ok_or(PaymentEscrowError::PolicyNotFound)
+    }
+}
+
+/// The built-in policy used when a payment selects policy 0
+/// 
+/// Reproduces the original escrow terms: payments default to 30 days with no
+/// upper limit, disputes close 7 days before expiry (or at expiry for payments
+/// shorter than 7 days), and the contract-wide inspection period applies.
+fn default_policy(env: &Env) -> PolicyProfile {
+    PolicyProfile {
+        default_expiry_days: 30,
+        max_expiry_days: u32::MAX,
+        dispute_window: DisputeWindow::BeforeExpiry(7),
+        inspection_period: PaymentEscrowContract::get_inspection_period(env.clone()),
+    }
+}
+
+/// Computes when disputes close for a payment running from `created_at` to `expiry`
+pub(crate) fn dispute_deadline(policy: &PolicyProfile, created_at: u64, expiry: u64) -> u64 {
+    match policy.dispute_window {
+        DisputeWindow::AfterCreation(days) => (created_at + days as u64 * 24 * 60 * 60).min(expiry),
+        DisputeWindow::BeforeExpiry(days) => {
+            let cutoff = days as u64 * 24 * 60 * 60;
+            if expiry - created_at >= cutoff {
+                expiry - cutoff
+            } else {
+                expiry
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    datatypes::{DataKey, LegacyPayment, Payment, PaymentIndex, PaymentIndexSlots, PaymentStatus, MAX_PAGE_SIZE},
+    implementations::{migration::upgrade_legacy_payment, retention::{extend_if_present, extend_index_slot_ttl, is_terminal, payment_ttl_ledgers}},
+    interface::QueryInterface,
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+/// Implementation of the QueryInterface trait for PaymentEscrowContract
+/// This module maintains the per-buyer, per-seller and per-status payment
+/// indexes and exposes paginated queries over them.
+#[contractimpl]
+impl QueryInterface for PaymentEscrowContract {
+
+    /// Lists the payments created by a buyer
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - The buyer address
+    /// * `cursor` - Slot in the buyer's index to start from, in creation order
+    /// * `limit` - Number of slots to read (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * `Vec<Payment>` - The requested page of payments
+    fn get_payments_by_buyer(env: Env, buyer: Address, cursor: u32, limit: u32) -> Vec<Payment> {
+        load_page(&env, PaymentIndex::Buyer(buyer), cursor, limit)
+    }
+
+    /// Lists the payments addressed to a seller
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `seller` - The seller address
+    /// * `cursor` - Slot in the seller's index to start from, in creation order
+    /// * `limit` - Number of slots to read (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * `Vec<Payment>` - The requested page of payments
+    fn get_payments_by_seller(env: Env, seller: Address, cursor: u32, limit: u32) -> Vec<Payment> {
+        load_page(&env, PaymentIndex::Seller(seller), cursor, limit)
+    }
+
+    /// Lists the payments currently in a given status
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `status` - The payment status to list
+    /// * `cursor` - Slot in the status index to start from, in order of entering the status
+    /// * `limit` - Number of slots to read (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * `Vec<Payment>` - The requested page of payments
+    fn get_payments_by_status(env: Env, status: PaymentStatus, cursor: u32, limit: u32) -> Vec<Payment> {
+        load_page(&env, PaymentIndex::Status(status), cursor, limit)
+    }
+
+    /// Number of slots allocated in a payment index
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `index` - The buyer, seller or status index
+    /// 
+    /// # Returns
+    /// * `u32` - Cursor value at which the index ends
+    fn get_index_len(env: Env, index: PaymentIndex) -> u32 {
+        index_len(&env, &index)
+    }
+}
+
+/// Loads a payment from either storage layout
+/// 
+/// Payments are stored under `DataKey::Payment`. Records written before schema
+/// version 2 live under their raw `u128` id, in the `LegacyPayment` layout,
+/// until `migrate_payments` (or their next update) moves them, so reads fall
+/// back to the legacy key and convert the record.
+pub(crate) fn read_payment(env: &Env, payment_id: u128) -> Option<Payment> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Payment(payment_id))
+        .or_else(|| {
+            env.storage()
+                .persistent()
+                .get::<_, LegacyPayment>(&payment_id)
+                .map(|legacy| upgrade_legacy_payment(env, legacy))
+        })
+}
+
+/// Persists a payment and keeps the payment indexes consistent
+/// 
+/// Every write also extends the storage lifetime of the payment and of the
+/// index slots it touched; callers extend the records the payment depends on
+/// and the configuration once per invocation. New and legacy payments are added to the buyer, seller and status indexes. For existing
+/// payments whose status changed, the id moves from its slot in the old status
+/// index to a new slot at the end of the new one. Every payment write should go through this function, and always
+/// writes the current layout, dropping any legacy record.
+pub(crate) fn save_payment(env: &Env, payment: &Payment) {
+    let previous = read_payment(env, payment.id);
+
+    // Remember when the payment first reached a final status
+    let was_terminal = previous.as_ref().is_some_and(|previous| is_terminal(&previous.status));
+    if is_terminal(&payment.status) && !was_terminal {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PaymentClosedAt(payment.id), &env.ledger().timestamp());
+    }
+
+    // Legacy records predate the indexes and are indexed on their first write
+    let indexed = env.storage().persistent().has(&DataKey::IndexSlots(payment.id));
+    let ledgers = payment_ttl_ledgers(env, payment);
+    match previous {
+        Some(previous) if indexed => {
+            if previous.status != payment.status {
+                let slot = move_status_index(env, &previous, payment);
+                extend_index_slot_ttl(env, PaymentIndex::Status(payment.status.clone()), slot, ledgers);
+                extend_if_present(env, &DataKey::IndexSlots(payment.id), ledgers);
+            }
+        }
+        _ => {
+            let slots = index_payment(env, payment);
+            extend_index_slot_ttl(env, PaymentIndex::Buyer(payment.buyer.clone()), slots.buyer, ledgers);
+            extend_index_slot_ttl(env, PaymentIndex::Seller(payment.seller.clone()), slots.seller, ledgers);
+            extend_index_slot_ttl(env, PaymentIndex::Status(payment.status.clone()), slots.status, ledgers);
+            extend_if_present(env, &DataKey::IndexSlots(payment.id), ledgers);
+        }
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Payment(payment.id), payment);
+    if env.storage().persistent().has(&payment.id) {
+        env.storage().persistent().remove(&payment.id);
+    }
+
+    // Keep the record alive until its deadlines and retention have passed
+    extend_if_present(env, &DataKey::Payment(payment.id), ledgers);
+    if is_terminal(&payment.status) {
+        extend_if_present(env, &DataKey::PaymentClosedAt(payment.id), ledgers);
+    }
+}
+
+/// Adds a new payment to the end of its buyer, seller and status indexes
+/// 
+/// Also used by the migration to backfill payments written before the indexes existed.
+pub(crate) fn index_payment(env: &Env, payment: &Payment) -> PaymentIndexSlots {
+    let slots = PaymentIndexSlots {
+        buyer: push_index(env, &PaymentIndex::Buyer(payment.buyer.clone()), payment.id),
+        seller: push_index(env, &PaymentIndex::Seller(payment.seller.clone()), payment.id),
+        status: push_index(env, &PaymentIndex::Status(payment.status.clone()), payment.id),
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::IndexSlots(payment.id), &slots);
+    slots
+}
+
+/// Moves a payment from its previous status index to the end of its new one
+/// 
+/// Returns the payment's slot in the new status index.
+fn move_status_index(env: &Env, previous: &Payment, payment: &Payment) -> u32 {
+    let key = DataKey::IndexSlots(payment.id);
+    let mut slots = env
+        .storage()
+        .persistent()
+        .get::<_, PaymentIndexSlots>(&key)
+        .unwrap();
+    remove_status_index(env, &previous.status, slots.status);
+    slots.status = push_index(env, &PaymentIndex::Status(payment.status.clone()), payment.id);
+    env.storage().persistent().set(&key, &slots);
+    slots.status
+}
+
+/// Vacates the status index slot of a payment that is being archived
+/// 
+/// The buyer and seller slots are kept; listings skip payments that no longer exist.
+pub(crate) fn unindex_status(env: &Env, payment: &Payment) {
+    let key = DataKey::IndexSlots(payment.id);
+    if let Some(slots) = env.storage().persistent().get::<_, PaymentIndexSlots>(&key) {
+        remove_status_index(env, &payment.status, slots.status);
+    }
+}
+
+/// Number of slots ever allocated in an index, including vacated ones
+pub(crate) fn index_len(env: &Env, index: &PaymentIndex) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::IndexLen(index.clone()))
+        .unwrap_or(0)
+}
+
+fn push_index(env: &Env, index: &PaymentIndex, payment_id: u128) -> u32 {
+    let slot = index_len(env, index);
+    env.storage()
+        .persistent()
+        .set(&DataKey::IndexEntry(index.clone(), slot), &payment_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::IndexLen(index.clone()), &(slot + 1));
+    slot
+}
+
+fn remove_status_index(env: &Env, status: &PaymentStatus, slot: u32) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::IndexEntry(PaymentIndex::Status(status.clone()), slot));
+}
+
+/// Loads the payments held in a window of index slots
+/// 
+/// Vacated slots and archived payments are skipped, so a page can hold fewer
+/// than `limit` payments without the end of the index being reached.
+fn load_page(env: &Env, index: PaymentIndex, cursor: u32, limit: u32) -> Vec<Payment> {
+    let mut page = Vec::new(env);
+    let end = cursor
+        .saturating_add(limit.min(MAX_PAGE_SIZE))
+        .min(index_len(env, &index));
+    for slot in cursor..end {
+        let payment_id: Option<u128> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::IndexEntry(index.clone(), slot));
+        if let Some(payment) = payment_id.and_then(|payment_id| read_payment(env, payment_id)) {
+            page.push_back(payment);
+        }
+    }
+    page
+}
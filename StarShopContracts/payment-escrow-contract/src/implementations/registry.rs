@@ -0,0 +1,150 @@
+use crate::{
+    datatypes::{DataKey, PaymentEscrowError, TokenConfig},
+    implementations::arbitrator::require_arbitrator,
+    interface::TokenRegistryInterface,
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+/// Implementation of the TokenRegistryInterface trait for PaymentEscrowContract
+/// This module handles the allow-list of Stellar asset contracts accepted for
+/// escrow, together with their per-payment amount limits.
+#[contractimpl]
+impl TokenRegistryInterface for PaymentEscrowContract {
+
+    /// Accepts a token for escrow with per-payment limits, or updates its limits
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `token` - The token contract address to accept
+    /// * `min_amount` - Smallest payment amount accepted in this token
+    /// * `max_amount` - Largest payment amount accepted in this token
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from an existing arbitrator
+    /// * Limits must be positive and ordered
+    /// * (Re-)registering a token enables it
+    fn add_token(
+        env: Env,
+        arbitrator: Address,
+        token: Address,
+        min_amount: i128,
+        max_amount: i128,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - arbitrator must authorize this transaction
+        arbitrator.require_auth();
+        require_arbitrator(&env, &arbitrator)?;
+
+        // Input validation: limits must describe a non-empty positive range
+        if min_amount <= 0 || max_amount < min_amount {
+            return Err(PaymentEscrowError::InvalidAmount);
+        }
+
+        // Track the token in the registry list on first registration
+        let key = DataKey::TokenConfig(token.clone());
+        if !env.storage().persistent().has(&key) {
+            let mut tokens = Self::get_tokens(env.clone());
+            tokens.push_back(token.clone());
+            env.storage().persistent().set(&DataKey::Tokens, &tokens);
+        }
+
+        let token_config = TokenConfig {
+            min_amount,
+            max_amount,
+            enabled: true,
+        };
+        env.storage().persistent().set(&key, &token_config);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("token_add"), token), token_config);
+
+        Ok(())
+    }
+
+    /// Enables or disables a registered token without losing its limits
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `token` - The registered token contract address
+    /// * `enabled` - Whether new payments may use the token
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Disabling a token only blocks new payments and amendments
+    /// * Existing escrows in the token can still be released, refunded or disputed
+    fn set_token_enabled(
+        env: Env,
+        arbitrator: Address,
+        token: Address,
+        enabled: bool,
+    ) -> Result<(), PaymentEscrowError> {
+        // Authentication - arbitrator must authorize this transaction
+        arbitrator.require_auth();
+        require_arbitrator(&env, &arbitrator)?;
+
+        let key = DataKey::TokenConfig(token.clone());
+        let token_config: TokenConfig = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(PaymentEscrowError::TokenNotAccepted)?;
+
+        env.storage()
+            .persistent()
+            .set(&key, &TokenConfig { enabled, ..token_config });
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("token_en"), token), enabled);
+
+        Ok(())
+    }
+
+    /// Retrieves the acceptance rules of a token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `Option<TokenConfig>` - The token's limits and status, if registered
+    fn get_token_config(env: Env, token: Address) -> Option<TokenConfig> {
+        env.storage().persistent().get(&DataKey::TokenConfig(token))
+    }
+
+    /// Lists every registered token, enabled or not
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Vec<Address>` - Registered token contract addresses in registration order
+    fn get_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Tokens)
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+/// Checks that a token is accepted for escrow and the amount is within its limits
+pub(crate) fn check_token_amount(env: &Env, token: &Address, amount: i128) -> Result<(), PaymentEscrowError> {
+    let token_config = match PaymentEscrowContract::get_token_config(env.clone(), token.clone()) {
+        Some(token_config) if token_config.enabled => token_config,
+        _ => return Err(PaymentEscrowError::TokenNotAccepted),
+    };
+
+    if amount < token_config.min_amount || amount > token_config.max_amount {
+        return Err(PaymentEscrowError::AmountOutOfRange);
+    }
+
+    Ok(())
+}
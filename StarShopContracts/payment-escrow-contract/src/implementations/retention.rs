@@ -0,0 +1,245 @@
+use crate::{
+    datatypes::{
+        ArchivedPayment, DataKey, Payment, PaymentEscrowError, PaymentIndex, PaymentIndexSlots,
+        PaymentStatus, ARCHIVE_GRACE_PERIOD,
+        LEDGER_CLOSE_SECONDS, RETENTION_PERIOD,
+    },
+    implementations::query::{read_payment, unindex_status},
+    interface::RetentionInterface,
+    PaymentEscrowContract, PaymentEscrowContractArgs, PaymentEscrowContractClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, IntoVal, Val};
+
+/// Implementation of the RetentionInterface trait for PaymentEscrowContract
+/// This module keeps escrow records alive in persistent storage for as long
+/// as they can still be acted on, and archives closed payments afterwards.
+#[contractimpl]
+impl RetentionInterface for PaymentEscrowContract {
+
+    /// Extends the storage lifetime of a payment and the records it depends on
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error if not found
+    /// 
+    /// # Security
+    /// * Permissionless: only extends lifetimes, never changes payment state
+    fn bump_payment(env: Env, payment_id: u128) -> Result<(), PaymentEscrowError> {
+        if let Some(payment) = read_payment(&env, payment_id) {
+            extend_payment_ttl(&env, &payment);
+            return Ok(());
+        }
+
+        // Archived payments keep only their summary and ruling
+        let archive_key = DataKey::ArchivedPayment(payment_id);
+        if !env.storage().persistent().has(&archive_key) {
+            return Err(PaymentEscrowError::NotFound);
+        }
+        let max_ttl = env.storage().max_ttl();
+        extend_if_present(&env, &archive_key, max_ttl);
+        extend_if_present(&env, &(DataKey::ResolvedDisputes, payment_id), max_ttl);
+        env.storage().instance().extend_ttl(max_ttl, max_ttl);
+
+        Ok(())
+    }
+
+    /// Replaces a closed payment with its archived summary
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<ArchivedPayment, PaymentEscrowError>` - The archived summary or error
+    /// 
+    /// # Business Logic
+    /// * Only payments that reached a final status can be archived
+    /// * The full record is kept for RETENTION_PERIOD after the payment closed
+    /// * The payment, dispute event, evidence and panel are removed; the ruling is kept
+    /// 
+    /// # Security
+    /// * Permissionless: archived payments hold no funds
+    fn archive_payment(env: Env, payment_id: u128) -> Result<ArchivedPayment, PaymentEscrowError> {
+        let payment = read_payment(&env, payment_id).ok_or(PaymentEscrowError::NotFound)?;
+
+        if !is_terminal(&payment.status) {
+            return Err(PaymentEscrowError::PaymentNotTerminal);
+        }
+
+        let closed_at = closed_at(&env, &payment);
+        if env.ledger().timestamp() < closed_at.saturating_add(RETENTION_PERIOD) {
+            return Err(PaymentEscrowError::RetentionActive);
+        }
+
+        let archived = ArchivedPayment {
+            id: payment.id,
+            buyer: payment.buyer.clone(),
+            seller: payment.seller.clone(),
+            token: payment.token.clone(),
+            amount: payment.amount,
+            released_amount: payment.released_amount,
+            status: payment.status.clone(),
+            created_at: payment.created_at,
+            closed_at,
+            order_id: payment.order_id,
+        };
+
+        // Drop the full record and its dispute working data
+        let storage = env.storage().persistent();
+        storage.remove(&DataKey::Payment(payment_id));
+        storage.remove(&payment_id);
+        storage.remove(&DataKey::PaymentClosedAt(payment_id));
+        storage.remove(&(DataKey::DisputedPayments, payment_id));
+        storage.remove(&DataKey::Evidence(payment_id));
+        storage.remove(&DataKey::DisputePanel(payment_id));
+        unindex_status(&env, &payment);
+
+        let archive_key = DataKey::ArchivedPayment(payment_id);
+        storage.set(&archive_key, &archived);
+        let max_ttl = env.storage().max_ttl();
+        extend_if_present(&env, &archive_key, max_ttl);
+        extend_if_present(&env, &(DataKey::ResolvedDisputes, payment_id), max_ttl);
+
+        // Emit an event for transparency and off-chain tracking
+        env.events()
+            .publish((symbol_short!("archived"), payment_id), closed_at);
+
+        Ok(archived)
+    }
+
+    /// Retrieves the archived summary of a payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<ArchivedPayment, PaymentEscrowError>` - The summary or error if not archived
+    fn get_archived_payment(env: Env, payment_id: u128) -> Result<ArchivedPayment, PaymentEscrowError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ArchivedPayment(payment_id))
+            .ok_or(PaymentEscrowError::NotFound)
+    }
+}
+
+/// Whether a payment has reached a status it can no longer leave
+pub(crate) fn is_terminal(status: &PaymentStatus) -> bool {
+    matches!(
+        status,
+        PaymentStatus::Completed
+            | PaymentStatus::Refunded
+            | PaymentStatus::Expired
+            | PaymentStatus::SplitResolved
+            | PaymentStatus::Cancelled
+    )
+}
+
+/// Extends the payment and every record it depends on to outlive every deadline
+/// 
+/// Covers the payment, its dispute records, pending amendment and cancellation
+/// requests, its order, its index slots and the configuration it is settled under.
+/// Meant to be called once per invocation for the payment it acts on; batch
+/// entry points extend each payment with save_payment and the configuration once.
+pub(crate) fn extend_payment_ttl(env: &Env, payment: &Payment) {
+    let ledgers = payment_ttl_ledgers(env, payment);
+
+    extend_if_present(env, &DataKey::Payment(payment.id), ledgers);
+    extend_if_present(env, &payment.id, ledgers);
+    extend_if_present(env, &DataKey::PaymentClosedAt(payment.id), ledgers);
+    extend_if_present(env, &(DataKey::DisputedPayments, payment.id), ledgers);
+    extend_if_present(env, &(DataKey::ResolvedDisputes, payment.id), ledgers);
+    extend_if_present(env, &DataKey::Evidence(payment.id), ledgers);
+    extend_if_present(env, &DataKey::DisputePanel(payment.id), ledgers);
+
+    // Pending requests, including any top-up escrowed with an amendment
+    extend_if_present(env, &DataKey::Amendment(payment.id), ledgers);
+    extend_if_present(env, &DataKey::CancellationRequest(payment.id), ledgers);
+    if let Some(order_id) = payment.order_id {
+        extend_if_present(env, &DataKey::Order(order_id), ledgers);
+    }
+
+    // The payment's slots in the buyer, seller and status indexes
+    extend_if_present(env, &DataKey::IndexSlots(payment.id), ledgers);
+    if let Some(slots) = env
+        .storage()
+        .persistent()
+        .get::<_, PaymentIndexSlots>(&DataKey::IndexSlots(payment.id))
+    {
+        extend_index_slot_ttl(env, PaymentIndex::Buyer(payment.buyer.clone()), slots.buyer, ledgers);
+        extend_index_slot_ttl(env, PaymentIndex::Seller(payment.seller.clone()), slots.seller, ledgers);
+        extend_index_slot_ttl(env, PaymentIndex::Status(payment.status.clone()), slots.status, ledgers);
+    }
+
+    extend_config_ttl(env, &payment.token, payment.policy_id, ledgers);
+}
+
+/// Number of ledgers a payment and its records must stay alive for
+/// 
+/// Open payments are kept until their latest expiry, dispute or inspection
+/// deadline plus RETENTION_PERIOD; closed payments until RETENTION_PERIOD plus
+/// ARCHIVE_GRACE_PERIOD after they closed. Every extension covers at least
+/// RETENTION_PERIOD, so unclaimed expired payments stay reachable, and is
+/// capped at the network's maximum TTL.
+pub(crate) fn payment_ttl_ledgers(env: &Env, payment: &Payment) -> u32 {
+    let horizon = if is_terminal(&payment.status) {
+        closed_at(env, payment)
+            .saturating_add(RETENTION_PERIOD)
+            .saturating_add(ARCHIVE_GRACE_PERIOD)
+    } else {
+        payment
+            .expiry
+            .max(payment.dispute_deadline)
+            .max(payment.inspection_deadline)
+            .saturating_add(RETENTION_PERIOD)
+    };
+    let seconds = horizon
+        .saturating_sub(env.ledger().timestamp())
+        .max(RETENTION_PERIOD);
+    (seconds / LEDGER_CLOSE_SECONDS).min(env.storage().max_ttl() as u64) as u32
+}
+
+/// Extends one slot of a payment index and the index's length
+pub(crate) fn extend_index_slot_ttl(env: &Env, index: PaymentIndex, slot: u32, ledgers: u32) {
+    extend_if_present(env, &DataKey::IndexEntry(index.clone(), slot), ledgers);
+    extend_if_present(env, &DataKey::IndexLen(index), ledgers);
+}
+
+/// Extends the configuration payments in `token` under `policy_id` are settled with
+/// 
+/// Also extends the contract instance, which must outlive the records it manages.
+pub(crate) fn extend_config_ttl(env: &Env, token: &Address, policy_id: u32, ledgers: u32) {
+    extend_if_present(env, &DataKey::Arbitrator, ledgers);
+    extend_if_present(env, &DataKey::PaymentCounter, ledgers);
+    extend_if_present(env, &DataKey::OrderCounter, ledgers);
+    extend_if_present(env, &DataKey::SchemaVersion, ledgers);
+    extend_if_present(env, &DataKey::Policy(policy_id), ledgers);
+    extend_if_present(env, &DataKey::InspectionPeriod, ledgers);
+    extend_if_present(env, &DataKey::PanelConfig, ledgers);
+    extend_if_present(env, &DataKey::FeeConfig, ledgers);
+    extend_if_present(env, &DataKey::TokenFeeBps(token.clone()), ledgers);
+    extend_if_present(env, &DataKey::AccruedFees(token.clone()), ledgers);
+    extend_if_present(env, &DataKey::TokenConfig(token.clone()), ledgers);
+    extend_if_present(env, &DataKey::Tokens, ledgers);
+
+    env.storage().instance().extend_ttl(ledgers, ledgers);
+}
+
+/// When a closed payment reached its final status
+/// 
+/// Payments closed before closure times were recorded fall back to their expiry.
+fn closed_at(env: &Env, payment: &Payment) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PaymentClosedAt(payment.id))
+        .unwrap_or(payment.expiry)
+}
+
+pub(crate) fn extend_if_present<K: IntoVal<Env, Val>>(env: &Env, key: &K, ledgers: u32) {
+    if env.storage().persistent().has(key) {
+        env.storage().persistent().extend_ttl(key, ledgers, ledgers);
+    }
+}
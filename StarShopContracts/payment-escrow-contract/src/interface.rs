@@ -1,5 +1,5 @@
-use crate::datatypes::{DisputeDecision, Payment, PaymentEscrowError, PaymentStatus, DeliveryDetails};
-use soroban_sdk::{Address, Env, String, Vec};
+use crate::datatypes::{AmendmentProposal, ArchivedPayment, CancellationRequest, DisputeDecision, DisputePanel, DisputeResolvedEvent, Evidence, FeeConfig, MigrationStatus, Order, OrderItem, PanelConfig, Payment, PaymentIndex, PolicyProfile, PaymentEscrowError, PaymentStatus, DeliveryDetails, TokenConfig};
+use soroban_sdk::{Address, BytesN, Env, String, Vec};
 
 /// PaymentInterface trait defines core payment management functionality
 /// This trait handles payment creation, retrieval, and basic payment operations
@@ -27,7 +27,8 @@ pub trait PaymentInterface {
     /// * `seller` - The address of the seller (recipient)
     /// * `amount` - The payment amount in token units
     /// * `token` - The token contract address for the payment
-    /// * `expiry_days` - Number of days until payment expires (0 = 30 days default)
+    /// * `expiry_days` - Number of days until payment expires (0 = policy default)
+    /// * `policy_id` - Escrow policy profile setting expiry, dispute and inspection terms (0 = default)
     /// * `description` - Human-readable description of the payment
     /// 
     /// # Returns
@@ -36,8 +37,9 @@ pub trait PaymentInterface {
     /// # Business Logic
     /// * Transfers funds from buyer to escrow contract
     /// * Creates unique payment ID and stores payment details
-    /// * Sets up dispute deadline based on expiry period
+    /// * Sets up expiry and dispute deadline from the selected policy
     /// * Validates all inputs and buyer authorization
+    #[allow(clippy::too_many_arguments)]
     fn create_payment(
         env: Env,
         buyer: Address,
@@ -45,6 +47,38 @@ pub trait PaymentInterface {
         amount: i128,
         token: Address,
         expiry_days: u32,
+        policy_id: u32,
+        description: String,
+    ) -> Result<u128, PaymentEscrowError>;
+
+    /// Creates a new escrow payment released to the seller in tranches
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - The address of the buyer (payer)
+    /// * `seller` - The address of the seller (recipient)
+    /// * `token` - The token contract address for the payment
+    /// * `tranches` - Ordered list of (amount, description) release tranches
+    /// * `expiry_days` - Number of days until payment expires (0 = policy default)
+    /// * `policy_id` - Escrow policy profile setting expiry, dispute and inspection terms (0 = default)
+    /// * `description` - Human-readable description of the payment
+    /// 
+    /// # Returns
+    /// * `Result<u128, PaymentEscrowError>` - Payment ID on success, error on failure
+    /// 
+    /// # Business Logic
+    /// * Escrows the sum of all tranche amounts in a single transfer
+    /// * Each tranche can later be released individually by the buyer
+    /// * Disputes and expiry claims only act on the unreleased remainder
+    #[allow(clippy::too_many_arguments)]
+    fn create_milestone_payment(
+        env: Env,
+        buyer: Address,
+        seller: Address,
+        token: Address,
+        tranches: Vec<(i128, String)>,
+        expiry_days: u32,
+        policy_id: u32,
         description: String,
     ) -> Result<u128, PaymentEscrowError>;
 
@@ -83,11 +117,28 @@ pub trait DeliveryInterface {
     /// 
     /// # Business Logic
     /// * Only works after seller has confirmed delivery (status = Delivered)
-    /// * Transfers funds from escrow to seller
+    /// * Transfers all unreleased tranches from escrow to seller, less the platform fee
     /// * Marks payment as Completed
     /// * Final step in successful escrow completion
     fn buyer_confirm_delivery(env: Env, payment_id: u128, buyer: Address) -> Result<(), PaymentEscrowError>;
 
+    /// Allows the buyer to release a single tranche of the payment to the seller
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `buyer` - The address of the buyer releasing the tranche
+    /// * `tranche_index` - Index of the tranche to release
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Transfers the tranche amount from escrow to the seller, less the platform fee
+    /// * Marks payment as PartiallyReleased while funds remain in escrow
+    /// * Marks payment as Completed once every tranche has been released
+    fn release_tranche(env: Env, payment_id: u128, buyer: Address, tranche_index: u32) -> Result<(), PaymentEscrowError>;
+
     /// Allows the seller to confirm that delivery has been made
     /// 
     /// # Arguments
@@ -133,6 +184,41 @@ pub trait DeliveryInterface {
     /// * Enables detailed payment tracking and reporting
     /// * Supports dispute resolution and claim verification
     fn get_delivery_details(env: Env, payment_id: u128) -> Result<DeliveryDetails, PaymentEscrowError>;
+
+    /// Releases a delivered payment to the seller once the inspection window has passed
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the delivered payment
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Callable by the seller or any keeper, no authorization required
+    /// * Only applies to Delivered payments the buyer neither confirmed nor disputed
+    /// * Transfers the unreleased remainder to the seller and marks payment as Completed
+    fn release_after_inspection(env: Env, payment_id: u128) -> Result<(), PaymentEscrowError>;
+
+    /// Sets the inspection window used by the default escrow policy
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `period` - Inspection window length in seconds
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    fn set_inspection_period(env: Env, arbitrator: Address, period: u64) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves the inspection window used by the default escrow policy
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `u64` - Inspection window length in seconds
+    fn get_inspection_period(env: Env) -> u64;
 }
 
 
@@ -165,18 +251,66 @@ pub trait DisputeInterface {
     /// * `env` - The Soroban environment
     /// * `payment_id` - The unique identifier of the disputed payment
     /// * `arbitrator` - The address of the arbitrator resolving the dispute
-    /// * `decision` - The arbitrator's decision (PaySeller or RefundBuyer)
+    /// * `decision` - The arbitrator's decision (PaySeller, RefundBuyer or Split)
     /// * `reason` - Human-readable reason for the decision
     /// 
     /// # Returns
     /// * `Result<(), PaymentEscrowError>` - Success or error
     /// 
     /// # Business Logic
-    /// * Transfers funds based on arbitrator decision
-    /// * Marks payment as Completed (seller wins) or Refunded (buyer wins)
-    /// * Records resolution details for transparency
+    /// * For panel disputes, records the arbitrator's vote and only executes once
+    ///   a majority of the panel agrees on the same decision
+    /// * A panel without quorum by its voting deadline falls back to a ruling by
+    ///   any registered arbitrator, with no panel fee
+    /// * Transfers the unreleased remainder based on arbitrator decision
+    /// * Split decisions refund the buyer's basis points and pay the seller the rest
+    /// * Seller payouts are subject to the platform fee
+    /// * Marks payment as Completed (seller wins), Refunded (buyer wins) or SplitResolved
+    /// * Records resolution details, including the evidence considered, for transparency
     /// * Finalizes the dispute resolution process
     fn resolve_dispute(env: Env, payment_id: u128, arbitrator: Address, decision: DisputeDecision, reason: String) -> Result<(), PaymentEscrowError>;
+
+    /// Allows the buyer or seller to append an evidence entry to a disputed payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the disputed payment
+    /// * `submitter` - The address submitting the evidence (must be buyer or seller)
+    /// * `content_hash` - Hash of the evidence content stored off-chain
+    /// * `uri` - Location of the evidence content
+    /// 
+    /// # Returns
+    /// * `Result<u32, PaymentEscrowError>` - Index of the new entry or error
+    /// 
+    /// # Business Logic
+    /// * Builds an append-only evidence log per payment
+    /// * Lets the counterparty respond to the initial dispute reason
+    /// * Only accepted while the payment is Disputed, within count and size bounds
+    fn submit_evidence(env: Env, payment_id: u128, submitter: Address, content_hash: BytesN<32>, uri: String) -> Result<u32, PaymentEscrowError>;
+
+    /// Retrieves the evidence log attached to a payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Vec<Evidence>` - Evidence entries in submission order (empty if none)
+    fn get_evidence(env: Env, payment_id: u128) -> Vec<Evidence>;
+
+    /// Retrieves the arbitrator ruling recorded for a resolved dispute
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the resolved payment
+    /// 
+    /// # Returns
+    /// * `Result<DisputeResolvedEvent, PaymentEscrowError>` - Ruling details or error if not found
+    /// 
+    /// # Purpose
+    /// * Exposes how the escrowed remainder was divided between buyer and seller
+    /// * Provides an audit trail for resolved disputes
+    fn get_dispute_resolution(env: Env, payment_id: u128) -> Result<DisputeResolvedEvent, PaymentEscrowError>;
 }
 
 
@@ -200,7 +334,8 @@ pub trait ClaimInterface {
     /// * Only expired payments can be claimed
     /// * Only the buyer can claim expired payments
     /// * Disputed payments cannot be claimed (must be resolved first)
-    /// * Claims transfer funds back to the buyer and mark payment as Refunded
+    /// * Delivered payments cannot be claimed (seller is paid after inspection)
+    /// * Claims transfer the unreleased remainder back to the buyer and mark payment as Refunded
     fn claim_payment(env: Env, payment_id: u128, claimer: Address) -> Result<(), PaymentEscrowError>;
 }
 
@@ -216,17 +351,40 @@ pub trait ArbitratorInterface {
     /// 
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `arbitrator` - The existing arbitrator who is authorizing this transaction
+    /// * `admin` - The contract admin who is authorizing this transaction
     /// * `new_arbitrator` - The new arbitrator address to be added
     /// 
     /// # Returns
     /// * `Result<(), PaymentEscrowError>` - Success or error
     /// 
     /// # Security
-    /// * Only existing arbitrators can add new arbitrators
+    /// * Only the admin can add new arbitrators
     /// * Prevents duplicate arbitrators
-    /// * Requires authentication from the existing arbitrator
-    fn add_arbitrator(env: Env, arbitrator: Address, new_arbitrator: Address) -> Result<(), PaymentEscrowError>;
+    /// * Requires authentication from the admin
+    fn add_arbitrator(env: Env, admin: Address, new_arbitrator: Address) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves the contract admin
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Result<Address, PaymentEscrowError>` - Admin address, or error if not initialized
+    fn get_admin(env: Env) -> Result<Address, PaymentEscrowError>;
+
+    /// Hands the admin role to another address
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - The current admin
+    /// * `new_admin` - The address that becomes admin
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Security
+    /// * Requires authentication from both the current and the new admin
+    fn transfer_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), PaymentEscrowError>;
     
     /// Retrieves the current list of all authorized arbitrators
     /// 
@@ -262,4 +420,511 @@ pub trait ArbitratorInterface {
     /// * Changing arbitrator addresses
     /// * Replacing compromised arbitrator keys
     fn transfer_arbitrator_rights(env: Env, old_arbitrator: Address, new_arbitrator: Address) -> Result<(), PaymentEscrowError>;
-}
\ No newline at end of file
+
+    /// Configures panel mode for high-value disputes
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `min_amount` - Disputed remainder at or above which a panel is assigned
+    /// * `panel_size` - Number of arbitrators assigned to each panel (at least 2)
+    /// * `arbitrator_fee_bps` - Share of the remainder paid to voting panel arbitrators
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Disputes below `min_amount` keep the single-arbitrator flow
+    /// * Larger disputes are assigned a panel when the dispute is raised
+    /// * The majority ruling executes once quorum is reached
+    fn set_panel_config(env: Env, arbitrator: Address, min_amount: i128, panel_size: u32, arbitrator_fee_bps: u32) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves the panel mode configuration
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Option<PanelConfig>` - Panel configuration, or None if panel mode is disabled
+    fn get_panel_config(env: Env) -> Option<PanelConfig>;
+
+    /// Retrieves the arbitrator panel assigned to a disputed payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<DisputePanel, PaymentEscrowError>` - Panel and votes cast, or error if none assigned
+    fn get_dispute_panel(env: Env, payment_id: u128) -> Result<DisputePanel, PaymentEscrowError>;
+}
+
+
+
+
+/// FeeInterface trait defines the marketplace fee schedule
+/// This trait handles fee configuration, per-token overrides, and the
+/// reporting and withdrawal of fees withheld from seller payouts.
+pub trait FeeInterface {
+    /// Sets the default platform fee and the address that receives accrued fees
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - The contract admin authorizing the change
+    /// * `fee_bps` - Default fee in basis points deducted from seller payouts
+    /// * `recipient` - The address allowed to withdraw accrued fees
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Only the admin can change the fee or the recipient
+    /// * Applies to payments created after the change
+    /// * Existing payments keep the fee rate they were created with
+    fn set_fee_config(env: Env, admin: Address, fee_bps: u32, recipient: Address) -> Result<(), PaymentEscrowError>;
+
+    /// Sets or clears the fee override for a specific token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `token` - The token contract address the override applies to
+    /// * `fee_bps` - Fee in basis points for this token, or None to use the default
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    fn set_token_fee(env: Env, arbitrator: Address, token: Address, fee_bps: Option<u32>) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves the default fee schedule
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Result<FeeConfig, PaymentEscrowError>` - Fee configuration or error if not configured
+    fn get_fee_config(env: Env) -> Result<FeeConfig, PaymentEscrowError>;
+
+    /// Retrieves the fee in basis points applied to new payments in a token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `u32` - The token override if set, otherwise the default fee (0 if unconfigured)
+    fn get_token_fee_bps(env: Env, token: Address) -> u32;
+
+    /// Retrieves the fees accrued in a token and not yet withdrawn
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `i128` - The accrued fee balance held by the contract
+    fn get_accrued_fees(env: Env, token: Address) -> i128;
+
+    /// Withdraws all accrued fees in a token to the fee recipient
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `Result<i128, PaymentEscrowError>` - The withdrawn amount or error
+    /// 
+    /// # Security
+    /// * Requires authentication from the configured fee recipient
+    fn withdraw_fees(env: Env, token: Address) -> Result<i128, PaymentEscrowError>;
+}
+
+
+
+
+/// QueryInterface trait defines indexed payment listing functionality
+/// This trait exposes paginated views of payments by buyer, seller and status
+/// so clients do not have to scan every payment id.
+pub trait QueryInterface {
+    /// Lists the payments created by a buyer
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - The buyer address
+    /// * `cursor` - Slot in the buyer's index to start from, in creation order
+    /// * `limit` - Number of slots to read (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * `Vec<Payment>` - The requested page of payments
+    fn get_payments_by_buyer(env: Env, buyer: Address, cursor: u32, limit: u32) -> Vec<Payment>;
+
+    /// Lists the payments addressed to a seller
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `seller` - The seller address
+    /// * `cursor` - Slot in the seller's index to start from, in creation order
+    /// * `limit` - Number of slots to read (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * `Vec<Payment>` - The requested page of payments
+    fn get_payments_by_seller(env: Env, seller: Address, cursor: u32, limit: u32) -> Vec<Payment>;
+
+    /// Lists the payments currently in a given status
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `status` - The payment status to list
+    /// * `cursor` - Slot in the status index to start from, in order of entering the status
+    /// * `limit` - Number of slots to read (capped at MAX_PAGE_SIZE)
+    /// 
+    /// # Returns
+    /// * `Vec<Payment>` - The requested page of payments
+    /// 
+    /// # Notes
+    /// * Payments vacate their slot when their status changes; slots never shift,
+    ///   so pages can hold fewer than `limit` payments and the next page always
+    ///   starts at `cursor + limit`
+    fn get_payments_by_status(env: Env, status: PaymentStatus, cursor: u32, limit: u32) -> Vec<Payment>;
+
+    /// Number of slots allocated in a payment index
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `index` - The buyer, seller or status index
+    /// 
+    /// # Returns
+    /// * `u32` - Cursor value at which the index ends
+    fn get_index_len(env: Env, index: PaymentIndex) -> u32;
+}
+
+
+
+
+/// OrderInterface trait defines multi-seller order functionality
+/// This trait handles orders that bundle several sellers' line items into one
+/// buyer-funded escrow, with each line item settled as its own payment.
+pub trait OrderInterface {
+    /// Creates an order escrowing several sellers' line items in one transfer
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `buyer` - The address of the buyer (payer)
+    /// * `token` - The token contract address for the order
+    /// * `items` - Line items, each with its seller, amount and description
+    /// * `expiry_days` - Number of days until the order expires (0 = policy default)
+    /// * `policy_id` - Escrow policy profile shared by every line item (0 = default)
+    /// * `description` - Human-readable description of the order
+    /// 
+    /// # Returns
+    /// * `Result<u128, PaymentEscrowError>` - Order ID on success, error on failure
+    /// 
+    /// # Business Logic
+    /// * Transfers the order total from buyer to escrow in a single transfer
+    /// * Creates one payment per line item sharing the order's expiry and dispute deadline
+    /// * Each seller confirms, and each line is disputed or refunded, through its own payment
+    fn create_order(env: Env, buyer: Address, token: Address, items: Vec<OrderItem>, expiry_days: u32, policy_id: u32, description: String) -> Result<u128, PaymentEscrowError>;
+
+    /// Retrieves an order by its unique identifier
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `order_id` - The unique identifier of the order
+    /// 
+    /// # Returns
+    /// * `Result<Order, PaymentEscrowError>` - Order details or error if not found
+    fn get_order(env: Env, order_id: u128) -> Result<Order, PaymentEscrowError>;
+
+    /// Retrieves the line item payments of an order
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `order_id` - The unique identifier of the order
+    /// 
+    /// # Returns
+    /// * `Result<Vec<Payment>, PaymentEscrowError>` - Line item payments in order, or error if not found
+    fn get_order_payments(env: Env, order_id: u128) -> Result<Vec<Payment>, PaymentEscrowError>;
+}
+
+
+
+
+/// PolicyInterface trait defines escrow policy profile management
+/// This trait handles the admin-managed profiles that set default and maximum
+/// expiry, dispute window and inspection window for new payments.
+pub trait PolicyInterface {
+    /// Creates or replaces an escrow policy profile
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `policy_id` - Identifier payments use to select the profile (0 is reserved)
+    /// * `profile` - Expiry, dispute window and inspection terms
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Payments select a profile by id at creation and keep its terms
+    /// * Changing a profile does not affect existing payments
+    fn set_policy(env: Env, arbitrator: Address, policy_id: u32, profile: PolicyProfile) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves an escrow policy profile
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `policy_id` - The policy identifier (0 for the built-in default)
+    /// 
+    /// # Returns
+    /// * `Result<PolicyProfile, PaymentEscrowError>` - The profile or error if not found
+    fn get_policy(env: Env, policy_id: u32) -> Result<PolicyProfile, PaymentEscrowError>;
+}
+
+
+
+
+/// AmendmentInterface trait defines mutual-consent changes to pending payments
+/// This trait handles cancelling a payment before the seller ships and
+/// amending its amount or expiry with both parties' sign-off.
+pub trait AmendmentInterface {
+    /// Buyer asks the seller to cancel a pending payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `buyer` - The buyer requesting the cancellation
+    /// * `reason` - Human-readable reason for the cancellation
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Only pending payments can be cancelled
+    /// * The request stays open until the seller accepts or either party rejects it
+    fn request_cancellation(env: Env, payment_id: u128, buyer: Address, reason: String) -> Result<(), PaymentEscrowError>;
+
+    /// Seller accepts the buyer's cancellation request and the buyer is refunded
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `seller` - The seller accepting the cancellation
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Refunds the escrowed amount, and any escrowed amendment top-up, to the buyer
+    /// * Marks the payment as Cancelled
+    fn accept_cancellation(env: Env, payment_id: u128, seller: Address) -> Result<(), PaymentEscrowError>;
+
+    /// Closes an open cancellation request without cancelling
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `caller` - The seller declining, or the buyer withdrawing, the request
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    fn reject_cancellation(env: Env, payment_id: u128, caller: Address) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves the open cancellation request for a payment, if any
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Option<CancellationRequest>` - The open request, if any
+    fn get_cancellation_request(env: Env, payment_id: u128) -> Option<CancellationRequest>;
+
+    /// Proposes a new amount and expiry for a pending payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `proposer` - The buyer or seller proposing the change
+    /// * `new_amount` - The proposed escrow amount
+    /// * `new_expiry` - The proposed expiry timestamp
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * A buyer proposing a higher amount escrows the increase immediately
+    /// * The new expiry must be in the future and within the payment's policy maximum
+    fn propose_amendment(env: Env, payment_id: u128, proposer: Address, new_amount: i128, new_expiry: u64) -> Result<(), PaymentEscrowError>;
+
+    /// Counterparty approves an open amendment and the payment terms are updated
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `approver` - The party that did not propose the amendment
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Increases are pulled from the buyer and decreases are refunded to the buyer
+    /// * The amount difference is applied to the final release tranche
+    fn accept_amendment(env: Env, payment_id: u128, approver: Address) -> Result<(), PaymentEscrowError>;
+
+    /// Closes an open amendment without applying it
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// * `caller` - The buyer or seller closing the proposal
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Any top-up escrowed by the buyer is returned
+    fn reject_amendment(env: Env, payment_id: u128, caller: Address) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves the open amendment for a payment, if any
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Option<AmendmentProposal>` - The open amendment, if any
+    fn get_amendment(env: Env, payment_id: u128) -> Option<AmendmentProposal>;
+}
+
+
+
+
+/// TokenRegistryInterface trait defines the allow-list of escrow tokens
+/// This trait handles which Stellar asset contracts can be escrowed, the
+/// amount limits for each, and whether they are currently enabled.
+pub trait TokenRegistryInterface {
+    /// Accepts a token for escrow with per-payment limits, or updates its limits
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `token` - The token contract address to accept
+    /// * `min_amount` - Smallest payment amount accepted in this token
+    /// * `max_amount` - Largest payment amount accepted in this token
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    fn add_token(env: Env, arbitrator: Address, token: Address, min_amount: i128, max_amount: i128) -> Result<(), PaymentEscrowError>;
+
+    /// Enables or disables a registered token without losing its limits
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the change
+    /// * `token` - The registered token contract address
+    /// * `enabled` - Whether new payments may use the token
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error
+    /// 
+    /// # Business Logic
+    /// * Disabling a token only blocks new payments and amendments
+    fn set_token_enabled(env: Env, arbitrator: Address, token: Address, enabled: bool) -> Result<(), PaymentEscrowError>;
+
+    /// Retrieves the acceptance rules of a token
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `token` - The token contract address
+    /// 
+    /// # Returns
+    /// * `Option<TokenConfig>` - The token's limits and status, if registered
+    fn get_token_config(env: Env, token: Address) -> Option<TokenConfig>;
+
+    /// Lists every registered token, enabled or not
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `Vec<Address>` - Registered token contract addresses in registration order
+    fn get_tokens(env: Env) -> Vec<Address>;
+}
+
+
+
+
+/// MigrationInterface trait defines versioned payment storage upgrades
+/// This trait handles rewriting payments stored in an older layout after a
+/// contract upgrade, in bounded batches that fit within a transaction.
+pub trait MigrationInterface {
+    /// Moves the next batch of payments to the current storage layout
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `arbitrator` - The arbitrator authorizing the migration
+    /// * `batch_size` - Number of payment ids to process (at most MAX_MIGRATION_BATCH)
+    /// 
+    /// # Returns
+    /// * `Result<MigrationStatus, PaymentEscrowError>` - Progress after this batch
+    /// 
+    /// # Business Logic
+    /// * Resumes from the last processed payment id
+    /// * Records the new schema version once every payment has been processed
+    /// * Payments remain readable in either layout while the migration runs
+    fn migrate_payments(env: Env, arbitrator: Address, batch_size: u32) -> Result<MigrationStatus, PaymentEscrowError>;
+
+    /// Retrieves the progress of the payment storage migration
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// 
+    /// # Returns
+    /// * `MigrationStatus` - Stored schema version and migration progress
+    fn get_migration_status(env: Env) -> MigrationStatus;
+}
+
+
+
+
+/// RetentionInterface trait defines storage lifetime management for escrow records
+/// This trait handles keeping payments and their dispute records alive while
+/// they can be acted on, and archiving closed payments after retention.
+pub trait RetentionInterface {
+    /// Extends the storage lifetime of a payment and the records it depends on
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<(), PaymentEscrowError>` - Success or error if not found
+    /// 
+    /// # Business Logic
+    /// * Callable by anyone, typically keepers watching long-lived escrows
+    /// * Also extends archived summaries
+    fn bump_payment(env: Env, payment_id: u128) -> Result<(), PaymentEscrowError>;
+
+    /// Replaces a closed payment with its archived summary
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<ArchivedPayment, PaymentEscrowError>` - The archived summary or error
+    /// 
+    /// # Business Logic
+    /// * Requires a final status and RETENTION_PERIOD elapsed since closure
+    /// * Archived payments no longer appear in payment queries
+    fn archive_payment(env: Env, payment_id: u128) -> Result<ArchivedPayment, PaymentEscrowError>;
+
+    /// Retrieves the archived summary of a payment
+    /// 
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `payment_id` - The unique identifier of the payment
+    /// 
+    /// # Returns
+    /// * `Result<ArchivedPayment, PaymentEscrowError>` - The summary or error if not archived
+    fn get_archived_payment(env: Env, payment_id: u128) -> Result<ArchivedPayment, PaymentEscrowError>;
+}
@@ -1,6 +1,6 @@
 #![no_std]
 use soroban_sdk::{contract, contractimpl, Env, Address, Vec, BytesN};
-use crate::datatypes::{DataKey, PaymentEscrowError};
+use crate::datatypes::{DataKey, PaymentEscrowError, PAYMENT_SCHEMA_VERSION};
 
 /// Payment Escrow Contract
 /// 
@@ -15,6 +15,7 @@ use crate::datatypes::{DataKey, PaymentEscrowError};
 /// - Expired payment claim functionality
 /// - Contract upgrade capability with state preservation
 /// - Multiple arbitrator support with dynamic management
+/// - Platform fee deducted from seller payouts
 #[contract]
 pub struct PaymentEscrowContract;
 
@@ -46,11 +47,19 @@ impl PaymentEscrowContract {
 
         // Create a vector to store arbitrators and add the initial arbitrator
         let mut arbitrators = Vec::new(&env);
-        arbitrators.push_back(arbitrator);
+        arbitrators.push_back(arbitrator.clone());
         
         // Store the arbitrators vector in persistent storage
         env.storage().persistent().set(&DataKey::Arbitrator, &arbitrators);
 
+        // The initial arbitrator is the admin who controls the arbitrator set
+        env.storage().persistent().set(&DataKey::Admin, &arbitrator);
+
+        // New deployments start on the current payment storage layout
+        env.storage()
+            .persistent()
+            .set(&DataKey::SchemaVersion, &PAYMENT_SCHEMA_VERSION);
+
         Ok(())
     }
 
@@ -86,6 +95,8 @@ impl PaymentEscrowContract {
     /// 2. New WASM code is deployed to the contract
     /// 3. All existing state (payments, arbitrators, etc.) is preserved
     /// 4. Contract continues to function with new code
+    /// 5. If the new code changes the payment layout, an arbitrator calls
+    ///    `migrate_payments` until `get_migration_status` reports completion
     /// 
     /// # Important Notes
     /// - The new WASM must be compatible with existing state structure
@@ -122,6 +133,14 @@ impl PaymentEscrowContract {
 /// - create.rs: Payment creation logic
 /// - delivery.rs: Delivery confirmation
 /// - dispute.rs: Dispute resolution
+/// - fee.rs: Platform fee schedule and fee withdrawal
+/// - query.rs: Payment indexes and paginated queries
+/// - order.rs: Multi-seller orders funded by a single transfer
+/// - policy.rs: Escrow policy profiles for expiry, dispute and inspection terms
+/// - amendment.rs: Mutual-consent cancellation and amendment of pending payments
+/// - registry.rs: Accepted tokens and their per-payment limits
+/// - migration.rs: Versioned payment storage and post-upgrade migration
+/// - retention.rs: Storage TTL extension and archiving of closed payments
 pub use implementations::*;
 
 // Declare modules
@@ -10,14 +10,14 @@ extern crate std;
 // For now, we'll test the upgrade function without WASM files
 
 use crate::{
-    datatypes::{DisputeDecision, PaymentStatus},
+    datatypes::{DataKey, DisputeDecision, DisputeWindow, LegacyPayment, OrderItem, PaymentEscrowError, PaymentIndex, PaymentStatus, PolicyProfile, TokenConfig, LEDGER_CLOSE_SECONDS, MAX_ORDER_ITEMS, PAYMENT_SCHEMA_VERSION, RETENTION_PERIOD},
     PaymentEscrowContract, PaymentEscrowContractClient,
 };
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    testutils::{storage::Persistent as _, Address as _, AuthorizedFunction, AuthorizedInvocation},
     Address, Env, IntoVal, String, Symbol,
-    BytesN
+    BytesN, vec
 };
 use soroban_sdk::{
     testutils::Ledger,
@@ -26,6 +26,7 @@ use soroban_sdk::{
 
 
 // FOR CONTRACT UPGRADE TESTING ONLY
+#[allow(clippy::too_many_arguments)]
 mod new_contract {
     soroban_sdk::contractimport!(
         file = "/Users/mac/StarShop-Contracts/target/wasm32-unknown-unknown/release/payment_escrow_contract.wasm"
@@ -54,6 +55,10 @@ fn test_process_deposit_with_auth() {
 
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -73,6 +78,7 @@ fn test_process_deposit_with_auth() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -91,6 +97,7 @@ fn test_process_deposit_with_auth() {
                         100_i128,
                         token_contract_id.clone(),
                         30_u32,
+                        0_u32,
                         description.clone()
                     )
                         .into_val(&env),
@@ -128,6 +135,10 @@ fn test_get_payment() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -147,6 +158,7 @@ fn test_get_payment() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -178,6 +190,10 @@ fn test_confirm_deliveries() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -197,6 +213,7 @@ fn test_confirm_deliveries() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -231,6 +248,10 @@ fn test_get_delivery_status() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -250,6 +271,7 @@ fn test_get_delivery_status() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -287,6 +309,10 @@ fn test_get_delivery_details() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -306,6 +332,7 @@ fn test_get_delivery_details() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -335,6 +362,10 @@ fn test_dispute_payment() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -354,6 +385,7 @@ fn test_dispute_payment() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -387,6 +419,10 @@ fn test_resolve_dispute() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -406,6 +442,7 @@ fn test_resolve_dispute() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -447,6 +484,10 @@ fn test_claim_expired_payment() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -465,6 +506,7 @@ fn test_claim_expired_payment() {
         &amount,
         &token_contract_id,
         &1,
+        &0,
         &description,
     );
 
@@ -512,6 +554,10 @@ fn test_claim_before_expiry_should_fail() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -530,6 +576,7 @@ fn test_claim_before_expiry_should_fail() {
         &amount,
         &token_contract_id,
         &30,
+        &0,
         &description,
     );
 
@@ -665,6 +712,44 @@ fn test_transfer_to_existing_arbitrator() {
     client.transfer_arbitrator_rights(&arbitrator, &arbitrator);
 }
 
+#[test]
+fn test_only_admin_adds_arbitrators() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // The initial arbitrator becomes the admin
+    let admin = Address::generate(&env);
+    client.init(&admin);
+    assert_eq!(client.get_admin(), admin);
+
+    // Other arbitrators cannot grow the set on their own
+    let arbitrator = Address::generate(&env);
+    client.add_arbitrator(&admin, &arbitrator);
+    assert_eq!(
+        client.try_add_arbitrator(&arbitrator, &Address::generate(&env)),
+        Err(Ok(PaymentEscrowError::NotAdmin))
+    );
+    assert_eq!(client.get_arbitrators().len(), 2);
+
+    // The admin role can be handed over, after which only the new admin adds arbitrators
+    let new_admin = Address::generate(&env);
+    assert_eq!(
+        client.try_transfer_admin(&arbitrator, &new_admin),
+        Err(Ok(PaymentEscrowError::NotAdmin))
+    );
+    client.transfer_admin(&admin, &new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(
+        client.try_add_arbitrator(&admin, &Address::generate(&env)),
+        Err(Ok(PaymentEscrowError::NotAdmin))
+    );
+    client.add_arbitrator(&new_admin, &Address::generate(&env));
+    assert_eq!(client.get_arbitrators().len(), 3);
+}
+
 #[test]
 fn test_multiple_arbitrators() {
     let env = Env::default();
@@ -708,6 +793,10 @@ fn test_dispute_after_one_day_for_short_payment() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -727,6 +816,7 @@ fn test_dispute_after_one_day_for_short_payment() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -770,6 +860,10 @@ fn test_dispute_after_expiry_for_short_payment() {
     let token_admin = Address::generate(&env);
     let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     let token = TokenAdmin::new(&env, &token_contract_id);
 
     // Setup test accounts
@@ -789,6 +883,7 @@ fn test_dispute_after_expiry_for_short_payment() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -832,6 +927,11 @@ fn test_contract_upgrade() {
 
     client.init(&arbitrator);
 
+
+    // Accept the token for escrow
+
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
     // Mint tokens to buyer
     token.mint(&buyer, &1000);
 
@@ -842,6 +942,7 @@ fn test_contract_upgrade() {
         &amount,
         &token_contract_id,
         &expiry_days,
+        &0,
         &description,
     );
 
@@ -879,4 +980,2420 @@ fn test_contract_upgrade() {
     assert_eq!(token_client.balance(&buyer), 900);
     assert_eq!(token_client.balance(&seller), 0); 
 
-}
\ No newline at end of file
+}
+
+
+#[test]
+fn test_milestone_payment_partial_release() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Multi-shipment order");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    // Create a payment with two shipments
+    let tranches = vec![
+        &env,
+        (60_i128, String::from_str(&env, "First shipment")),
+        (40_i128, String::from_str(&env, "Second shipment")),
+    ];
+    let payment_id = client.create_milestone_payment(
+        &buyer,
+        &seller,
+        &token_contract_id,
+        &tranches,
+        &30,
+        &0,
+        &description,
+    );
+
+    // The full order amount is escrowed up front
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.amount, 100);
+    assert_eq!(payment.tranches.len(), 2);
+    assert_eq!(payment.released_amount, 0);
+    assert_eq!(payment.remaining_amount, 100);
+
+    // Buyer releases the first shipment
+    client.release_tranche(&payment_id, &buyer, &0);
+
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::PartiallyReleased);
+    assert_eq!(payment.released_amount, 60);
+    assert_eq!(payment.remaining_amount, 40);
+    assert!(payment.tranches.get(0).unwrap().released);
+    assert!(!payment.tranches.get(1).unwrap().released);
+
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&seller), 60);
+    assert_eq!(token_client.balance(&contract_id), 40);
+
+    // Buyer releases the second shipment, completing the payment
+    client.release_tranche(&payment_id, &buyer, &1);
+
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.released_amount, 100);
+    assert_eq!(payment.remaining_amount, 0);
+    assert_eq!(token_client.balance(&seller), 100);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_buyer_confirm_releases_remaining_tranches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Three shipment order");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let tranches = vec![
+        &env,
+        (30_i128, String::from_str(&env, "Shipment 1")),
+        (30_i128, String::from_str(&env, "Shipment 2")),
+        (40_i128, String::from_str(&env, "Shipment 3")),
+    ];
+    let payment_id = client.create_milestone_payment(
+        &buyer,
+        &seller,
+        &token_contract_id,
+        &tranches,
+        &30,
+        &0,
+        &description,
+    );
+
+    // Release the first tranche, then the seller confirms full delivery
+    client.release_tranche(&payment_id, &buyer, &0);
+    client.seller_confirm_delivery(&payment_id, &seller);
+    assert_eq!(client.get_delivery_status(&payment_id), PaymentStatus::Delivered);
+
+    // Buyer confirmation only pays out the unreleased remainder
+    client.buyer_confirm_delivery(&payment_id, &buyer);
+
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.remaining_amount, 0);
+    assert!(payment.tranches.iter().all(|t| t.released));
+
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&seller), 100);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(token_client.balance(&buyer), 900);
+}
+
+#[test]
+fn test_dispute_refunds_only_unreleased_remainder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Partially delivered order");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let tranches = vec![
+        &env,
+        (70_i128, String::from_str(&env, "Shipment 1")),
+        (30_i128, String::from_str(&env, "Shipment 2")),
+    ];
+    let payment_id = client.create_milestone_payment(
+        &buyer,
+        &seller,
+        &token_contract_id,
+        &tranches,
+        &30,
+        &0,
+        &description,
+    );
+
+    // First shipment arrives and is released, the second never does
+    client.release_tranche(&payment_id, &buyer, &0);
+    let dispute_reason = String::from_str(&env, "Second shipment never arrived");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    let resolution_reason = String::from_str(&env, "Seller failed to ship");
+    client.resolve_dispute(
+        &payment_id,
+        &arbitrator,
+        &DisputeDecision::RefundBuyer,
+        &resolution_reason,
+    );
+
+    // Only the unreleased 30 goes back to the buyer
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Refunded);
+    assert_eq!(payment.released_amount, 70);
+    assert_eq!(payment.remaining_amount, 0);
+
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&seller), 70);
+    assert_eq!(token_client.balance(&buyer), 930);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_release_same_tranche_twice_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Double release attempt");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let tranches = vec![
+        &env,
+        (50_i128, String::from_str(&env, "Shipment 1")),
+        (50_i128, String::from_str(&env, "Shipment 2")),
+    ];
+    let payment_id = client.create_milestone_payment(
+        &buyer,
+        &seller,
+        &token_contract_id,
+        &tranches,
+        &30,
+        &0,
+        &description,
+    );
+
+    // Releasing the same tranche twice - this should panic
+    client.release_tranche(&payment_id, &buyer, &0);
+    client.release_tranche(&payment_id, &buyer, &0);
+}
+
+#[test]
+fn test_resolve_dispute_with_split_ruling() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let amount = 100;
+    let description = String::from_str(&env, "Test payment for split ruling");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &amount,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    // Buyer disputes a partially damaged shipment
+    let dispute_reason = String::from_str(&env, "Part of the shipment arrived damaged");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    // Arbitrator refunds 40% to the buyer and pays 60% to the seller
+    let resolution_reason = String::from_str(&env, "Damage confirmed on 40% of the items");
+    client.resolve_dispute(
+        &payment_id,
+        &arbitrator,
+        &DisputeDecision::Split(4_000),
+        &resolution_reason,
+    );
+
+    // Verify the payment reached the split terminal state
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::SplitResolved);
+    assert_eq!(payment.released_amount, 60);
+    assert_eq!(payment.remaining_amount, 0);
+
+    // Verify the split is recorded with the resolution
+    let resolution = client.get_dispute_resolution(&payment_id);
+    assert_eq!(resolution.resolution, DisputeDecision::Split(4_000));
+    assert_eq!(resolution.buyer_amount, 40);
+    assert_eq!(resolution.seller_amount, 60);
+    assert_eq!(resolution.admin, arbitrator);
+
+    // Verify both transfers happened
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&buyer), 940);
+    assert_eq!(token_client.balance(&seller), 60);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_split_resolved_payment_cannot_be_disputed_or_claimed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Split payment stays closed");
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &100,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    // Dispute and split the payment
+    let reason = String::from_str(&env, "Half of the order is missing");
+    client.dispute_payment(&payment_id, &buyer, &reason);
+    client.resolve_dispute(&payment_id, &arbitrator, &DisputeDecision::Split(5_000), &reason);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::SplitResolved);
+
+    // A split ruling is final: it cannot be disputed again
+    assert_eq!(
+        client.try_dispute_payment(&payment_id, &seller, &reason),
+        Err(Ok(PaymentEscrowError::NotValid))
+    );
+
+    // Nor claimed by the buyer once the payment has expired
+    env.ledger().set_timestamp(env.ledger().timestamp() + 31 * 24 * 60 * 60);
+    assert_eq!(
+        client.try_claim_payment(&payment_id, &buyer),
+        Err(Ok(PaymentEscrowError::NotValid))
+    );
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::SplitResolved);
+}
+
+#[test]
+#[should_panic]
+fn test_split_ruling_without_seller_share_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for invalid split");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &100,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    let dispute_reason = String::from_str(&env, "Item not received");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    // A 100% split is not a split - this should panic
+    let resolution_reason = String::from_str(&env, "Full refund");
+    client.resolve_dispute(
+        &payment_id,
+        &arbitrator,
+        &DisputeDecision::Split(10_000),
+        &resolution_reason,
+    );
+}
+
+#[test]
+fn test_platform_fee_on_seller_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Configure a 2.5% platform fee
+    let fee_recipient = Address::generate(&env);
+    client.set_fee_config(&arbitrator, &250, &fee_recipient);
+    assert_eq!(client.get_token_fee_bps(&token_contract_id), 250);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment with platform fee");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &10_000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &1_000,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+    assert_eq!(client.get_a_payment(&payment_id).fee_bps, 250);
+
+    // Complete the payment
+    client.seller_confirm_delivery(&payment_id, &seller);
+    client.buyer_confirm_delivery(&payment_id, &buyer);
+
+    // Seller receives the payout less the fee, which stays in the contract
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&seller), 975);
+    assert_eq!(token_client.balance(&contract_id), 25);
+    assert_eq!(client.get_accrued_fees(&token_contract_id), 25);
+
+    // Fee recipient withdraws the accrued fees
+    let withdrawn = client.withdraw_fees(&token_contract_id);
+    assert_eq!(withdrawn, 25);
+    assert_eq!(token_client.balance(&fee_recipient), 25);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(client.get_accrued_fees(&token_contract_id), 0);
+}
+
+#[test]
+fn test_token_fee_override_on_dispute_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Default 2.5% fee with a 1% override for this token
+    let fee_recipient = Address::generate(&env);
+    client.set_fee_config(&arbitrator, &250, &fee_recipient);
+    client.set_token_fee(&arbitrator, &token_contract_id, &Some(100));
+    assert_eq!(client.get_token_fee_bps(&token_contract_id), 100);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test dispute payout with token fee");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &10_000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &1_000,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    // Changing the schedule later does not affect the escrowed payment
+    client.set_token_fee(&arbitrator, &token_contract_id, &None);
+    assert_eq!(client.get_token_fee_bps(&token_contract_id), 250);
+
+    let dispute_reason = String::from_str(&env, "Buyer claims non-delivery");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    let resolution_reason = String::from_str(&env, "Tracking shows delivery");
+    client.resolve_dispute(
+        &payment_id,
+        &arbitrator,
+        &DisputeDecision::PaySeller,
+        &resolution_reason,
+    );
+
+    // Seller payout uses the 1% rate snapshotted at creation
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&seller), 990);
+    assert_eq!(client.get_accrued_fees(&token_contract_id), 10);
+}
+
+#[test]
+#[should_panic]
+fn test_set_fee_config_non_arbitrator_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    // A non-arbitrator tries to configure fees - this should panic
+    let outsider = Address::generate(&env);
+    client.set_fee_config(&outsider, &250, &outsider);
+}
+
+#[test]
+fn test_set_fee_config_requires_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an admin and a second arbitrator
+    let admin = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    client.init(&admin);
+    client.add_arbitrator(&admin, &arbitrator);
+
+    // An arbitrator cannot redirect accrued fees to itself
+    assert_eq!(
+        client.try_set_fee_config(&arbitrator, &250, &arbitrator),
+        Err(Ok(PaymentEscrowError::NotAdmin))
+    );
+    assert_eq!(
+        client.try_get_fee_config(),
+        Err(Ok(PaymentEscrowError::FeeNotConfigured))
+    );
+
+    // The admin sets the schedule and its recipient
+    let fee_recipient = Address::generate(&env);
+    client.set_fee_config(&admin, &250, &fee_recipient);
+    assert_eq!(client.get_fee_config().recipient, fee_recipient);
+}
+
+#[test]
+fn test_submit_evidence_during_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for dispute evidence");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &100,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    let dispute_reason = String::from_str(&env, "Item not received as described");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    // Buyer submits photos, seller responds with tracking information
+    let buyer_hash = BytesN::from_array(&env, &[1; 32]);
+    let buyer_uri = String::from_str(&env, "ipfs://buyer-photos");
+    let seller_hash = BytesN::from_array(&env, &[2; 32]);
+    let seller_uri = String::from_str(&env, "ipfs://seller-tracking");
+    assert_eq!(client.submit_evidence(&payment_id, &buyer, &buyer_hash, &buyer_uri), 0);
+    assert_eq!(client.submit_evidence(&payment_id, &seller, &seller_hash, &seller_uri), 1);
+
+    // Verify the evidence log
+    let evidence = client.get_evidence(&payment_id);
+    assert_eq!(evidence.len(), 2);
+    assert_eq!(evidence.get(0).unwrap().submitter, buyer);
+    assert_eq!(evidence.get(0).unwrap().content_hash, buyer_hash);
+    assert_eq!(evidence.get(1).unwrap().submitter, seller);
+    assert_eq!(evidence.get(1).unwrap().uri, seller_uri);
+
+    // Resolution records the evidence that was on file
+    let resolution_reason = String::from_str(&env, "Tracking confirms delivery");
+    client.resolve_dispute(
+        &payment_id,
+        &arbitrator,
+        &DisputeDecision::PaySeller,
+        &resolution_reason,
+    );
+    assert_eq!(client.get_dispute_resolution(&payment_id).evidence_count, 2);
+}
+
+#[test]
+#[should_panic]
+fn test_submit_evidence_without_dispute_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment without dispute");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &100,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    // Evidence outside of a dispute - this should panic
+    let hash = BytesN::from_array(&env, &[1; 32]);
+    let uri = String::from_str(&env, "ipfs://photos");
+    client.submit_evidence(&payment_id, &buyer, &hash, &uri);
+}
+
+#[test]
+fn test_panel_dispute_majority_vote_with_arbitrator_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with three arbitrators
+    let arbitrator1 = Address::generate(&env);
+    let arbitrator2 = Address::generate(&env);
+    let arbitrator3 = Address::generate(&env);
+    client.init(&arbitrator1);
+    client.add_arbitrator(&arbitrator1, &arbitrator2);
+    client.add_arbitrator(&arbitrator1, &arbitrator3);
+
+    // Disputes of 500 or more go to a 3 member panel paid 3% of the remainder
+    client.set_panel_config(&arbitrator1, &500, &3, &300);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator1, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "High value order");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &10_000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &1_000,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    let dispute_reason = String::from_str(&env, "Item not received as described");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    // The whole registered set forms the panel, needing 2 matching votes
+    let panel = client.get_dispute_panel(&payment_id);
+    assert_eq!(panel.arbitrators.len(), 3);
+    assert_eq!(panel.quorum, 2);
+
+    // First two votes disagree, so nothing executes yet
+    let reason = String::from_str(&env, "Reviewed the evidence");
+    client.resolve_dispute(&payment_id, &arbitrator1, &DisputeDecision::PaySeller, &reason);
+    client.resolve_dispute(&payment_id, &arbitrator2, &DisputeDecision::RefundBuyer, &reason);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::Disputed);
+    assert_eq!(client.get_dispute_panel(&payment_id).votes.len(), 2);
+
+    // Third vote forms a PaySeller majority and executes the ruling
+    client.resolve_dispute(&payment_id, &arbitrator3, &DisputeDecision::PaySeller, &reason);
+
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+
+    // Each voting arbitrator receives 10, the seller receives the rest
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&arbitrator1), 10);
+    assert_eq!(token_client.balance(&arbitrator2), 10);
+    assert_eq!(token_client.balance(&arbitrator3), 10);
+    assert_eq!(token_client.balance(&seller), 970);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let resolution = client.get_dispute_resolution(&payment_id);
+    assert_eq!(resolution.arbitrator_fee, 30);
+    assert_eq!(resolution.admin, arbitrator3);
+}
+
+#[test]
+fn test_invalid_panel_split_vote_is_not_recorded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with three arbitrators on a 3 member panel
+    let arbitrator1 = Address::generate(&env);
+    let arbitrator2 = Address::generate(&env);
+    client.init(&arbitrator1);
+    client.add_arbitrator(&arbitrator1, &arbitrator2);
+    client.add_arbitrator(&arbitrator1, &Address::generate(&env));
+    client.set_panel_config(&arbitrator1, &500, &3, &0);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator1, &token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    TokenAdmin::new(&env, &token_contract_id).mint(&buyer, &10_000);
+
+    let description = String::from_str(&env, "High value order");
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &1_000,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+    let reason = String::from_str(&env, "Item not received");
+    client.dispute_payment(&payment_id, &buyer, &reason);
+
+    // Splits that leave a party with nothing are rejected without counting as a vote
+    assert_eq!(
+        client.try_resolve_dispute(&payment_id, &arbitrator1, &DisputeDecision::Split(0), &reason),
+        Err(Ok(PaymentEscrowError::InvalidSplit))
+    );
+    assert_eq!(
+        client.try_resolve_dispute(&payment_id, &arbitrator1, &DisputeDecision::Split(10_000), &reason),
+        Err(Ok(PaymentEscrowError::InvalidSplit))
+    );
+    assert_eq!(client.get_dispute_panel(&payment_id).votes.len(), 0);
+
+    // The arbitrator can still cast a valid vote afterwards
+    client.resolve_dispute(&payment_id, &arbitrator1, &DisputeDecision::Split(5_000), &reason);
+    let panel = client.get_dispute_panel(&payment_id);
+    assert_eq!(panel.votes.len(), 1);
+    assert_eq!(panel.votes.get(0).unwrap().decision, DisputeDecision::Split(5_000));
+}
+
+#[test]
+fn test_deadlocked_panel_falls_back_to_single_arbitrator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with three arbitrators and 2 member panels
+    let arbitrator1 = Address::generate(&env);
+    client.init(&arbitrator1);
+    client.add_arbitrator(&arbitrator1, &Address::generate(&env));
+    client.add_arbitrator(&arbitrator1, &Address::generate(&env));
+    client.set_panel_config(&arbitrator1, &500, &2, &300);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator1, &token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    TokenAdmin::new(&env, &token_contract_id).mint(&buyer, &10_000);
+
+    let description = String::from_str(&env, "High value order");
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &1_000,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+    let reason = String::from_str(&env, "Item not received");
+    client.dispute_payment(&payment_id, &buyer, &reason);
+
+    // Two of the three arbitrators are drawn for the panel
+    let panel = client.get_dispute_panel(&payment_id);
+    assert_eq!(panel.arbitrators.len(), 2);
+    assert_eq!(panel.quorum, 2);
+    let panelist_a = panel.arbitrators.get(0).unwrap();
+    let panelist_b = panel.arbitrators.get(1).unwrap();
+    let outsider = client
+        .get_arbitrators()
+        .iter()
+        .find(|arbitrator| !panel.arbitrators.contains(arbitrator))
+        .unwrap();
+
+    // The panel splits its votes, so quorum can never be reached
+    client.resolve_dispute(&payment_id, &panelist_a, &DisputeDecision::PaySeller, &reason);
+    client.resolve_dispute(&payment_id, &panelist_b, &DisputeDecision::RefundBuyer, &reason);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::Disputed);
+
+    // Before the voting deadline an arbitrator outside the panel cannot rule
+    assert_eq!(
+        client.try_resolve_dispute(&payment_id, &outsider, &DisputeDecision::RefundBuyer, &reason),
+        Err(Ok(PaymentEscrowError::NotPanelArbitrator))
+    );
+
+    // After the voting deadline any registered arbitrator rules alone
+    env.ledger().set_timestamp(panel.voting_deadline + 1);
+    client.resolve_dispute(&payment_id, &outsider, &DisputeDecision::RefundBuyer, &reason);
+
+    // The buyer is refunded in full and the deadlocked panel earns no fee
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::Refunded);
+    assert_eq!(token_client.balance(&buyer), 10_000);
+    assert_eq!(token_client.balance(&panelist_a), 0);
+    assert_eq!(token_client.balance(&panelist_b), 0);
+
+    let resolution = client.get_dispute_resolution(&payment_id);
+    assert_eq!(resolution.admin, outsider);
+    assert_eq!(resolution.arbitrator_fee, 0);
+}
+
+#[test]
+fn test_low_value_dispute_keeps_single_arbitrator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with two arbitrators and panel mode enabled
+    let arbitrator1 = Address::generate(&env);
+    let arbitrator2 = Address::generate(&env);
+    client.init(&arbitrator1);
+    client.add_arbitrator(&arbitrator1, &arbitrator2);
+    client.set_panel_config(&arbitrator1, &500, &2, &300);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator1, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Low value order");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &100,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    let dispute_reason = String::from_str(&env, "Item not received");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    // No panel is assigned and a single arbitrator resolves without a fee
+    assert!(client.try_get_dispute_panel(&payment_id).is_err());
+    let reason = String::from_str(&env, "Seller could not prove shipment");
+    client.resolve_dispute(&payment_id, &arbitrator2, &DisputeDecision::RefundBuyer, &reason);
+
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::Refunded);
+    assert_eq!(token_client.balance(&buyer), 1000);
+    assert_eq!(token_client.balance(&arbitrator2), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_arbitrator_outside_panel_cannot_vote() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with four arbitrators
+    let arbitrator1 = Address::generate(&env);
+    client.init(&arbitrator1);
+    client.add_arbitrator(&arbitrator1, &Address::generate(&env));
+    client.add_arbitrator(&arbitrator1, &Address::generate(&env));
+    client.add_arbitrator(&arbitrator1, &Address::generate(&env));
+    client.set_panel_config(&arbitrator1, &500, &3, &0);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator1, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "High value order");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &10_000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &1_000,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    let dispute_reason = String::from_str(&env, "Item not received");
+    client.dispute_payment(&payment_id, &buyer, &dispute_reason);
+
+    // Three of the four arbitrators are drawn for the panel
+    let panel = client.get_dispute_panel(&payment_id);
+    assert_eq!(panel.arbitrators.len(), 3);
+    let outsider = client
+        .get_arbitrators()
+        .iter()
+        .find(|arbitrator| !panel.arbitrators.contains(arbitrator))
+        .unwrap();
+
+    // An arbitrator outside the panel tries to vote - this should panic
+    let reason = String::from_str(&env, "Overriding the panel");
+    client.resolve_dispute(&payment_id, &outsider, &DisputeDecision::PaySeller, &reason);
+}
+
+#[test]
+fn test_release_after_inspection_pays_seller() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator and a 2-day inspection window
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+    client.set_inspection_period(&arbitrator, &(2 * 24 * 60 * 60));
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for inspection release");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &100,
+        &token_contract_id,
+        &30,
+        &0,
+        &description,
+    );
+
+    // Seller ships and the inspection window opens
+    client.seller_confirm_delivery(&payment_id, &seller);
+    let delivered_at = env.ledger().timestamp();
+    let details = client.get_delivery_details(&payment_id);
+    assert_eq!(details.inspection_deadline, delivered_at + 2 * 24 * 60 * 60);
+
+    // Release is refused while the buyer can still inspect
+    assert!(client.try_release_after_inspection(&payment_id).is_err());
+
+    // Buyer stays silent past the inspection window
+    env.ledger().set_timestamp(delivered_at + 3 * 24 * 60 * 60);
+    client.release_after_inspection(&payment_id);
+
+    // Seller is paid and the payment is completed
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&seller), 100);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_delivered_payment_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test claim on delivered payment");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    // Create a payment with 1 day expiry and mark it delivered
+    let payment_id = client.create_payment(
+        &buyer,
+        &seller,
+        &100,
+        &token_contract_id,
+        &1,
+        &0,
+        &description,
+    );
+    client.seller_confirm_delivery(&payment_id, &seller);
+
+    // Advance time past expiry
+    let current_time = env.ledger().timestamp();
+    env.ledger().set_timestamp(current_time + 5 * 24 * 60 * 60);
+
+    // Buyer tries to reclaim a delivered, undisputed order - this should panic
+    client.claim_payment(&payment_id, &buyer);
+}
+
+#[test]
+fn test_payment_indexes_and_pagination() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller1 = Address::generate(&env);
+    let seller2 = Address::generate(&env);
+    let description = String::from_str(&env, "Indexed payment");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    // Buyer opens three orders across two sellers
+    let payment1 = client.create_payment(&buyer, &seller1, &100, &token_contract_id, &30, &0, &description);
+    let payment2 = client.create_payment(&buyer, &seller2, &100, &token_contract_id, &30, &0, &description);
+    let payment3 = client.create_payment(&buyer, &seller1, &100, &token_contract_id, &30, &0, &description);
+
+    // Buyer index is paginated in creation order
+    let first_page = client.get_payments_by_buyer(&buyer, &0, &2);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page.get(0).unwrap().id, payment1);
+    assert_eq!(first_page.get(1).unwrap().id, payment2);
+    let second_page = client.get_payments_by_buyer(&buyer, &2, &2);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page.get(0).unwrap().id, payment3);
+
+    // Seller index only holds that seller's payments
+    let seller1_payments = client.get_payments_by_seller(&seller1, &0, &10);
+    assert_eq!(seller1_payments.len(), 2);
+    assert_eq!(seller1_payments.get(1).unwrap().id, payment3);
+
+    // Status index follows payment transitions
+    assert_eq!(client.get_payments_by_status(&PaymentStatus::Pending, &0, &10).len(), 3);
+    client.seller_confirm_delivery(&payment1, &seller1);
+    client.buyer_confirm_delivery(&payment1, &buyer);
+    let dispute_reason = String::from_str(&env, "Wrong item");
+    client.dispute_payment(&payment2, &buyer, &dispute_reason);
+
+    let pending = client.get_payments_by_status(&PaymentStatus::Pending, &0, &10);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap().id, payment3);
+    assert_eq!(client.get_payments_by_status(&PaymentStatus::Delivered, &0, &10).len(), 0);
+    let completed = client.get_payments_by_status(&PaymentStatus::Completed, &0, &10);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed.get(0).unwrap().id, payment1);
+    let disputed = client.get_payments_by_status(&PaymentStatus::Disputed, &0, &10);
+    assert_eq!(disputed.len(), 1);
+    assert_eq!(disputed.get(0).unwrap().status, PaymentStatus::Disputed);
+}
+
+#[test]
+fn test_status_pagination_is_stable_across_transitions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    TokenAdmin::new(&env, &token_contract_id).mint(&buyer, &1000);
+    let description = String::from_str(&env, "Paged payment");
+
+    let mut ids = std::vec::Vec::new();
+    for _ in 0..4 {
+        ids.push(client.create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description));
+    }
+    let pending = PaymentIndex::Status(PaymentStatus::Pending);
+    assert_eq!(client.get_index_len(&pending), 4);
+
+    // A client reads the first page of pending payments
+    let first_page = client.get_payments_by_status(&PaymentStatus::Pending, &0, &2);
+    assert_eq!(first_page.get(0).unwrap().id, ids[0]);
+    assert_eq!(first_page.get(1).unwrap().id, ids[1]);
+
+    // The first payment leaves Pending between page reads
+    let reason = String::from_str(&env, "Wrong item");
+    client.dispute_payment(&ids[0], &buyer, &reason);
+
+    // The next page still starts where the client left off
+    let second_page = client.get_payments_by_status(&PaymentStatus::Pending, &2, &2);
+    assert_eq!(second_page.len(), 2);
+    assert_eq!(second_page.get(0).unwrap().id, ids[2]);
+    assert_eq!(second_page.get(1).unwrap().id, ids[3]);
+
+    // The vacated slot is skipped rather than filled
+    let first_page = client.get_payments_by_status(&PaymentStatus::Pending, &0, &2);
+    assert_eq!(first_page.len(), 1);
+    assert_eq!(first_page.get(0).unwrap().id, ids[1]);
+    assert_eq!(client.get_index_len(&pending), 4);
+
+    // The payment takes a new slot at the end of the Disputed index
+    let disputed = PaymentIndex::Status(PaymentStatus::Disputed);
+    assert_eq!(client.get_index_len(&disputed), 1);
+    assert_eq!(client.get_payments_by_status(&PaymentStatus::Disputed, &0, &10).get(0).unwrap().id, ids[0]);
+
+    // Each index slot is its own storage entry
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            env.storage().persistent().get::<_, u128>(&DataKey::IndexEntry(PaymentIndex::Buyer(buyer.clone()), 3)),
+            Some(ids[3])
+        );
+        assert!(!env.storage().persistent().has(&DataKey::IndexEntry(pending.clone(), 0)));
+    });
+}
+
+#[test]
+fn test_multi_seller_order_settles_per_line_item() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller1 = Address::generate(&env);
+    let seller2 = Address::generate(&env);
+    let description = String::from_str(&env, "Cart checkout");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    // Buyer checks out a cart with items from two sellers
+    let items = vec![
+        &env,
+        OrderItem {
+            seller: seller1.clone(),
+            amount: 300,
+            description: String::from_str(&env, "Coffee beans"),
+        },
+        OrderItem {
+            seller: seller2.clone(),
+            amount: 200,
+            description: String::from_str(&env, "Grinder"),
+        },
+    ];
+    let order_id = client.create_order(&buyer, &token_contract_id, &items, &30, &0, &description);
+
+    // The whole cart is funded by one transfer
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&buyer), 500);
+    assert_eq!(token_client.balance(&contract_id), 500);
+
+    // Every line item is its own payment sharing the order deadlines
+    let order = client.get_order(&order_id);
+    assert_eq!(order.total_amount, 500);
+    let lines = client.get_order_payments(&order_id);
+    assert_eq!(lines.len(), 2);
+    let line1 = lines.get(0).unwrap();
+    let line2 = lines.get(1).unwrap();
+    assert_eq!(line1.seller, seller1);
+    assert_eq!(line1.order_id, Some(order_id));
+    assert_eq!(line1.expiry, order.expiry);
+    assert_eq!(line2.dispute_deadline, order.dispute_deadline);
+
+    // Seller 1 ships and the buyer confirms their line
+    client.seller_confirm_delivery(&line1.id, &seller1);
+    client.buyer_confirm_delivery(&line1.id, &buyer);
+
+    // Seller 2's line is disputed and refunded
+    let dispute_reason = String::from_str(&env, "Grinder never shipped");
+    client.dispute_payment(&line2.id, &buyer, &dispute_reason);
+    let resolution_reason = String::from_str(&env, "No proof of shipment");
+    client.resolve_dispute(&line2.id, &arbitrator, &DisputeDecision::RefundBuyer, &resolution_reason);
+
+    assert_eq!(client.get_a_payment(&line1.id).status, PaymentStatus::Completed);
+    assert_eq!(client.get_a_payment(&line2.id).status, PaymentStatus::Refunded);
+    assert_eq!(token_client.balance(&seller1), 300);
+    assert_eq!(token_client.balance(&seller2), 0);
+    assert_eq!(token_client.balance(&buyer), 700);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_order_seller_cannot_confirm_other_line() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller1 = Address::generate(&env);
+    let seller2 = Address::generate(&env);
+    let description = String::from_str(&env, "Cart checkout");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let items = vec![
+        &env,
+        OrderItem {
+            seller: seller1.clone(),
+            amount: 300,
+            description: String::from_str(&env, "Coffee beans"),
+        },
+        OrderItem {
+            seller: seller2.clone(),
+            amount: 200,
+            description: String::from_str(&env, "Grinder"),
+        },
+    ];
+    let order_id = client.create_order(&buyer, &token_contract_id, &items, &30, &0, &description);
+    let line2 = client.get_order(&order_id).payment_ids.get(1).unwrap();
+
+    // Seller 1 tries to confirm seller 2's line - this should panic
+    client.seller_confirm_delivery(&line2, &seller1);
+}
+
+#[test]
+fn test_custom_policy_sets_payment_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    // Perishables: short default expiry, 2-day dispute window, 1-day inspection
+    let profile = PolicyProfile {
+        default_expiry_days: 5,
+        max_expiry_days: 10,
+        dispute_window: DisputeWindow::AfterCreation(2),
+        inspection_period: 24 * 60 * 60,
+    };
+    client.set_policy(&arbitrator, &7, &profile);
+    assert_eq!(client.get_policy(&7), profile);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Fresh produce");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    // Expiry 0 selects the policy default
+    let created_at = env.ledger().timestamp();
+    let payment_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &0, &7, &description);
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.policy_id, 7);
+    assert_eq!(payment.expiry, created_at + 5 * 24 * 60 * 60);
+    assert_eq!(payment.dispute_deadline, created_at + 2 * 24 * 60 * 60);
+
+    // Delivery opens the policy's inspection window
+    client.seller_confirm_delivery(&payment_id, &seller);
+    let details = client.get_delivery_details(&payment_id);
+    assert_eq!(details.policy_id, 7);
+    assert_eq!(details.inspection_period, 24 * 60 * 60);
+    assert_eq!(details.inspection_deadline, created_at + 24 * 60 * 60);
+
+    // Expiries beyond the policy maximum are rejected
+    assert_eq!(
+        client.try_create_payment(&buyer, &seller, &100, &token_contract_id, &11, &7, &description),
+        Err(Ok(PaymentEscrowError::InvalidPeriod))
+    );
+
+    // Amendments cannot extend a payment beyond the policy maximum either
+    let pending_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &0, &7, &description);
+    assert_eq!(
+        client.try_propose_amendment(&pending_id, &buyer, &100, &(created_at + 11 * 24 * 60 * 60)),
+        Err(Ok(PaymentEscrowError::InvalidPeriod))
+    );
+
+    // Unknown policies are rejected
+    assert_eq!(
+        client.try_create_payment(&buyer, &seller, &100, &token_contract_id, &5, &8, &description),
+        Err(Ok(PaymentEscrowError::PolicyNotFound))
+    );
+}
+
+#[test]
+fn test_set_policy_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let valid = PolicyProfile {
+        default_expiry_days: 30,
+        max_expiry_days: 90,
+        dispute_window: DisputeWindow::AfterCreation(14),
+        inspection_period: 3 * 24 * 60 * 60,
+    };
+
+    // The built-in default policy is reserved
+    assert_eq!(
+        client.try_set_policy(&arbitrator, &0, &valid),
+        Err(Ok(PaymentEscrowError::InvalidPolicy))
+    );
+
+    // Default expiry above the maximum is rejected
+    let mut invalid = valid.clone();
+    invalid.default_expiry_days = 120;
+    assert_eq!(
+        client.try_set_policy(&arbitrator, &1, &invalid),
+        Err(Ok(PaymentEscrowError::InvalidPolicy))
+    );
+
+    // A zero dispute window is rejected
+    let mut invalid = valid.clone();
+    invalid.dispute_window = DisputeWindow::AfterCreation(0);
+    assert_eq!(
+        client.try_set_policy(&arbitrator, &1, &invalid),
+        Err(Ok(PaymentEscrowError::InvalidPolicy))
+    );
+
+    // Non-arbitrators cannot manage policies
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_set_policy(&outsider, &1, &valid),
+        Err(Ok(PaymentEscrowError::NotArbitrator))
+    );
+
+    // The default policy is always available
+    let default_policy = client.get_policy(&0);
+    assert_eq!(default_policy.default_expiry_days, 30);
+    assert_eq!(default_policy.max_expiry_days, u32::MAX);
+    assert_eq!(default_policy.dispute_window, DisputeWindow::BeforeExpiry(7));
+}
+
+#[test]
+fn test_default_policy_keeps_original_dispute_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    TokenAdmin::new(&env, &token_contract_id).mint(&buyer, &1000);
+    let description = String::from_str(&env, "Default policy payment");
+
+    env.ledger().set_timestamp(1_000);
+    let day = 24 * 60 * 60;
+
+    // Short payments stay disputable for their full period
+    let short = client.create_payment(&buyer, &seller, &100, &token_contract_id, &5, &0, &description);
+    let payment = client.get_a_payment(&short);
+    assert_eq!(payment.expiry, 1_000 + 5 * day);
+    assert_eq!(payment.dispute_deadline, 1_000 + 5 * day);
+
+    // Longer payments close disputes 7 days before expiry
+    let medium = client.create_payment(&buyer, &seller, &100, &token_contract_id, &10, &0, &description);
+    let payment = client.get_a_payment(&medium);
+    assert_eq!(payment.expiry, 1_000 + 10 * day);
+    assert_eq!(payment.dispute_deadline, 1_000 + 3 * day);
+
+    let long = client.create_payment(&buyer, &seller, &100, &token_contract_id, &90, &0, &description);
+    let payment = client.get_a_payment(&long);
+    assert_eq!(payment.expiry, 1_000 + 90 * day);
+    assert_eq!(payment.dispute_deadline, 1_000 + 83 * day);
+
+    // Expiries beyond a year are still accepted
+    let yearly = client.create_payment(&buyer, &seller, &100, &token_contract_id, &400, &0, &description);
+    assert_eq!(client.get_a_payment(&yearly).dispute_deadline, 1_000 + 393 * day);
+}
+
+#[test]
+fn test_cancellation_request_refunds_buyer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for cancellation");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description);
+
+    // Accepting without a request fails
+    assert_eq!(
+        client.try_accept_cancellation(&payment_id, &seller),
+        Err(Ok(PaymentEscrowError::RequestNotFound))
+    );
+
+    // Buyer requests, seller declines, buyer requests again
+    let reason = String::from_str(&env, "Ordered the wrong size");
+    client.request_cancellation(&payment_id, &buyer, &reason);
+    assert_eq!(
+        client.try_request_cancellation(&payment_id, &buyer, &reason),
+        Err(Ok(PaymentEscrowError::RequestAlreadyOpen))
+    );
+    client.reject_cancellation(&payment_id, &seller);
+    assert!(client.get_cancellation_request(&payment_id).is_none());
+    client.request_cancellation(&payment_id, &buyer, &reason);
+    assert_eq!(client.get_cancellation_request(&payment_id).unwrap().reason, reason);
+
+    // Seller accepts and the buyer is refunded immediately
+    client.accept_cancellation(&payment_id, &seller);
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.status, PaymentStatus::Cancelled);
+    assert_eq!(payment.remaining_amount, 0);
+    assert_eq!(token_client.balance(&buyer), 1000);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    // A cancelled payment cannot be disputed
+    let dispute_reason = String::from_str(&env, "Too late");
+    assert_eq!(
+        client.try_dispute_payment(&payment_id, &buyer, &dispute_reason),
+        Err(Ok(PaymentEscrowError::NotValid))
+    );
+}
+
+#[test]
+fn test_cancellation_not_allowed_after_shipping() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for late cancellation");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description);
+
+    // Buyer requests a cancellation but the seller ships first
+    let reason = String::from_str(&env, "Found it cheaper");
+    client.request_cancellation(&payment_id, &buyer, &reason);
+    client.seller_confirm_delivery(&payment_id, &seller);
+
+    // The request can no longer be accepted
+    assert_eq!(
+        client.try_accept_cancellation(&payment_id, &seller),
+        Err(Ok(PaymentEscrowError::NotValid))
+    );
+}
+
+#[test]
+fn test_amendment_top_up_and_partial_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for amendment");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &10, &0, &description);
+    let payment = client.get_a_payment(&payment_id);
+
+    // Buyer adds an item: the top-up is escrowed with the proposal
+    let new_expiry = payment.expiry + 5 * 24 * 60 * 60;
+    client.propose_amendment(&payment_id, &buyer, &150, &new_expiry);
+    assert_eq!(client.get_amendment(&payment_id).unwrap().escrowed_top_up, 50);
+    assert_eq!(token_client.balance(&contract_id), 150);
+
+    // The proposer cannot approve their own amendment
+    assert_eq!(
+        client.try_accept_amendment(&payment_id, &buyer),
+        Err(Ok(PaymentEscrowError::UnauthorizedAccess))
+    );
+
+    // Seller approves and the payment terms are updated
+    client.accept_amendment(&payment_id, &seller);
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.amount, 150);
+    assert_eq!(payment.remaining_amount, 150);
+    assert_eq!(payment.expiry, new_expiry);
+    assert_eq!(payment.tranches.get(0).unwrap().amount, 150);
+    assert!(client.get_amendment(&payment_id).is_none());
+
+    // Seller offers a discount and the buyer approves: the difference is refunded
+    client.propose_amendment(&payment_id, &seller, &120, &new_expiry);
+    client.accept_amendment(&payment_id, &buyer);
+    assert_eq!(client.get_a_payment(&payment_id).amount, 120);
+    assert_eq!(token_client.balance(&buyer), 880);
+    assert_eq!(token_client.balance(&contract_id), 120);
+
+    // Completing delivery pays the amended amount
+    client.seller_confirm_delivery(&payment_id, &seller);
+    client.buyer_confirm_delivery(&payment_id, &buyer);
+    assert_eq!(token_client.balance(&seller), 120);
+}
+
+#[test]
+fn test_rejected_amendment_returns_top_up() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Accept the token for escrow
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for rejected amendment");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description);
+    let payment = client.get_a_payment(&payment_id);
+
+    // Expiry that is not in the future is rejected
+    assert_eq!(
+        client.try_propose_amendment(&payment_id, &buyer, &100, &payment.created_at),
+        Err(Ok(PaymentEscrowError::InvalidPeriod))
+    );
+
+    // Buyer proposes a top-up and the seller rejects it
+    client.propose_amendment(&payment_id, &buyer, &300, &payment.expiry);
+    assert_eq!(token_client.balance(&buyer), 700);
+    client.reject_amendment(&payment_id, &seller);
+
+    // The top-up is returned and the payment is unchanged
+    assert_eq!(token_client.balance(&buyer), 900);
+    assert_eq!(client.get_a_payment(&payment_id).amount, 100);
+    assert_eq!(
+        client.try_accept_amendment(&payment_id, &seller),
+        Err(Ok(PaymentEscrowError::RequestNotFound))
+    );
+}
+
+#[test]
+fn test_token_registry_enforced_on_create() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Test payment for token registry");
+
+    // Mint tokens to buyer
+    token.mint(&buyer, &10_000);
+
+    // Unregistered tokens cannot be escrowed
+    assert_eq!(
+        client.try_create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description),
+        Err(Ok(PaymentEscrowError::TokenNotAccepted))
+    );
+
+    // Accept the token with limits
+    client.add_token(&arbitrator, &token_contract_id, &10, &500);
+    assert_eq!(client.get_tokens(), vec![&env, token_contract_id.clone()]);
+    assert_eq!(
+        client.get_token_config(&token_contract_id),
+        Some(TokenConfig { min_amount: 10, max_amount: 500, enabled: true })
+    );
+
+    // Amounts outside the limits are rejected
+    assert_eq!(
+        client.try_create_payment(&buyer, &seller, &9, &token_contract_id, &30, &0, &description),
+        Err(Ok(PaymentEscrowError::AmountOutOfRange))
+    );
+    let items = vec![
+        &env,
+        OrderItem {
+            seller: seller.clone(),
+            amount: 501,
+            description: String::from_str(&env, "Oversized line"),
+        },
+    ];
+    assert_eq!(
+        client.try_create_order(&buyer, &token_contract_id, &items, &30, &0, &description),
+        Err(Ok(PaymentEscrowError::AmountOutOfRange))
+    );
+
+    // Amendments must also stay within the limits
+    let payment_id = client.create_payment(&buyer, &seller, &500, &token_contract_id, &30, &0, &description);
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(
+        client.try_propose_amendment(&payment_id, &buyer, &600, &payment.expiry),
+        Err(Ok(PaymentEscrowError::AmountOutOfRange))
+    );
+
+    // Disabled tokens block new payments but not existing escrows
+    client.set_token_enabled(&arbitrator, &token_contract_id, &false);
+    assert_eq!(
+        client.try_create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description),
+        Err(Ok(PaymentEscrowError::TokenNotAccepted))
+    );
+    client.seller_confirm_delivery(&payment_id, &seller);
+    client.buyer_confirm_delivery(&payment_id, &buyer);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::Completed);
+}
+
+#[test]
+fn test_token_registry_requires_arbitrator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    // Only arbitrators manage the registry
+    assert_eq!(
+        client.try_add_token(&outsider, &token, &1, &100),
+        Err(Ok(PaymentEscrowError::NotArbitrator))
+    );
+
+    // Limits must be positive and ordered
+    assert_eq!(
+        client.try_add_token(&arbitrator, &token, &100, &10),
+        Err(Ok(PaymentEscrowError::InvalidAmount))
+    );
+
+    // Unregistered tokens cannot be toggled
+    assert_eq!(
+        client.try_set_token_enabled(&arbitrator, &token, &true),
+        Err(Ok(PaymentEscrowError::TokenNotAccepted))
+    );
+    assert!(client.get_tokens().is_empty());
+}
+
+/// Writes payments in the schema version 1 layout, as the original contract code left them
+fn seed_legacy_payments(env: &Env, contract_id: &Address, payments: &[LegacyPayment]) {
+    env.as_contract(contract_id, || {
+        for payment in payments {
+            env.storage().persistent().set(&payment.id, payment);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::PaymentCounter, &(payments.len() as u128));
+        env.storage().persistent().remove(&DataKey::SchemaVersion);
+    });
+}
+
+/// Builds a schema version 1 payment record
+fn legacy_payment(env: &Env, id: u128, buyer: &Address, seller: &Address, token: &Address, status: PaymentStatus) -> LegacyPayment {
+    let created_at = env.ledger().timestamp();
+    LegacyPayment {
+        id,
+        buyer: buyer.clone(),
+        seller: seller.clone(),
+        amount: 100,
+        token: token.clone(),
+        status,
+        created_at,
+        expiry: created_at + 30 * 24 * 60 * 60,
+        dispute_deadline: created_at + 23 * 24 * 60 * 60,
+        description: String::from_str(env, "Legacy payment"),
+    }
+}
+
+#[test]
+fn test_migrate_legacy_payments_in_batches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    // New deployments start on the current layout
+    assert!(client.get_migration_status().complete);
+    assert_eq!(client.get_migration_status().schema_version, PAYMENT_SCHEMA_VERSION);
+
+    // Three payments left behind by the original contract code
+    seed_legacy_payments(&env, &contract_id, &[
+        legacy_payment(&env, 1, &buyer, &seller, &token_contract_id, PaymentStatus::Pending),
+        legacy_payment(&env, 2, &buyer, &seller, &token_contract_id, PaymentStatus::Completed),
+        legacy_payment(&env, 3, &buyer, &seller, &token_contract_id, PaymentStatus::Delivered),
+    ]);
+
+    // Legacy records stay readable before the migration runs
+    let status = client.get_migration_status();
+    assert_eq!(status.schema_version, 1);
+    assert!(!status.complete);
+    let pending = client.get_a_payment(&1);
+    assert_eq!(pending.amount, 100);
+    assert_eq!(pending.remaining_amount, 100);
+    assert_eq!(pending.released_amount, 0);
+    assert_eq!(pending.fee_bps, 0);
+    assert_eq!(pending.tranches.len(), 1);
+    assert_eq!(pending.tranches.get(0).unwrap().amount, 100);
+    assert!(!pending.tranches.get(0).unwrap().released);
+    let completed = client.get_a_payment(&2);
+    assert_eq!(completed.remaining_amount, 0);
+    assert_eq!(completed.released_amount, 100);
+    assert!(completed.tranches.get(0).unwrap().released);
+    assert_eq!(client.get_a_payment(&3).inspection_deadline, client.get_a_payment(&3).expiry);
+
+    // Legacy records predate the indexes
+    assert_eq!(client.get_payments_by_buyer(&buyer, &0, &10).len(), 0);
+
+    // The first batch moves payments 1 and 2 only
+    let status = client.migrate_payments(&arbitrator, &2);
+    assert_eq!(status.migrated_up_to, 2);
+    assert_eq!(status.payment_count, 3);
+    assert!(!status.complete);
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().persistent().has(&DataKey::Payment(2)));
+        assert!(!env.storage().persistent().has(&2u128));
+        assert!(env.storage().persistent().has(&3u128));
+    });
+
+    // The second batch finishes and records the new schema version
+    let status = client.migrate_payments(&arbitrator, &2);
+    assert!(status.complete);
+    assert_eq!(status.schema_version, PAYMENT_SCHEMA_VERSION);
+    assert_eq!(status.migrated_up_to, 3);
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().persistent().has(&DataKey::Payment(3)));
+        assert!(!env.storage().persistent().has(&3u128));
+    });
+    assert_eq!(client.get_a_payment(&3).buyer, buyer);
+
+    // Migrated payments are backfilled into the buyer, seller and status indexes
+    assert_eq!(client.get_payments_by_buyer(&buyer, &0, &10).len(), 3);
+    assert_eq!(client.get_payments_by_seller(&seller, &0, &10).len(), 3);
+    let delivered = client.get_payments_by_status(&PaymentStatus::Delivered, &0, &10);
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered.get(0).unwrap().id, 3);
+    assert_eq!(client.get_payments_by_status(&PaymentStatus::Completed, &0, &10).len(), 1);
+}
+
+#[test]
+fn test_legacy_payment_rewritten_on_update() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    // A pending legacy payment whose funds the contract already holds
+    let payment_id = 1;
+    seed_legacy_payments(&env, &contract_id, &[
+        legacy_payment(&env, payment_id, &buyer, &seller, &token_contract_id, PaymentStatus::Pending),
+    ]);
+    token.mint(&contract_id, &100);
+
+    // State transitions on a legacy record write it in the current layout
+    client.seller_confirm_delivery(&payment_id, &seller);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::Delivered);
+    assert_eq!(client.get_payments_by_status(&PaymentStatus::Delivered, &0, &10).len(), 1);
+    assert_eq!(client.get_payments_by_status(&PaymentStatus::Pending, &0, &10).len(), 0);
+    assert_eq!(client.get_payments_by_buyer(&buyer, &0, &10).len(), 1);
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().persistent().has(&DataKey::Payment(payment_id)));
+        assert!(!env.storage().persistent().has(&payment_id));
+    });
+
+    // The buyer settles it in full, without a platform fee
+    client.buyer_confirm_delivery(&payment_id, &buyer);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+    assert_eq!(token_client.balance(&seller), 100);
+    assert_eq!(client.get_a_payment(&payment_id).status, PaymentStatus::Completed);
+
+    // Only arbitrators migrate, in bounded batches
+    assert_eq!(
+        client.try_migrate_payments(&outsider, &10),
+        Err(Ok(PaymentEscrowError::NotArbitrator))
+    );
+    assert_eq!(
+        client.try_migrate_payments(&arbitrator, &0),
+        Err(Ok(PaymentEscrowError::InvalidBatchSize))
+    );
+    assert!(client.migrate_payments(&arbitrator, &10).complete);
+}
+
+#[test]
+fn test_amend_migrated_legacy_payment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+    let token = TokenAdmin::new(&env, &token_contract_id);
+    let token_client = TokenClient::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    token.mint(&buyer, &1000);
+
+    // A pending legacy payment whose funds the contract already holds
+    let payment_id = 1;
+    seed_legacy_payments(&env, &contract_id, &[
+        legacy_payment(&env, payment_id, &buyer, &seller, &token_contract_id, PaymentStatus::Pending),
+    ]);
+    token.mint(&contract_id, &100);
+    assert!(client.migrate_payments(&arbitrator, &10).complete);
+
+    // The migrated payment can be amended like any other
+    let payment = client.get_a_payment(&payment_id);
+    let new_expiry = payment.expiry - 24 * 60 * 60;
+    client.propose_amendment(&payment_id, &buyer, &150, &new_expiry);
+    client.accept_amendment(&payment_id, &seller);
+    let payment = client.get_a_payment(&payment_id);
+    assert_eq!(payment.amount, 150);
+    assert_eq!(payment.remaining_amount, 150);
+    assert_eq!(payment.tranches.len(), 1);
+    assert_eq!(payment.tranches.get(0).unwrap().amount, 150);
+
+    // Completing delivery pays the amended amount
+    client.seller_confirm_delivery(&payment_id, &seller);
+    client.buyer_confirm_delivery(&payment_id, &buyer);
+    assert_eq!(token_client.balance(&seller), 150);
+}
+
+#[test]
+fn test_payment_ttl_covers_deadlines_and_bump() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Long escrow");
+    token.mint(&buyer, &1000);
+
+    let payment_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description);
+
+    // The record outlives its expiry plus the retention period
+    let expiry = client.get_a_payment(&payment_id).expiry;
+    let expected = ((expiry - env.ledger().timestamp() + RETENTION_PERIOD) / LEDGER_CLOSE_SECONDS) as u32;
+    let payment_ttl = || env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&DataKey::Payment(payment_id)));
+    assert!(payment_ttl() >= expected);
+
+    // Dispute records get the same lifetime as the payment
+    client.dispute_payment(&payment_id, &buyer, &String::from_str(&env, "Damaged"));
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().persistent().get_ttl(&(DataKey::DisputedPayments, payment_id)) >= expected);
+    });
+
+    // Keepers can top the lifetime back up as ledgers pass
+    env.ledger().with_mut(|li| li.sequence_number += 1_000);
+    assert!(payment_ttl() < expected);
+    client.bump_payment(&payment_id);
+    assert!(payment_ttl() >= expected - 1_000 / LEDGER_CLOSE_SECONDS as u32);
+    assert_eq!(
+        client.try_bump_payment(&99),
+        Err(Ok(PaymentEscrowError::NotFound))
+    );
+}
+
+#[test]
+fn test_payment_ttl_covers_requests_order_indexes_and_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+    client.set_fee_config(&arbitrator, &100, &arbitrator);
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    token.mint(&buyer, &1000);
+
+    // A single line order escrows one payment
+    let items = vec![
+        &env,
+        OrderItem {
+            seller: seller.clone(),
+            amount: 100,
+            description: String::from_str(&env, "Long escrow"),
+        },
+    ];
+    let description = String::from_str(&env, "Long order");
+    let order_id = client.create_order(&buyer, &token_contract_id, &items, &30, &0, &description);
+    let payment_id = client.get_order(&order_id).payment_ids.get(0).unwrap();
+
+    // The buyer escrows a top-up with an amendment and asks to cancel
+    let expiry = client.get_a_payment(&payment_id).expiry;
+    client.propose_amendment(&payment_id, &buyer, &150, &expiry);
+    client.request_cancellation(&payment_id, &buyer, &String::from_str(&env, "Changed my mind"));
+
+    // Everything the payment depends on outlives its expiry plus the retention period
+    let expected = ((expiry - env.ledger().timestamp() + RETENTION_PERIOD) / LEDGER_CLOSE_SECONDS) as u32;
+    let pending = PaymentIndex::Status(PaymentStatus::Pending);
+    env.as_contract(&contract_id, || {
+        let ttl = |key: DataKey| env.storage().persistent().get_ttl(&key);
+        assert!(ttl(DataKey::Amendment(payment_id)) >= expected);
+        assert!(ttl(DataKey::CancellationRequest(payment_id)) >= expected);
+        assert!(ttl(DataKey::Order(order_id)) >= expected);
+        assert!(ttl(DataKey::IndexSlots(payment_id)) >= expected);
+        assert!(ttl(DataKey::IndexEntry(PaymentIndex::Buyer(buyer.clone()), 0)) >= expected);
+        assert!(ttl(DataKey::IndexEntry(PaymentIndex::Seller(seller.clone()), 0)) >= expected);
+        assert!(ttl(DataKey::IndexEntry(pending.clone(), 0)) >= expected);
+        assert!(ttl(DataKey::IndexLen(pending.clone())) >= expected);
+        assert!(ttl(DataKey::Arbitrator) >= expected);
+        assert!(ttl(DataKey::FeeConfig) >= expected);
+        assert!(ttl(DataKey::TokenConfig(token_contract_id.clone())) >= expected);
+    });
+}
+
+#[test]
+fn test_order_extends_configuration_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+    client.set_fee_config(&arbitrator, &100, &arbitrator);
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let description = String::from_str(&env, "Order");
+    token.mint(&buyer, &100_000);
+
+    let order_items = |count: u32| {
+        let mut items = vec![&env];
+        for _ in 0..count {
+            items.push_back(OrderItem {
+                seller: Address::generate(&env),
+                amount: 100,
+                description: String::from_str(&env, "Line item"),
+            });
+        }
+        items
+    };
+
+    client.create_order(&buyer, &token_contract_id, &order_items(1), &30, &0, &description);
+    let single_reads = env.cost_estimate().resources().read_entries;
+
+    let order_id = client.create_order(&buyer, &token_contract_id, &order_items(MAX_ORDER_ITEMS), &30, &0, &description);
+    let full_reads = env.cost_estimate().resources().read_entries;
+
+    // Configuration and order records are read once, not once per line item
+    assert!(full_reads - single_reads <= MAX_ORDER_ITEMS);
+
+    // Every line item still outlives its expiry plus the retention period
+    let payment_ids = client.get_order(&order_id).payment_ids;
+    let last_id = payment_ids.get(payment_ids.len() - 1).unwrap();
+    let last = client.get_a_payment(&last_id);
+    let expected = ((last.expiry - env.ledger().timestamp() + RETENTION_PERIOD) / LEDGER_CLOSE_SECONDS) as u32;
+    env.as_contract(&contract_id, || {
+        let ttl = |key: DataKey| env.storage().persistent().get_ttl(&key);
+        assert!(ttl(DataKey::Payment(last_id)) >= expected);
+        assert!(ttl(DataKey::IndexSlots(last_id)) >= expected);
+        assert!(ttl(DataKey::IndexEntry(PaymentIndex::Seller(last.seller.clone()), 0)) >= expected);
+        assert!(ttl(DataKey::Order(order_id)) >= expected);
+        assert!(ttl(DataKey::TokenConfig(token_contract_id.clone())) >= expected);
+    });
+}
+
+#[test]
+fn test_archive_closed_payment_after_retention() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PaymentEscrowContract, ());
+    let client = PaymentEscrowContractClient::new(&env, &contract_id);
+
+    // Initialize the contract with an arbitrator
+    let arbitrator = Address::generate(&env);
+    client.init(&arbitrator);
+
+    let token_admin = Address::generate(&env);
+    let stellar_asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_contract_id = stellar_asset.address();
+    client.add_token(&arbitrator, &token_contract_id, &1, &1_000_000);
+    let token = TokenAdmin::new(&env, &token_contract_id);
+
+    // Setup test accounts
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let description = String::from_str(&env, "Archived escrow");
+    token.mint(&buyer, &1000);
+
+    let open_id = client.create_payment(&buyer, &seller, &100, &token_contract_id, &30, &0, &description);
+    let payment_id = client.create_payment(&buyer, &seller, &200, &token_contract_id, &30, &0, &description);
+    client.seller_confirm_delivery(&payment_id, &seller);
+    client.buyer_confirm_delivery(&payment_id, &buyer);
+    let closed_at = env.ledger().timestamp();
+
+    // Open payments and recently closed payments keep their full record
+    assert_eq!(
+        client.try_archive_payment(&open_id),
+        Err(Ok(PaymentEscrowError::PaymentNotTerminal))
+    );
+    assert_eq!(
+        client.try_archive_payment(&payment_id),
+        Err(Ok(PaymentEscrowError::RetentionActive))
+    );
+
+    // After the retention period anyone can archive the closed payment
+    env.ledger().with_mut(|li| li.timestamp = closed_at + RETENTION_PERIOD);
+    let archived = client.archive_payment(&payment_id);
+    assert_eq!(archived.amount, 200);
+    assert_eq!(archived.status, PaymentStatus::Completed);
+    assert_eq!(archived.closed_at, closed_at);
+    assert_eq!(client.get_archived_payment(&payment_id), archived);
+
+    // The full record is gone and no longer listed
+    assert_eq!(
+        client.try_get_a_payment(&payment_id).err(),
+        Some(Ok(PaymentEscrowError::NotFound))
+    );
+    assert_eq!(client.get_payments_by_status(&PaymentStatus::Completed, &0, &10).len(), 0);
+    assert_eq!(client.get_payments_by_buyer(&buyer, &0, &10).len(), 1);
+
+    // Archived summaries can still be kept alive
+    client.bump_payment(&payment_id);
+    assert_eq!(
+        client.try_archive_payment(&payment_id),
+        Err(Ok(PaymentEscrowError::NotFound))
+    );
+}